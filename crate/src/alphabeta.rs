@@ -0,0 +1,422 @@
+//! Iterative-deepening alpha-beta search, the alternative to the MCTS in
+//! `engine::search`.
+//!
+//! Negamax with alpha-beta pruning is run to depth 1, 2, ... until the time
+//! budget runs out or a forced result is proven, and the last completed
+//! iteration picks the move. A transposition table keyed by the Zobrist
+//! hash carries best moves and bounds between iterations; the remaining
+//! moves are tried killers first, then by history score, then from the
+//! centre outwards.
+//!
+//! Every node prunes with the same tactics as the playout policy: an
+//! immediate win ends the search, moves that make three are never tried,
+//! and if the opponent has a cell that completes four the only candidate is
+//! to block it (two such cells are a proven loss). Leaves are scored by the
+//! shape of every 4-cell line: a line holding only one player's stones is
+//! worth more the more stones it holds.
+
+use crate::board::MAX_CELLS;
+use crate::engine::{
+    apply_move_with_meta, candidate_moves, centered_moves, immediate_losing_moves,
+    immediate_winning_moves, legal_moves_with_swap, now_ms, one_ply_safe_moves, outcome,
+    settled_at_root, winner_from_outcome, Candidates, MoveStats, Outcome, Position, SearchReport,
+};
+use crate::moveset::MoveSet;
+
+/// Score of a win on the spot; a win `n` plies away scores `WIN - n`.
+const WIN: i32 = 1_000_000;
+/// Scores beyond this are proven wins or losses.
+const PROVEN: i32 = WIN - 1_000;
+const INFINITY: i32 = WIN + 1;
+const MAX_DEPTH: u8 = 64;
+const TABLE_SIZE: usize = 1 << 18;
+/// Worth of a line with 1, 2 or 3 of one player's stones and none of the
+/// other's.
+const LINE_WEIGHTS: [i32; 4] = [0, 1, 6, 40];
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Bound {
+    Exact,
+    Lower,
+    Upper,
+}
+
+#[derive(Clone, Copy)]
+struct Entry {
+    key: u64,
+    depth: u8,
+    score: i32,
+    bound: Bound,
+    best: Option<u8>,
+}
+
+struct AlphaBeta {
+    table: Vec<Option<Entry>>,
+    killers: [[Option<u8>; 2]; MAX_DEPTH as usize + 1],
+    /// Cutoff credit per player and move.
+    history: [[u32; MAX_CELLS + 1]; 2],
+    /// Position of each cell in the board's centre-out order.
+    centre_rank: [u8; MAX_CELLS + 1],
+    nodes: u64,
+    deadline: f64,
+    can_stop: bool,
+    stopped: bool,
+}
+
+impl AlphaBeta {
+    fn new(pos: Position, deadline: f64) -> Self {
+        let mut centre_rank = [u8::MAX; MAX_CELLS + 1];
+        for (rank, &idx) in pos.board().center_order().iter().enumerate() {
+            centre_rank[usize::from(idx)] = rank as u8;
+        }
+        Self {
+            table: vec![None; TABLE_SIZE],
+            killers: [[None; 2]; MAX_DEPTH as usize + 1],
+            history: [[0; MAX_CELLS + 1]; 2],
+            centre_rank,
+            nodes: 0,
+            deadline,
+            can_stop: false,
+            stopped: false,
+        }
+    }
+
+    fn negamax(
+        &mut self,
+        pos: Position,
+        just_played: Option<(u8, u8)>,
+        depth: u8,
+        ply: u8,
+        mut alpha: i32,
+        beta: i32,
+    ) -> i32 {
+        self.nodes += 1;
+        if self.can_stop && self.nodes.is_multiple_of(1024) && now_ms() >= self.deadline {
+            self.stopped = true;
+        }
+        if self.stopped {
+            return 0;
+        }
+        let ply_score = i32::from(ply);
+        let result = outcome(pos, just_played);
+        if let Some(winner) = winner_from_outcome(result) {
+            return if winner == pos.turn {
+                WIN - ply_score
+            } else {
+                ply_score - WIN
+            };
+        }
+        if !matches!(result, Outcome::Ongoing) {
+            return 0;
+        }
+        if !immediate_winning_moves(pos).is_empty() {
+            return WIN - ply_score - 1;
+        }
+        let moves = match candidate_moves(pos) {
+            Candidates::Moves(moves) if moves.is_empty() => return 0,
+            Candidates::Moves(moves) => moves,
+            Candidates::LostIn(plies) => return ply_score + plies - WIN,
+        };
+        if depth == 0 {
+            return evaluate(pos);
+        }
+
+        let slot = (pos.hash as usize) & (TABLE_SIZE - 1);
+        let mut table_move = None;
+        if let Some(entry) = self.table[slot].filter(|entry| entry.key == pos.hash) {
+            table_move = entry.best;
+            if entry.depth >= depth {
+                let score = from_table(entry.score, ply_score);
+                let usable = match entry.bound {
+                    Bound::Exact => true,
+                    Bound::Lower => score >= beta,
+                    Bound::Upper => score <= alpha,
+                };
+                if usable {
+                    return score;
+                }
+            }
+        }
+
+        let original_alpha = alpha;
+        let mut best_score = -INFINITY;
+        let mut best_move = None;
+        for mv in self.ordered(pos, moves, table_move, ply) {
+            let Ok((next, next_jp)) = apply_move_with_meta(pos, mv) else {
+                continue;
+            };
+            let score = -self.negamax(next, next_jp, depth - 1, ply + 1, -beta, -alpha);
+            if self.stopped {
+                return 0;
+            }
+            if score > best_score {
+                best_score = score;
+                best_move = Some(mv);
+            }
+            alpha = alpha.max(score);
+            if alpha >= beta {
+                let killers = &mut self.killers[usize::from(ply)];
+                if killers[0] != Some(mv) {
+                    killers[1] = killers[0];
+                    killers[0] = Some(mv);
+                }
+                let credit = u32::from(depth) * u32::from(depth);
+                let history = &mut self.history[usize::from(pos.turn)][usize::from(mv)];
+                *history = history.saturating_add(credit);
+                break;
+            }
+        }
+
+        let bound = if best_score <= original_alpha {
+            Bound::Upper
+        } else if best_score >= beta {
+            Bound::Lower
+        } else {
+            Bound::Exact
+        };
+        self.table[slot] = Some(Entry {
+            key: pos.hash,
+            depth,
+            score: to_table(best_score, ply_score),
+            bound,
+            best: best_move,
+        });
+        best_score
+    }
+
+    /// `moves` in search order: the table's best move, this ply's killers,
+    /// then the rest by history score and closeness to the centre.
+    fn ordered(&self, pos: Position, moves: MoveSet, table_move: Option<u8>, ply: u8) -> Vec<u8> {
+        let killers = self.killers[usize::from(ply)];
+        let history = &self.history[usize::from(pos.turn)];
+        let mut ordered = moves.to_vec();
+        ordered.sort_by_key(|&mv| {
+            let first = if Some(mv) == table_move {
+                0
+            } else if killers.contains(&Some(mv)) {
+                1
+            } else {
+                2
+            };
+            (
+                first,
+                std::cmp::Reverse(history[usize::from(mv)]),
+                self.centre_rank[usize::from(mv)],
+            )
+        });
+        ordered
+    }
+}
+
+/// The shape of the position for the side to move.
+fn evaluate(pos: Position) -> i32 {
+    let (ours, theirs) = (pos.stones(pos.turn), pos.stones(pos.turn ^ 1));
+    pos.board()
+        .four_line_masks()
+        .iter()
+        .map(|&mask| {
+            let (us, them) = ((ours & mask).count_ones(), (theirs & mask).count_ones());
+            match (us, them) {
+                (n, 0) => LINE_WEIGHTS[n.min(3) as usize],
+                (0, n) => -LINE_WEIGHTS[n.min(3) as usize],
+                _ => 0,
+            }
+        })
+        .sum()
+}
+
+/// Proven scores count plies from the root; the table stores them counted
+/// from the node so they stay right wherever the node is reached.
+fn to_table(score: i32, ply: i32) -> i32 {
+    if score > PROVEN {
+        score + ply
+    } else if score < -PROVEN {
+        score - ply
+    } else {
+        score
+    }
+}
+
+fn from_table(score: i32, ply: i32) -> i32 {
+    if score > PROVEN {
+        score - ply
+    } else if score < -PROVEN {
+        score + ply
+    } else {
+        score
+    }
+}
+
+/// A score as a value from -1 (loss) to 1 (win) for the side to move.
+fn to_value(score: i32) -> f64 {
+    if score.abs() > PROVEN {
+        f64::from(score.signum())
+    } else {
+        (f64::from(score) / 100.0).tanh()
+    }
+}
+
+/// Deepest iteration for each strength; the time budget usually stops the
+/// search first at the higher ones.
+fn max_depth(strength: u8) -> u8 {
+    match strength {
+        0 => 2,
+        1 => 4,
+        2 => 8,
+        _ => MAX_DEPTH,
+    }
+}
+
+/// Searches `pos` within `budget_ms` and reports the move from the deepest
+/// completed iteration. `strength` caps the depth.
+pub fn search(pos: Position, budget_ms: f64, strength: u8) -> SearchReport {
+    if let Some(report) = settled_at_root(pos) {
+        return report;
+    }
+    let legal = legal_moves_with_swap(pos);
+    let safe = one_ply_safe_moves(pos);
+    let not_losing = legal - immediate_losing_moves(pos);
+    let root_moves = if !safe.is_empty() {
+        safe
+    } else if !not_losing.is_empty() {
+        not_losing
+    } else {
+        legal
+    };
+
+    let deadline = now_ms() + budget_ms.max(10.0);
+    let mut searcher = AlphaBeta::new(pos, deadline);
+    let mut order: Vec<u8> = centered_moves(pos.board(), root_moves).collect();
+    let mut completed: Vec<MoveStats> = Vec::new();
+    let mut completed_depth = 0;
+    let mut best_score = 0;
+
+    for depth in 1..=max_depth(strength) {
+        // Depth 1 always finishes, so there is a searched move to report.
+        searcher.can_stop = depth > 1;
+        let mut alpha = -INFINITY;
+        let mut scored: Vec<(i32, MoveStats)> = Vec::with_capacity(order.len());
+        for &mv in &order {
+            let Ok((next, next_jp)) = apply_move_with_meta(pos, mv) else {
+                continue;
+            };
+            let before = searcher.nodes;
+            let score = -searcher.negamax(next, next_jp, depth - 1, 1, -INFINITY, -alpha);
+            if searcher.stopped {
+                break;
+            }
+            alpha = alpha.max(score);
+            scored.push((
+                score,
+                MoveStats {
+                    mv,
+                    visits: (searcher.nodes - before).min(u64::from(u32::MAX)) as u32,
+                    value: to_value(score),
+                },
+            ));
+        }
+        if searcher.stopped {
+            break;
+        }
+        // Stable, so equal scores keep the previous iteration's order.
+        scored.sort_by_key(|&(score, _)| std::cmp::Reverse(score));
+        order = scored.iter().map(|(_, stats)| stats.mv).collect();
+        completed = scored.iter().map(|&(_, stats)| stats).collect();
+        completed_depth = depth;
+        best_score = scored[0].0;
+        if best_score.abs() > PROVEN {
+            break;
+        }
+    }
+
+    SearchReport {
+        best_move: completed.first().map(|stats| stats.mv),
+        iterations: searcher.nodes.min(u64::from(u32::MAX)) as u32,
+        depth: completed_depth,
+        value: completed.first().map(|stats| stats.value),
+        proven: best_score.abs() > PROVEN,
+        moves: completed,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::diagram::parse_diagram;
+    use crate::engine::Searcher;
+    use crate::error::ParseError;
+    use crate::fixtures;
+    use crate::notation::parse_cell;
+
+    #[test]
+    fn blocks_the_only_threat() {
+        let pos = parse_diagram(
+            "
+                a x . . . .
+               b . . . . . .
+              c . . . . . . .
+             d x . . . . . . .
+            e o o . o . . . . .
+             f . . . . . . . .
+              g . . . . . . .
+               h . . . . . .
+                i . . . . x
+            x to move
+            ",
+        )
+        .unwrap();
+        assert_eq!(
+            search(pos, 200.0, 2).best_move,
+            parse_cell(pos.board(), "e3").ok()
+        );
+    }
+
+    #[test]
+    fn proves_a_double_threat() {
+        let pos = fixtures::double_threat();
+        let report = search(pos, 2_000.0, 3);
+        assert_eq!(
+            report.best_move,
+            parse_cell(pos.board(), "e4").ok(),
+            "\n{pos}"
+        );
+        assert_eq!(report.value, Some(1.0));
+        // The threat precheck shared with MCTS proves it before any
+        // iteration runs.
+        assert!(report.proven, "{report:?}");
+        assert_eq!(report.depth, 0);
+
+        // Negamax proves it on its own within three plies.
+        let mut searcher = AlphaBeta::new(pos, now_ms() + 2_000.0);
+        let score = searcher.negamax(pos, None, 3, 0, -INFINITY, INFINITY);
+        assert!(score >= PROVEN, "{score}");
+    }
+
+    #[test]
+    fn reports_every_root_move_best_first() {
+        let pos = Position::empty().apply(30).unwrap().apply(21).unwrap();
+        let report = search(pos, 100.0, 1);
+        assert!(report.depth >= 1);
+        assert!(report.iterations > 0);
+        assert_eq!(report.best_move, Some(report.moves[0].mv));
+        assert!(report
+            .moves
+            .windows(2)
+            .all(|pair| pair[0].value >= pair[1].value));
+        assert!(report.moves.iter().all(|stats| stats.value.abs() <= 1.0));
+    }
+
+    #[test]
+    fn searchers_are_selected_by_name() {
+        assert_eq!("alpha-beta".parse(), Ok(Searcher::AlphaBeta));
+        assert_eq!("mcts".parse(), Ok(Searcher::Mcts));
+        assert_eq!(
+            "minimax".parse::<Searcher>(),
+            Err(ParseError::UnknownSearcher)
+        );
+        let pos = Position::empty().apply(30).unwrap();
+        let report = Searcher::AlphaBeta.search(pos, 50.0, 0);
+        assert!(report.depth > 0);
+        assert_eq!(Searcher::Mcts.search(pos, 50.0, 0).depth, 0);
+    }
+}
@@ -1,7 +1,15 @@
 use std::env;
 use std::io::{self, BufRead, Write};
 
-use yavalath_engine::engine::{outcome, Outcome, Position, SWAP_MOVE};
+use yavalath_engine::board::Board;
+use yavalath_engine::engine::{
+    apply_move_with_meta, encode_board_hex, legal_moves_with_swap, outcome, MoveOptions, Outcome,
+    Position, Searcher,
+};
+use yavalath_engine::error::MoveError;
+use yavalath_engine::pns::{self, Verdict};
+use yavalath_engine::rules::Rules;
+use yavalath_engine::{fen, notation};
 
 #[derive(Clone, Copy)]
 struct Rng64 {
@@ -38,24 +46,29 @@ enum Strategy {
     Random,
     DeterministicSequence,
     Strength(u8),
+    AlphaBeta(u8),
+    /// Plays a proven win or draw when proof-number search finds one within
+    /// the node budget, and the alpha-beta move otherwise.
+    Solver {
+        strength: u8,
+        max_nodes: u32,
+    },
 }
 
 fn parse_arg_value(args: &[String], key: &str) -> Option<String> {
-    args.windows(2)
-        .find(|w| w[0] == key)
-        .map(|w| w[1].clone())
+    args.windows(2).find(|w| w[0] == key).map(|w| w[1].clone())
 }
 
 fn deterministic_sequence_move(pos: Position) -> Option<u8> {
     const PREFERRED: [u8; 61] = [
-        30, 22, 23, 29, 31, 37, 38, 15, 16, 17, 21, 24, 28, 32, 36, 39, 43, 44, 8, 9, 10, 11,
-        14, 18, 20, 25, 27, 33, 35, 40, 42, 45, 46, 50, 51, 1, 2, 3, 4, 5, 7, 12, 13, 19, 26,
-        34, 41, 47, 48, 49, 52, 53, 54, 0, 6, 55, 56, 57, 58, 59, 60,
+        30, 22, 23, 29, 31, 37, 38, 15, 16, 17, 21, 24, 28, 32, 36, 39, 43, 44, 8, 9, 10, 11, 14,
+        18, 20, 25, 27, 33, 35, 40, 42, 45, 46, 50, 51, 1, 2, 3, 4, 5, 7, 12, 13, 19, 26, 34, 41,
+        47, 48, 49, 52, 53, 54, 0, 6, 55, 56, 57, 58, 59, 60,
     ];
 
     let us = pos.turn;
     for mv in legal_moves_with_swap(pos) {
-        let Some((next, jp)) = apply_move(pos, mv) else {
+        let Ok((next, jp)) = apply_move_with_meta(pos, mv) else {
             continue;
         };
         if matches!(outcome(next, jp), Outcome::Win(w, _) if w == us) {
@@ -63,11 +76,15 @@ fn deterministic_sequence_move(pos: Position) -> Option<u8> {
         }
     }
 
-    for &mv in &PREFERRED {
-        if !is_legal(pos, mv) {
-            continue;
-        }
-        let Some((next, jp)) = apply_move(pos, mv) else {
+    // The hand-tuned order is for the standard board; other sizes fall back
+    // to plain centre-first order.
+    let preferred: &[u8] = if *pos.board() == *Board::standard() {
+        &PREFERRED
+    } else {
+        pos.board().center_order()
+    };
+    for &mv in preferred {
+        let Ok((next, jp)) = apply_move_with_meta(pos, mv) else {
             continue;
         };
         if matches!(outcome(next, jp), Outcome::Lose(l, _) if l == us) {
@@ -76,50 +93,77 @@ fn deterministic_sequence_move(pos: Position) -> Option<u8> {
         return Some(mv);
     }
 
-    legal_moves_with_swap(pos).into_iter().next()
-}
-
-fn is_legal(pos: Position, mv: u8) -> bool {
-    if mv == SWAP_MOVE {
-        pos.can_swap()
-    } else {
-        pos.occupied() & (1_u64 << mv) == 0
-    }
-}
-
-fn legal_moves_with_swap(pos: Position) -> Vec<u8> {
-    let mut moves = pos.legal_moves();
-    if pos.can_swap() {
-        moves.push(SWAP_MOVE);
-    }
-    moves
+    legal_moves_with_swap(pos).first()
 }
 
-fn apply_move(pos: Position, mv: u8) -> Option<(Position, Option<(u8, u8)>)> {
-    if mv == SWAP_MOVE {
-        Some((pos.apply_swap()?, None))
-    } else {
-        Some((pos.apply(mv)?, Some((pos.turn, mv))))
-    }
-}
-
-fn choose_move(pos: Position, strategy: Strategy, time_ms: f64, rng: &mut Rng64) -> Option<u8> {
+fn choose_move(
+    pos: Position,
+    strategy: Strategy,
+    time_ms: f64,
+    use_book: bool,
+    use_swap_table: bool,
+    rng: &mut Rng64,
+) -> Option<u8> {
+    // Drawn only by the searching strategies, so `random` games keep their
+    // move sequence for a given `--seed`.
+    let mut options = || MoveOptions {
+        book: use_book,
+        swap_table: use_swap_table,
+        seed: rng.next_u64(),
+    };
     match strategy {
         Strategy::Random => {
             let legal = legal_moves_with_swap(pos);
-            if legal.is_empty() {
-                None
-            } else {
-                Some(legal[rng.gen_index(legal.len())])
-            }
+            legal.nth(rng.gen_index(legal.len()))
         }
         Strategy::DeterministicSequence => deterministic_sequence_move(pos),
         Strategy::Strength(strength) => {
-            yavalath_engine::engine::best_move_with_strength(pos, time_ms, strength)
+            Searcher::Mcts.best_move_with(pos, time_ms, strength, options())
+        }
+        Strategy::AlphaBeta(strength) => {
+            Searcher::AlphaBeta.best_move_with(pos, time_ms, strength, options())
+        }
+        Strategy::Solver {
+            strength,
+            max_nodes,
+        } => {
+            let report = pns::solve(pos, max_nodes);
+            match report.verdict {
+                Verdict::Win | Verdict::Draw => report.best_move,
+                Verdict::Loss | Verdict::Unknown => None,
+            }
+            .or_else(|| Searcher::AlphaBeta.best_move_with(pos, time_ms, strength, options()))
         }
     }
 }
 
+/// Reads one request line: a `yav1` position string, a packed board hex, or
+/// `moves` followed by the moves from the empty board, each in notation or
+/// as a move index (e.g. `moves e5 swap d4` or `moves 30 61 22`).
+fn read_position(
+    board: &'static Board,
+    rules: Rules,
+    line: &str,
+) -> Result<(Position, Option<(u8, u8)>), String> {
+    let Some(moves) = line.strip_prefix("moves") else {
+        let pos = fen::parse_position_or_hex(board, rules, line).map_err(|err| err.to_string())?;
+        return Ok((pos, None));
+    };
+    let mut pos = Position::empty_on(board).with_rules(rules);
+    let mut just_played = None;
+    for word in moves.split_whitespace() {
+        let mv = match word.parse::<u8>() {
+            Ok(mv) => mv,
+            Err(_) => notation::parse_move(board, word).map_err(|err| err.to_string())?,
+        };
+        if !matches!(outcome(pos, just_played), Outcome::Ongoing) {
+            return Err(MoveError::GameOver.to_string());
+        }
+        (pos, just_played) = apply_move_with_meta(pos, mv).map_err(|err| err.to_string())?;
+    }
+    Ok((pos, just_played))
+}
+
 fn main() {
     let args: Vec<String> = env::args().collect();
     let strategy_name = parse_arg_value(&args, "--strategy").unwrap_or_else(|| "strength".into());
@@ -129,14 +173,43 @@ fn main() {
     let strength = parse_arg_value(&args, "--strength")
         .and_then(|s| s.parse::<u8>().ok())
         .unwrap_or(2);
+    let max_nodes = parse_arg_value(&args, "--nodes")
+        .and_then(|s| s.parse::<u32>().ok())
+        .unwrap_or(50_000);
     let seed = parse_arg_value(&args, "--seed")
         .and_then(|s| s.parse::<u64>().ok())
         .unwrap_or(1);
+    let reply_in_notation = args.iter().any(|arg| arg == "--notation");
+    let use_book = !args.iter().any(|arg| arg == "--no-book");
+    let use_swap_table = !args.iter().any(|arg| arg == "--no-swap-table");
+    let board = match parse_arg_value(&args, "--radius") {
+        Some(radius) => match radius.parse::<u8>().ok().and_then(Board::with_radius) {
+            Some(board) => board,
+            None => {
+                eprintln!("unsupported --radius {radius}");
+                std::process::exit(2);
+            }
+        },
+        None => Board::standard(),
+    };
+    let rules = match parse_arg_value(&args, "--rules").map(|spec| spec.parse::<Rules>()) {
+        Some(Ok(rules)) => rules,
+        Some(Err(err)) => {
+            eprintln!("invalid --rules: {err}");
+            std::process::exit(2);
+        }
+        None => Rules::STANDARD,
+    };
 
     let strategy = match strategy_name.as_str() {
         "random" => Strategy::Random,
         "sequence" => Strategy::DeterministicSequence,
-        "strength" => Strategy::Strength(strength),
+        "strength" | "mcts" => Strategy::Strength(strength),
+        "alpha-beta" | "alphabeta" => Strategy::AlphaBeta(strength),
+        "pns" | "solver" => Strategy::Solver {
+            strength,
+            max_nodes,
+        },
         _ => Strategy::Strength(strength),
     };
 
@@ -145,20 +218,53 @@ fn main() {
     let mut rng = Rng64::new(seed);
 
     for line in stdin.lock().lines() {
-        let Ok(board_hex) = line else { break };
-        let board_hex = board_hex.trim();
-        if board_hex.is_empty() {
+        let Ok(line) = line else { break };
+        let line = line.trim();
+        if line.is_empty() {
             continue;
         }
-        let Ok(pos) = yavalath_engine::engine::parse_board_hex(board_hex) else {
-            let _ = writeln!(stdout, "-1");
+        // `result <request>` asks for the state of a game instead of a
+        // move, so a tournament can referee with the engine's own rules: the
+        // board hex and outcome JSON, or `error <reason>` for an illegal
+        // move list.
+        if let Some(request) = line.strip_prefix("result ") {
+            let reply = read_position(board, rules, request).map(|(pos, jp)| {
+                serde_json::json!({
+                    "board_hex": encode_board_hex(pos),
+                    "outcome": outcome(pos, jp),
+                })
+            });
+            let _ = match reply {
+                Ok(reply) => writeln!(stdout, "{reply}"),
+                Err(err) => writeln!(stdout, "error {err}"),
+            };
             let _ = stdout.flush();
             continue;
-        };
-        let mv = choose_move(pos, strategy, time_ms, &mut rng)
-            .map(i32::from)
-            .unwrap_or(-1);
-        let _ = writeln!(stdout, "{mv}");
+        }
+        // Replies are a move index (or its name with `--notation`), or
+        // `error <reason>` when no move can be made for the given board.
+        match read_position(board, rules, line) {
+            Err(err) => {
+                let _ = writeln!(stdout, "error {err}");
+            }
+            Ok((pos, jp)) if !matches!(outcome(pos, jp), Outcome::Ongoing) => {
+                let _ = writeln!(stdout, "error {}", MoveError::GameOver);
+            }
+            Ok((pos, _)) => {
+                match choose_move(pos, strategy, time_ms, use_book, use_swap_table, &mut rng) {
+                    Some(mv) if reply_in_notation => {
+                        let name = notation::move_name(board, mv).unwrap_or_default();
+                        let _ = writeln!(stdout, "{name}");
+                    }
+                    Some(mv) => {
+                        let _ = writeln!(stdout, "{mv}");
+                    }
+                    None => {
+                        let _ = writeln!(stdout, "error no legal move");
+                    }
+                }
+            }
+        }
         let _ = stdout.flush();
     }
 }
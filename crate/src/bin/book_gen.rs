@@ -0,0 +1,94 @@
+//! Builds an opening book offline (see `yavalath_engine::book`).
+//!
+//! `book_gen --plies 3 --time-ms 1000 --out book/standard.book` searches
+//! every distinct position of the first three plies on the standard board
+//! for a second each. `--searcher`, `--strength`, `--radius` and `--rules`
+//! work as for `arena_agent`. Swap decisions follow the compiled-in swap
+//! table, so regenerate the table first when it changes.
+//!
+//! `book_gen --swap-table --time-ms 10000 --runs 8` instead prints the rows
+//! of `swap::STANDARD_TABLE`: the swap and keep values of one first move per
+//! symmetry class, each the mean of `--runs` MCTS searches of the given
+//! time (`swap::search_averaged`). With `--searcher alpha-beta` each value
+//! is a single search.
+
+use std::env;
+use std::fs;
+
+use yavalath_engine::board::Board;
+use yavalath_engine::engine::{Position, Searcher};
+use yavalath_engine::rules::Rules;
+use yavalath_engine::{book, swap};
+
+fn parse_arg_value(args: &[String], key: &str) -> Option<String> {
+    args.windows(2).find(|w| w[0] == key).map(|w| w[1].clone())
+}
+
+fn fail(message: String) -> ! {
+    eprintln!("{message}");
+    std::process::exit(2);
+}
+
+fn main() {
+    let args: Vec<String> = env::args().collect();
+    let plies = parse_arg_value(&args, "--plies")
+        .and_then(|s| s.parse::<u8>().ok())
+        .unwrap_or(3);
+    let time_ms = parse_arg_value(&args, "--time-ms")
+        .and_then(|s| s.parse::<f64>().ok())
+        .unwrap_or(1000.0);
+    let strength = parse_arg_value(&args, "--strength")
+        .and_then(|s| s.parse::<u8>().ok())
+        .unwrap_or(3);
+    let runs = parse_arg_value(&args, "--runs")
+        .and_then(|s| s.parse::<u32>().ok())
+        .unwrap_or(8);
+    let out = parse_arg_value(&args, "--out").unwrap_or_else(|| "book/standard.book".into());
+    let searcher = match parse_arg_value(&args, "--searcher").map(|s| s.parse::<Searcher>()) {
+        Some(Ok(searcher)) => searcher,
+        Some(Err(err)) => fail(format!("invalid --searcher: {err}")),
+        None => Searcher::Mcts,
+    };
+    let board = match parse_arg_value(&args, "--radius") {
+        Some(radius) => radius
+            .parse::<u8>()
+            .ok()
+            .and_then(Board::with_radius)
+            .unwrap_or_else(|| fail(format!("unsupported --radius {radius}"))),
+        None => Board::standard(),
+    };
+    let rules = match parse_arg_value(&args, "--rules").map(|spec| spec.parse::<Rules>()) {
+        Some(Ok(rules)) => rules,
+        Some(Err(err)) => fail(format!("invalid --rules: {err}")),
+        None => Rules::STANDARD,
+    };
+
+    let root = Position::empty_on(board).with_rules(rules);
+    if args.iter().any(|arg| arg == "--swap-table") {
+        for cell in swap::representative_openings(board) {
+            let opened = root
+                .apply(cell)
+                .unwrap_or_else(|err| fail(format!("cannot open at {cell}: {err}")));
+            let advice = match searcher {
+                Searcher::Mcts => swap::search_averaged(opened, time_ms, strength, runs),
+                Searcher::AlphaBeta => swap::search(opened, searcher, time_ms, strength),
+            };
+            let Some(advice) = advice else {
+                fail(format!("no swap decision after {cell}"));
+            };
+            println!(
+                "    ({cell}, {:.3}, {:.3}),",
+                advice.swap_value, advice.keep_value
+            );
+        }
+        return;
+    }
+    let book = book::generate(root, plies, searcher, time_ms, strength, |done, found| {
+        eprint!("\rsearched {done}/{found}");
+    });
+    eprintln!();
+    if let Err(err) = fs::write(&out, book.to_bytes()) {
+        fail(format!("cannot write {out}: {err}"));
+    }
+    eprintln!("wrote {} positions to {out}", book.len());
+}
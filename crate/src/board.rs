@@ -0,0 +1,324 @@
+//! Board geometry for hex-hex boards of any supported radius.
+//!
+//! Cells are numbered in the `axial_cells` order (by `q`, then `r`), and
+//! stones are stored in `Bitboard`s with one bit per cell. Each board carries
+//! line tables built once on first use: for every cell, the masks of every
+//! 4-cell/3-cell window through it, padded with `Bitboard::MAX` (which no real
+//! stone set can contain), so terminal detection after a move is a handful of
+//! AND/compare operations.
+
+use std::sync::OnceLock;
+
+use crate::engine::DIRS;
+
+/// One bit per cell. 128 bits cover every board up to radius 6.
+pub type Bitboard = u128;
+
+pub const MIN_RADIUS: u8 = 2;
+pub const MAX_RADIUS: u8 = 6;
+pub const STANDARD_RADIUS: u8 = 4;
+
+/// Largest cell count over all supported boards.
+pub(crate) const MAX_CELLS: usize = 127;
+
+pub(crate) const WIN_SLOTS: usize = 12;
+pub(crate) const LOSE_SLOTS: usize = 9;
+
+/// Axes as (positive, negative) indices into `DIRS`.
+const AXES: [(usize, usize); 3] = [(0, 3), (1, 4), (2, 5)];
+
+/// Directions in which every line runs towards higher indices, in the order
+/// that yields lines sorted lexicographically when started from each cell.
+const ASCENDING_DIRS: [(i32, i32); 3] = [(0, 1), (1, -1), (1, 0)];
+
+pub(crate) fn bit(idx: u8) -> Bitboard {
+    1 << idx
+}
+
+pub struct Board {
+    radius: u8,
+    cells: Vec<(i32, i32)>,
+    mask: Bitboard,
+    win_checks: Vec<[Bitboard; WIN_SLOTS]>,
+    win_lines: Vec<[[u8; 4]; WIN_SLOTS]>,
+    lose_checks: Vec<[Bitboard; LOSE_SLOTS]>,
+    lose_lines: Vec<[[u8; 3]; LOSE_SLOTS]>,
+    four_lines: Vec<[u8; 4]>,
+    four_line_masks: Vec<Bitboard>,
+    three_lines: Vec<[u8; 3]>,
+    three_line_masks: Vec<Bitboard>,
+    center_order: Vec<u8>,
+}
+
+impl Board {
+    /// The board of the given radius, or `None` outside
+    /// `MIN_RADIUS..=MAX_RADIUS`.
+    pub fn with_radius(radius: u8) -> Option<&'static Board> {
+        static BOARDS: [OnceLock<Board>; (MAX_RADIUS - MIN_RADIUS + 1) as usize] =
+            [const { OnceLock::new() }; (MAX_RADIUS - MIN_RADIUS + 1) as usize];
+        if !(MIN_RADIUS..=MAX_RADIUS).contains(&radius) {
+            return None;
+        }
+        Some(BOARDS[(radius - MIN_RADIUS) as usize].get_or_init(|| Board::build(radius)))
+    }
+
+    /// The standard 61-cell board.
+    pub fn standard() -> &'static Board {
+        static STANDARD: OnceLock<&'static Board> = OnceLock::new();
+        STANDARD.get_or_init(|| Board::with_radius(STANDARD_RADIUS).expect("standard radius"))
+    }
+
+    pub fn radius(&self) -> u8 {
+        self.radius
+    }
+
+    pub fn cell_count(&self) -> usize {
+        self.cells.len()
+    }
+
+    /// The move index used for the pie rule: one past the last cell.
+    pub fn swap_move(&self) -> u8 {
+        self.cells.len() as u8
+    }
+
+    /// Every cell on the board.
+    pub fn mask(&self) -> Bitboard {
+        self.mask
+    }
+
+    /// Number of cells in the `column`-th column (constant `q`), counting
+    /// from `q = -radius`.
+    pub fn column_len(&self, column: usize) -> usize {
+        let radius = usize::from(self.radius);
+        2 * radius + 1 - column.abs_diff(radius)
+    }
+
+    pub fn axial_cells(&self) -> &[(i32, i32)] {
+        &self.cells
+    }
+
+    pub fn axial(&self, idx: u8) -> Option<(i32, i32)> {
+        self.cells.get(idx as usize).copied()
+    }
+
+    /// Index of `(q, r)` in the `axial_cells` ordering.
+    pub fn index(&self, q: i32, r: i32) -> Option<u8> {
+        let radius = i32::from(self.radius);
+        if q.abs() > radius || r.abs() > radius || (q + r).abs() > radius {
+            return None;
+        }
+        let before: i32 = (-radius..q).map(|col| 2 * radius + 1 - col.abs()).sum();
+        let r_min = (-radius).max(-q - radius);
+        Some((before + r - r_min) as u8)
+    }
+
+    /// Hex distance of `idx` from the centre cell.
+    pub fn distance_from_centre(&self, idx: u8) -> u8 {
+        let (q, r) = self.cells[idx as usize];
+        q.abs().max(r.abs()).max((q + r).abs()) as u8
+    }
+
+    /// Cells at most `distance` from the centre.
+    pub fn cells_within(&self, distance: u8) -> Bitboard {
+        (0..self.cells.len() as u8)
+            .filter(|&idx| self.distance_from_centre(idx) <= distance)
+            .fold(0, |mask, idx| mask | bit(idx))
+    }
+
+    /// Every line of four cells, sorted, each listed from its lowest index.
+    pub fn four_lines(&self) -> &[[u8; 4]] {
+        &self.four_lines
+    }
+
+    /// Every line of three cells, sorted, each listed from its lowest index.
+    pub fn three_lines(&self) -> &[[u8; 3]] {
+        &self.three_lines
+    }
+
+    /// Cell indices ordered from the centre outwards, ties broken by index.
+    pub fn center_order(&self) -> &[u8] {
+        &self.center_order
+    }
+
+    pub(crate) fn win_checks(&self, idx: u8) -> (&[Bitboard; WIN_SLOTS], &[[u8; 4]; WIN_SLOTS]) {
+        (
+            &self.win_checks[idx as usize],
+            &self.win_lines[idx as usize],
+        )
+    }
+
+    pub(crate) fn lose_checks(&self, idx: u8) -> (&[Bitboard; LOSE_SLOTS], &[[u8; 3]; LOSE_SLOTS]) {
+        (
+            &self.lose_checks[idx as usize],
+            &self.lose_lines[idx as usize],
+        )
+    }
+
+    pub(crate) fn four_line_masks(&self) -> &[Bitboard] {
+        &self.four_line_masks
+    }
+
+    pub(crate) fn three_line_masks(&self) -> &[Bitboard] {
+        &self.three_line_masks
+    }
+
+    fn build(radius: u8) -> Board {
+        let rad = i32::from(radius);
+        let mut cells = Vec::new();
+        for q in -rad..=rad {
+            let r_min = (-rad).max(-q - rad);
+            let r_max = rad.min(-q + rad);
+            for r in r_min..=r_max {
+                cells.push((q, r));
+            }
+        }
+        let mut board = Board {
+            radius,
+            mask: Bitboard::MAX >> (Bitboard::BITS as usize - cells.len()),
+            cells,
+            win_checks: Vec::new(),
+            win_lines: Vec::new(),
+            lose_checks: Vec::new(),
+            lose_lines: Vec::new(),
+            four_lines: Vec::new(),
+            four_line_masks: Vec::new(),
+            three_lines: Vec::new(),
+            three_line_masks: Vec::new(),
+            center_order: Vec::new(),
+        };
+        (board.win_checks, board.win_lines) = board.line_checks::<4, WIN_SLOTS>();
+        (board.lose_checks, board.lose_lines) = board.line_checks::<3, LOSE_SLOTS>();
+        (board.four_lines, board.four_line_masks) = board.all_lines::<4>();
+        (board.three_lines, board.three_line_masks) = board.all_lines::<3>();
+        board.center_order = board.build_center_order();
+        board
+    }
+
+    /// A `W`-cell line from `(q, r)` stepping by `(dq, dr)`, if it fits.
+    fn line_from<const W: usize>(
+        &self,
+        (q, r): (i32, i32),
+        (dq, dr): (i32, i32),
+    ) -> Option<[u8; W]> {
+        let mut line = [0_u8; W];
+        for (step, cell) in line.iter_mut().enumerate() {
+            let k = step as i32;
+            *cell = self.index(q + dq * k, r + dr * k)?;
+        }
+        Some(line)
+    }
+
+    /// Every `W`-cell window through each cell, ordered by axis and then from
+    /// the negative end of the axis, with cells listed along the positive
+    /// direction.
+    #[allow(clippy::type_complexity)]
+    fn line_checks<const W: usize, const N: usize>(
+        &self,
+    ) -> (Vec<[Bitboard; N]>, Vec<[[u8; W]; N]>) {
+        let mut masks = vec![[Bitboard::MAX; N]; self.cells.len()];
+        let mut lines = vec![[[0_u8; W]; N]; self.cells.len()];
+        for (idx, &(q, r)) in self.cells.iter().enumerate() {
+            let mut slot = 0;
+            for &(dir_pos, _) in &AXES {
+                let (dq, dr) = DIRS[dir_pos];
+                for start in -(W as i32 - 1)..=0 {
+                    let from = (q + dq * start, r + dr * start);
+                    if let Some(line) = self.line_from::<W>(from, (dq, dr)) {
+                        masks[idx][slot] = line.iter().fold(0, |m, &c| m | bit(c));
+                        lines[idx][slot] = line;
+                        slot += 1;
+                    }
+                }
+            }
+        }
+        (masks, lines)
+    }
+
+    fn all_lines<const W: usize>(&self) -> (Vec<[u8; W]>, Vec<Bitboard>) {
+        let mut lines = Vec::new();
+        for &cell in &self.cells {
+            for &dir in &ASCENDING_DIRS {
+                if let Some(line) = self.line_from::<W>(cell, dir) {
+                    lines.push(line);
+                }
+            }
+        }
+        let masks = lines
+            .iter()
+            .map(|line| line.iter().fold(0, |m, &c| m | bit(c)))
+            .collect();
+        (lines, masks)
+    }
+
+    fn build_center_order(&self) -> Vec<u8> {
+        let distance = |idx: u8| {
+            let (q, r) = self.cells[idx as usize];
+            let x = f64::from(q) + f64::from(r) / 2.0;
+            let y = f64::from(r) * (3f64.sqrt() / 2.0);
+            (x * x + y * y).sqrt()
+        };
+        let mut order: Vec<u8> = (0..self.cells.len() as u8).collect();
+        order.sort_by(|&a, &b| {
+            distance(a)
+                .partial_cmp(&distance(b))
+                .unwrap_or(std::cmp::Ordering::Equal)
+        });
+        order
+    }
+}
+
+impl PartialEq for Board {
+    fn eq(&self, other: &Self) -> bool {
+        self.radius == other.radius
+    }
+}
+
+impl Eq for Board {}
+
+impl std::fmt::Debug for Board {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Board")
+            .field("radius", &self.radius)
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn cell_counts_and_indices_match_axial_order() {
+        for radius in MIN_RADIUS..=MAX_RADIUS {
+            let board = Board::with_radius(radius).unwrap();
+            let r = usize::from(radius);
+            assert_eq!(board.cell_count(), 3 * r * (r + 1) + 1);
+            for (idx, &(q, r)) in board.axial_cells().iter().enumerate() {
+                assert_eq!(board.index(q, r), Some(idx as u8));
+            }
+            assert_eq!(board.center_order()[0], board.index(0, 0).unwrap());
+        }
+        assert!(Board::with_radius(1).is_none());
+        assert!(Board::with_radius(7).is_none());
+        assert_eq!(Board::standard().cell_count(), 61);
+    }
+
+    #[test]
+    fn line_counts_follow_board_size() {
+        // Each axis has rows of length radius+1..=2*radius+1 (twice, except
+        // the middle row), contributing len - width + 1 lines each.
+        for radius in MIN_RADIUS..=MAX_RADIUS {
+            let board = Board::with_radius(radius).unwrap();
+            let rows = (0..=radius).map(|k| 2 * radius + 1 - k);
+            let per_axis = |width: u8| -> usize {
+                rows.clone()
+                    .map(|len| usize::from(len.saturating_sub(width - 1)))
+                    .enumerate()
+                    .map(|(k, n)| if k == 0 { n } else { 2 * n })
+                    .sum()
+            };
+            assert_eq!(board.four_lines().len(), 3 * per_axis(4));
+            assert_eq!(board.three_lines().len(), 3 * per_axis(3));
+        }
+    }
+}
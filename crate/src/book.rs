@@ -0,0 +1,440 @@
+//! Opening book: weighted moves for early positions, found offline.
+//!
+//! Positions are stored once per symmetry class, keyed by the Zobrist key
+//! of their `geometry::canonical` representative, with moves given on that
+//! representative; a lookup maps them back onto the position asked about.
+//! `generate` fills a book by searching every distinct position of the
+//! first few plies for much longer than a game allows, and the `book_gen`
+//! binary writes the result out.
+//!
+//! The binary format is little-endian:
+//!
+//! 1. the magic bytes `YAVB` and the format version, currently 1;
+//! 2. the board radius and the rules byte used by share codes (`code`);
+//! 3. the entry count as a `u32`;
+//! 4. the entries in ascending key order, each a `u64` key, a move count
+//!    and that many moves of a cell index (or the swap move) and a `u16`
+//!    weight.
+//!
+//! A book for the standard board and rules is embedded in the library, so
+//! wasm builds need no file access. `Searcher::best_move_with` plays from
+//! it unless `MoveOptions::book` is off, picking among the moves in
+//! proportion to weight.
+
+use std::cmp::Reverse;
+use std::collections::{BTreeMap, HashSet, VecDeque};
+use std::sync::OnceLock;
+
+use crate::board::Board;
+use crate::code::{rules_byte, rules_from_byte};
+use crate::engine::{
+    apply_move_with_meta, legal_moves_with_swap, outcome, Outcome, Position, Rng64, SearchReport,
+    Searcher,
+};
+use crate::error::BookError;
+use crate::geometry::canonical;
+use crate::rules::Rules;
+use crate::swap;
+
+pub const VERSION: u8 = 1;
+
+const MAGIC: &[u8; 4] = b"YAVB";
+const HEADER_LEN: usize = 11;
+/// Most moves `generate` keeps for one position.
+const MAX_MOVES: usize = 4;
+/// How far below the best move's value another move may be and still be
+/// kept by `generate`.
+const VALUE_MARGIN: f64 = 0.1;
+
+static EMBEDDED: &[u8] = include_bytes!("../book/standard.book");
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct BookMove {
+    pub mv: u8,
+    pub weight: u16,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Book {
+    board: &'static Board,
+    rules: Rules,
+    entries: BTreeMap<u64, Vec<BookMove>>,
+}
+
+impl Book {
+    /// An empty book for positions on `board` under `rules`.
+    pub fn new(board: &'static Board, rules: Rules) -> Self {
+        Self {
+            board,
+            rules,
+            entries: BTreeMap::new(),
+        }
+    }
+
+    pub fn board(&self) -> &'static Board {
+        self.board
+    }
+
+    pub fn rules(&self) -> Rules {
+        self.rules
+    }
+
+    /// Distinct positions in the book, up to symmetry.
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Whether positions like `pos` can be in the book at all.
+    pub fn covers(&self, pos: Position) -> bool {
+        *pos.board() == *self.board && pos.rules() == self.rules
+    }
+
+    /// Records `moves` for `pos` and every position symmetric to it,
+    /// replacing what was there. Moves of weight zero are dropped.
+    pub fn insert(&mut self, pos: Position, moves: &[BookMove]) {
+        if !self.covers(pos) {
+            return;
+        }
+        let (image, transform) = canonical(pos);
+        let moves = moves
+            .iter()
+            .filter(|entry| entry.weight > 0)
+            .map(|entry| BookMove {
+                mv: transform.apply_move(self.board, entry.mv),
+                weight: entry.weight,
+            })
+            .collect::<Vec<_>>();
+        if moves.is_empty() {
+            self.entries.remove(&image.hash);
+        } else {
+            self.entries.insert(image.hash, moves);
+        }
+    }
+
+    /// The book moves for `pos`, as moves on `pos` itself.
+    pub fn moves(&self, pos: Position) -> Option<Vec<BookMove>> {
+        if !self.covers(pos) {
+            return None;
+        }
+        let (image, transform) = canonical(pos);
+        let back = transform.inverse();
+        let legal = legal_moves_with_swap(pos);
+        let moves = self
+            .entries
+            .get(&image.hash)?
+            .iter()
+            .map(|entry| BookMove {
+                mv: back.apply_move(self.board, entry.mv),
+                weight: entry.weight,
+            })
+            .filter(|entry| legal.contains(entry.mv))
+            .collect::<Vec<_>>();
+        (!moves.is_empty()).then_some(moves)
+    }
+
+    /// A book move for `pos`, each chosen with probability in proportion
+    /// to its weight; the same `seed` always gives the same move.
+    pub fn choose(&self, pos: Position, seed: u64) -> Option<u8> {
+        let moves = self.moves(pos)?;
+        let total: u64 = moves.iter().map(|entry| u64::from(entry.weight)).sum();
+        if total == 0 {
+            return None;
+        }
+        let mut pick = Rng64::new(seed).next_u64() % total;
+        moves.iter().find_map(|entry| {
+            let weight = u64::from(entry.weight);
+            if pick < weight {
+                Some(entry.mv)
+            } else {
+                pick -= weight;
+                None
+            }
+        })
+    }
+
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut out = Vec::with_capacity(HEADER_LEN + self.entries.len() * 16);
+        out.extend_from_slice(MAGIC);
+        out.push(VERSION);
+        out.push(self.board.radius());
+        out.push(rules_byte(self.rules));
+        out.extend_from_slice(&(self.entries.len() as u32).to_le_bytes());
+        for (key, moves) in &self.entries {
+            out.extend_from_slice(&key.to_le_bytes());
+            out.push(moves.len() as u8);
+            for entry in moves {
+                out.push(entry.mv);
+                out.extend_from_slice(&entry.weight.to_le_bytes());
+            }
+        }
+        out
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, BookError> {
+        let mut reader = ByteReader { bytes, at: 0 };
+        if reader.take(MAGIC.len())? != MAGIC {
+            return Err(BookError::BadMagic);
+        }
+        let version = reader.byte()?;
+        if version != VERSION {
+            return Err(BookError::UnknownVersion(version));
+        }
+        let radius = reader.byte()?;
+        let board = Board::with_radius(radius).ok_or(BookError::UnsupportedBoard(radius))?;
+        let rules = rules_from_byte(reader.byte()?);
+        let count = u32::from_le_bytes(reader.array()?);
+
+        let mut book = Self::new(board, rules);
+        let mut last_key = None;
+        for _ in 0..count {
+            let key = u64::from_le_bytes(reader.array()?);
+            if last_key.is_some_and(|last| last >= key) {
+                return Err(BookError::Unsorted);
+            }
+            last_key = Some(key);
+            let moves = (0..reader.byte()?)
+                .map(|_| {
+                    Ok(BookMove {
+                        mv: reader.byte()?,
+                        weight: u16::from_le_bytes(reader.array()?),
+                    })
+                })
+                .collect::<Result<Vec<_>, BookError>>()?;
+            if moves.is_empty() || moves.iter().any(|entry| entry.weight == 0) {
+                return Err(BookError::EmptyEntry);
+            }
+            book.entries.insert(key, moves);
+        }
+        Ok(book)
+    }
+}
+
+struct ByteReader<'a> {
+    bytes: &'a [u8],
+    at: usize,
+}
+
+impl<'a> ByteReader<'a> {
+    fn take(&mut self, len: usize) -> Result<&'a [u8], BookError> {
+        let slice = self
+            .bytes
+            .get(self.at..self.at + len)
+            .ok_or(BookError::Truncated)?;
+        self.at += len;
+        Ok(slice)
+    }
+
+    fn byte(&mut self) -> Result<u8, BookError> {
+        Ok(self.take(1)?[0])
+    }
+
+    fn array<const N: usize>(&mut self) -> Result<[u8; N], BookError> {
+        Ok(self.take(N)?.try_into().expect("took N bytes"))
+    }
+}
+
+/// The book built into the library, for the standard board and rules.
+pub fn embedded() -> &'static Book {
+    static BOOK: OnceLock<Book> = OnceLock::new();
+    BOOK.get_or_init(|| Book::from_bytes(EMBEDDED).expect("the embedded book is valid"))
+}
+
+/// A move from the embedded book, chosen by `seed` as for `Book::choose`.
+pub fn book_move(pos: Position, seed: u64) -> Option<u8> {
+    embedded().choose(pos, pos.hash ^ seed)
+}
+
+/// The moves worth keeping from a search: the best move, and up to
+/// `MAX_MOVES` in all of those nearly as good, weighted by the effort the
+/// search spent on them. A proven result keeps only the best move.
+fn book_moves(report: &SearchReport) -> Vec<BookMove> {
+    let Some(best) = report.best_move else {
+        return Vec::new();
+    };
+    let mut moves = vec![best];
+    if !report.proven {
+        let best_value = report.value.unwrap_or(0.0);
+        let mut rest = report
+            .moves
+            .iter()
+            .filter(|stats| stats.mv != best && stats.value >= best_value - VALUE_MARGIN)
+            .collect::<Vec<_>>();
+        rest.sort_by_key(|stats| Reverse(stats.visits));
+        moves.extend(rest.iter().take(MAX_MOVES - 1).map(|stats| stats.mv));
+    }
+    let visits = |mv: u8| {
+        report
+            .moves
+            .iter()
+            .find(|stats| stats.mv == mv)
+            .map_or(1, |stats| u64::from(stats.visits).max(1))
+    };
+    let total: u64 = moves.iter().map(|&mv| visits(mv)).sum();
+    moves
+        .into_iter()
+        .map(|mv| BookMove {
+            mv,
+            weight: (visits(mv) * u64::from(u16::MAX) / total).max(1) as u16,
+        })
+        .collect()
+}
+
+/// Builds a book for every position up to `plies` moves from `root`, one
+/// search of `budget_ms` per symmetry class. Positions already decided and
+/// moves that lose on the spot are left out. `progress` is called after
+/// each search with the number of positions done and found so far.
+pub fn generate(
+    root: Position,
+    plies: u8,
+    searcher: Searcher,
+    budget_ms: f64,
+    strength: u8,
+    mut progress: impl FnMut(usize, usize),
+) -> Book {
+    let mut book = Book::new(root.board(), root.rules());
+    let mut seen = HashSet::from([canonical(root).0.hash]);
+    let mut queue = VecDeque::from([(root, 0u8)]);
+    let mut done = 0;
+    while let Some((pos, depth)) = queue.pop_front() {
+        // Swap decisions the swap table covers follow it, so the book and
+        // `Searcher::best_move` never disagree about swapping.
+        let moves = match swap::precomputed(pos) {
+            Some(advice) if advice.swap => vec![BookMove {
+                mv: pos.swap_move(),
+                weight: 1,
+            }],
+            Some(_) => book_moves(&searcher.search(swap::without_swap(pos), budget_ms, strength)),
+            None => book_moves(&searcher.search(pos, budget_ms, strength)),
+        };
+        book.insert(pos, &moves);
+        done += 1;
+
+        if depth + 1 < plies {
+            for mv in legal_moves_with_swap(pos) {
+                let Ok((next, jp)) = apply_move_with_meta(pos, mv) else {
+                    continue;
+                };
+                if matches!(outcome(next, jp), Outcome::Ongoing)
+                    && seen.insert(canonical(next).0.hash)
+                {
+                    queue.push_back((next, depth + 1));
+                }
+            }
+        }
+        progress(done, done + queue.len());
+    }
+    book
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::engine::{best_move_with_strength, MoveOptions, SWAP_MOVE};
+    use crate::geometry::Transform;
+
+    #[test]
+    fn round_trips_and_rejects_bad_data() {
+        let mut book = Book::new(Board::standard(), Rules::STANDARD);
+        let opened = Position::empty().apply(0).unwrap();
+        book.insert(
+            Position::empty(),
+            &[
+                BookMove { mv: 30, weight: 3 },
+                BookMove { mv: 29, weight: 1 },
+            ],
+        );
+        book.insert(
+            opened,
+            &[
+                BookMove {
+                    mv: SWAP_MOVE,
+                    weight: 1,
+                },
+                BookMove { mv: 30, weight: 1 },
+            ],
+        );
+        let bytes = book.to_bytes();
+        assert_eq!(bytes.len(), HEADER_LEN + 2 * (9 + 2 * 3));
+        assert_eq!(Book::from_bytes(&bytes).unwrap(), book);
+
+        assert_eq!(Book::from_bytes(b"YAVC"), Err(BookError::BadMagic));
+        let mut newer = bytes.clone();
+        newer[4] = 2;
+        assert_eq!(Book::from_bytes(&newer), Err(BookError::UnknownVersion(2)));
+        assert_eq!(
+            Book::from_bytes(&bytes[..bytes.len() - 1]),
+            Err(BookError::Truncated)
+        );
+        let mut swapped = bytes[..HEADER_LEN].to_vec();
+        swapped.extend_from_slice(&bytes[HEADER_LEN + 15..]);
+        swapped.extend_from_slice(&bytes[HEADER_LEN..HEADER_LEN + 15]);
+        assert_eq!(Book::from_bytes(&swapped), Err(BookError::Unsorted));
+
+        let mut weightless = bytes.clone();
+        weightless[HEADER_LEN + 10..HEADER_LEN + 12].fill(0);
+        assert_eq!(Book::from_bytes(&weightless), Err(BookError::EmptyEntry));
+        let mut moveless = bytes[..HEADER_LEN].to_vec();
+        moveless[HEADER_LEN - 4..].copy_from_slice(&1u32.to_le_bytes());
+        moveless.extend_from_slice(&[0; 9]);
+        assert_eq!(Book::from_bytes(&moveless), Err(BookError::EmptyEntry));
+    }
+
+    #[test]
+    fn looks_up_symmetric_positions() {
+        let board = Board::standard();
+        let mut book = Book::new(board, Rules::STANDARD);
+        // a1 answered by e4 and b2.
+        let pos = Position::empty().apply(0).unwrap().apply(29).unwrap();
+        book.insert(pos, &[BookMove { mv: 7, weight: 1 }]);
+        for t in Transform::all() {
+            let image = t.position(pos);
+            let moves = book.moves(image).unwrap();
+            assert_eq!(
+                moves,
+                [BookMove {
+                    mv: t.cell(board, 7),
+                    weight: 1
+                }]
+            );
+            assert_eq!(book.choose(image, 9), Some(t.cell(board, 7)));
+        }
+        assert_eq!(book.moves(Position::empty()), None);
+        let other_rules = pos.with_rules(Rules {
+            swap: false,
+            ..Rules::STANDARD
+        });
+        assert_eq!(book.moves(other_rules), None);
+    }
+
+    #[test]
+    fn generates_and_plays_from_a_book() {
+        let small = Position::empty_on(Board::with_radius(2).unwrap());
+        let mut searched = 0;
+        let book = generate(small, 2, Searcher::AlphaBeta, 5.0, 1, |done, _| {
+            searched = done
+        });
+        assert_eq!(book.len(), searched);
+        // The empty board and one position per distinct first move: the
+        // centre, a corner, an edge cell and a cell of the inner ring.
+        assert_eq!(book.len(), 1 + 4);
+        let first = book.choose(small, 1).unwrap();
+        assert!(book.moves(small.apply(first).unwrap()).is_some());
+        assert_eq!(Book::from_bytes(&book.to_bytes()).unwrap(), book);
+
+        let embedded = embedded();
+        assert!(embedded.covers(Position::empty()));
+        let opening = embedded.moves(Position::empty()).unwrap();
+        let played = best_move_with_strength(Position::empty(), 1.0, 0).unwrap();
+        assert!(opening.iter().any(|entry| entry.mv == played));
+        let seeded = MoveOptions {
+            seed: 7,
+            ..MoveOptions::default()
+        };
+        let again = || Searcher::Mcts.best_move_with(Position::empty(), 1.0, 0, seeded);
+        assert_eq!(again(), again());
+    }
+}
@@ -0,0 +1,228 @@
+//! Compact, URL-safe codes for whole games.
+//!
+//! A code is a bit string written in the base64url alphabet (`A-Z a-z 0-9 -
+//! _`, six bits per character, no padding). It starts with three header
+//! bytes:
+//!
+//! 1. the format version, currently 1;
+//! 2. the rules: bit 0 is swap, bit 1 is forced block and bits 2-7 the
+//!    opening distance (capped at 63, which already excludes every cell);
+//! 3. the board radius.
+//!
+//! Every move follows as a fixed-width index, the swap move included: six
+//! bits on boards of up to 63 cells (the standard board), seven on larger
+//! ones. The last character is padded with zero bits, always fewer than a
+//! move's width, so the number of moves follows from the length. A
+//! 40-move standard game is 44 characters.
+//!
+//! Decoding replays every move, so the restored `Game` has the full undo
+//! history and the right side to move after a swap.
+
+use crate::board::{Board, MAX_RADIUS, MIN_RADIUS};
+use crate::engine::Position;
+use crate::error::CodeError;
+use crate::game::Game;
+use crate::rules::Rules;
+
+pub const VERSION: u8 = 1;
+
+const ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789-_";
+const HEADER_BITS: usize = 24;
+
+/// Bits per move on `board`: enough for every cell and the swap move, and
+/// never fewer than six so padding cannot be mistaken for a move.
+fn move_bits(board: &Board) -> usize {
+    let values = board.cell_count() + 1;
+    (usize::BITS - (values - 1).leading_zeros()).max(6) as usize
+}
+
+pub(crate) fn rules_byte(rules: Rules) -> u8 {
+    u8::from(rules.swap)
+        | u8::from(rules.forced_block) << 1
+        | rules.first_move_min_distance.min(63) << 2
+}
+
+pub(crate) fn rules_from_byte(byte: u8) -> Rules {
+    Rules {
+        swap: byte & 1 != 0,
+        forced_block: byte & 2 != 0,
+        first_move_min_distance: byte >> 2,
+    }
+}
+
+/// The code for the moves played so far in `game`, or `None` if the game
+/// did not start from an empty board.
+pub fn encode_game(game: &Game) -> Option<String> {
+    let start = game.start();
+    if start.occupied() != 0 || start.turn != 0 {
+        return None;
+    }
+    let board = start.board();
+    let width = move_bits(board);
+    let mut bits = BitWriter::default();
+    bits.push(u32::from(VERSION), 8);
+    bits.push(u32::from(rules_byte(start.rules())), 8);
+    bits.push(u32::from(board.radius()), 8);
+    for &mv in game.moves() {
+        bits.push(u32::from(mv), width);
+    }
+    Some(bits.finish())
+}
+
+/// Restores a game from its code, with every move replayed and checked.
+pub fn decode_game(code: &str) -> Result<Game, CodeError> {
+    let symbols = code
+        .trim()
+        .chars()
+        .enumerate()
+        .map(|(position, found)| {
+            u8::try_from(found)
+                .ok()
+                .and_then(|byte| ALPHABET.iter().position(|&a| a == byte))
+                .map(|value| value as u32)
+                .ok_or(CodeError::BadCharacter { position, found })
+        })
+        .collect::<Result<Vec<u32>, CodeError>>()?;
+    let total_bits = symbols.len() * 6;
+    if total_bits < HEADER_BITS {
+        return Err(CodeError::Malformed);
+    }
+    let mut bits = BitReader {
+        symbols: &symbols,
+        at: 0,
+    };
+    let version = bits.take(8) as u8;
+    if version != VERSION {
+        return Err(CodeError::UnknownVersion(version));
+    }
+    let rules = rules_from_byte(bits.take(8) as u8);
+    let radius = bits.take(8) as u8;
+    if !(MIN_RADIUS..=MAX_RADIUS).contains(&radius) {
+        return Err(CodeError::Malformed);
+    }
+    let board = Board::with_radius(radius).ok_or(CodeError::Malformed)?;
+    let width = move_bits(board);
+
+    let mut game = Game::from_position(Position::empty_on(board).with_rules(rules));
+    for ply in 1..=(total_bits - HEADER_BITS) / width {
+        let mv = bits.take(width) as u8;
+        game.play(mv)
+            .map_err(|error| CodeError::IllegalMove { ply, error })?;
+    }
+    Ok(game)
+}
+
+#[derive(Default)]
+struct BitWriter {
+    out: String,
+    acc: u32,
+    len: usize,
+}
+
+impl BitWriter {
+    fn push(&mut self, value: u32, width: usize) {
+        for shift in (0..width).rev() {
+            self.acc = self.acc << 1 | (value >> shift) & 1;
+            self.len += 1;
+            if self.len == 6 {
+                self.out.push(ALPHABET[self.acc as usize] as char);
+                self.acc = 0;
+                self.len = 0;
+            }
+        }
+    }
+
+    fn finish(mut self) -> String {
+        if self.len > 0 {
+            let padding = 6 - self.len;
+            self.push(0, padding);
+        }
+        self.out
+    }
+}
+
+struct BitReader<'a> {
+    symbols: &'a [u32],
+    at: usize,
+}
+
+impl BitReader<'_> {
+    fn take(&mut self, width: usize) -> u32 {
+        let mut value = 0;
+        for _ in 0..width {
+            let symbol = self.symbols[self.at / 6];
+            value = value << 1 | (symbol >> (5 - self.at % 6)) & 1;
+            self.at += 1;
+        }
+        value
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::engine::SWAP_MOVE;
+    use crate::error::MoveError;
+
+    #[test]
+    fn one_character_per_move_on_the_standard_board() {
+        let mut game = Game::new();
+        assert_eq!(encode_game(&game).as_deref(), Some("AQEE"));
+        for mv in [30, SWAP_MOVE, 21, 0] {
+            game.play(mv).unwrap();
+        }
+        let code = encode_game(&game).unwrap();
+        assert_eq!(code.len(), 4 + 4);
+        let restored = decode_game(&code).unwrap();
+        assert_eq!(restored.moves(), game.moves());
+        assert_eq!(restored.position(), game.position());
+        assert_eq!(restored.position().turn, 0);
+        assert_eq!(restored.position().p1.count_ones(), 2);
+    }
+
+    #[test]
+    fn round_trips_rules_and_larger_boards() {
+        let rules = Rules {
+            swap: false,
+            forced_block: true,
+            first_move_min_distance: 2,
+        };
+        for radius in MIN_RADIUS..=MAX_RADIUS {
+            let board = Board::with_radius(radius).unwrap();
+            let mut game = Game::from_position(Position::empty_on(board).with_rules(rules));
+            let cells = board.cell_count() as u8;
+            for mv in [cells - 1, 0, cells / 2 + 1] {
+                game.play(mv).unwrap();
+            }
+            let restored = decode_game(&encode_game(&game).unwrap()).unwrap();
+            assert_eq!(restored.moves(), game.moves(), "radius {radius}");
+            assert_eq!(restored.position().rules(), rules);
+        }
+    }
+
+    #[test]
+    fn rejects_bad_codes() {
+        assert_eq!(
+            decode_game("AQE!").unwrap_err(),
+            CodeError::BadCharacter {
+                position: 3,
+                found: '!'
+            }
+        );
+        assert_eq!(decode_game("AQ").unwrap_err(), CodeError::Malformed);
+        assert_eq!(
+            decode_game("AgEE").unwrap_err(),
+            CodeError::UnknownVersion(2)
+        );
+        // e5 twice.
+        assert_eq!(
+            decode_game("AQEEee").unwrap_err(),
+            CodeError::IllegalMove {
+                ply: 2,
+                error: MoveError::Occupied(30)
+            }
+        );
+        let from_setup = Game::from_position(Position::empty().apply(30).unwrap());
+        assert_eq!(encode_game(&from_setup), None);
+    }
+}
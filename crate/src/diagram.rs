@@ -0,0 +1,389 @@
+//! Text diagrams of positions, for test failures and bug reports.
+//!
+//! Each column of the board (see `notation`) is drawn as one row, labelled
+//! with its letter and read left to right from cell 1, and rows are indented
+//! so that neighbouring cells touch as they do on the board. Cells with the
+//! same number lie on two diagonals that meet at the middle row; the number
+//! is written where each diagonal leaves the board, above the first row and
+//! below the last for the low numbers and after the shorter rows for the
+//! high ones:
+//!
+//! ```text
+//!       1 2 3 4 5
+//!     a . . . . . 6
+//!    b . . . . . . 7
+//!   c . . . . . . . 8
+//!  d . . . . . . . . 9
+//! e . . . .(x). . . .
+//!  f . . . . . . . . 9
+//!   g . . . . . . . 8
+//!    h . . . . . . 7
+//!     i . . . . . 6
+//!       1 2 3 4 5
+//! o to move, swap available; last move e5
+//! ```
+//!
+//! Stones are `x` for player 0 and `o` for player 1, written in capitals
+//! where they form a completed line; the last move is bracketed. The
+//! alternate form (`{:#}`) draws `●`/`○`, `◉`/`◎` for line stones and `·`
+//! for empty cells. The final line gives the side to move or the result.
+//!
+//! `parse_diagram` reads either form back. It takes the side to move from
+//! the final line when there is one, so a diagram after a swap round-trips,
+//! and otherwise infers it from the stone counts.
+
+use std::fmt;
+
+use crate::board::{bit, Bitboard, Board, MAX_RADIUS, MIN_RADIUS};
+use crate::engine::{outcome, DecidingRule, Outcome, Position};
+use crate::error::ParseError;
+use crate::notation;
+use crate::rules::Rules;
+
+/// A position drawn with an optional last move and result.
+#[derive(Clone, Copy, Debug)]
+pub struct Diagram {
+    pos: Position,
+    last_move: Option<u8>,
+    outcome: Option<Outcome>,
+}
+
+impl Diagram {
+    /// A diagram of `pos`; its result is found from the whole board unless
+    /// `outcome` supplies one.
+    pub fn new(pos: Position) -> Self {
+        Self {
+            pos,
+            last_move: None,
+            outcome: None,
+        }
+    }
+
+    /// Brackets `mv` as the move just played.
+    pub fn last_move(mut self, mv: u8) -> Self {
+        self.last_move = Some(mv);
+        self
+    }
+
+    pub fn outcome(mut self, outcome: Outcome) -> Self {
+        self.outcome = Some(outcome);
+        self
+    }
+}
+
+impl fmt::Display for Diagram {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let pos = self.pos;
+        let board = pos.board();
+        let result = self.outcome.unwrap_or_else(|| outcome(pos, None));
+        let highlighted: Bitboard = result
+            .lines()
+            .map_or(0, |lines| lines.iter().fold(0, |a, b| a | b));
+        // Empty, player 0, player 0 in a line, player 1, player 1 in a line.
+        let glyphs = if f.alternate() {
+            ['·', '●', '◉', '○', '◎']
+        } else {
+            ['.', 'x', 'X', 'o', 'O']
+        };
+        let radius = usize::from(board.radius());
+        let edge_numbers = |f: &mut fmt::Formatter<'_>| {
+            write!(f, "{:width$}", "", width = radius + 1)?;
+            for number in 1..=radius + 1 {
+                write!(f, "{number:>2}")?;
+            }
+            writeln!(f)
+        };
+
+        edge_numbers(f)?;
+        let mut idx = 0_u8;
+        for column in 0..2 * radius + 1 {
+            let letter = (b'a' + column as u8) as char;
+            write!(
+                f,
+                "{:indent$}{letter}",
+                "",
+                indent = column.abs_diff(radius)
+            )?;
+            let mut bracket_open = false;
+            for _ in 0..board.column_len(column) {
+                let is_last = self.last_move == Some(idx);
+                f.write_str(if is_last {
+                    "("
+                } else if bracket_open {
+                    ")"
+                } else {
+                    " "
+                })?;
+                bracket_open = is_last;
+                let cell = bit(idx);
+                let glyph = if pos.p0 & cell != 0 {
+                    1
+                } else if pos.p1 & cell != 0 {
+                    3
+                } else {
+                    0
+                };
+                let glyph = glyphs[glyph + usize::from(glyph > 0 && highlighted & cell != 0)];
+                write!(f, "{glyph}")?;
+                idx += 1;
+            }
+            let number = board.column_len(column) + 1;
+            match (column == radius, bracket_open) {
+                (true, true) => f.write_str(")")?,
+                (true, false) => {}
+                (false, true) => write!(f, "){number}")?,
+                (false, false) => write!(f, "{number:>2}")?,
+            }
+            writeln!(f)?;
+        }
+        edge_numbers(f)?;
+
+        match result {
+            Outcome::Ongoing => {
+                write!(f, "{} to move", player_name(pos.turn))?;
+                if pos.can_swap() {
+                    f.write_str(", swap available")?;
+                }
+            }
+            decided => write!(f, "{decided}")?,
+        }
+        if let Some(name) = self.last_move.and_then(|mv| notation::move_name(board, mv)) {
+            write!(f, "; last move {name}")?;
+        }
+        Ok(())
+    }
+}
+
+fn player_name(player: u8) -> char {
+    if player == 0 {
+        'x'
+    } else {
+        'o'
+    }
+}
+
+/// A diagram with the result found from the whole board; `{:#}` draws it
+/// with Unicode stones.
+impl fmt::Display for Position {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(&Diagram::new(*self), f)
+    }
+}
+
+/// The result in words, e.g. `x wins (four in a row)`.
+impl fmt::Display for Outcome {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match (*self, self.deciding_rule()) {
+            (Outcome::Win(player, _), Some(DecidingRule::FourDespiteThree)) => write!(
+                f,
+                "{} wins (four in a row, despite also making three)",
+                player_name(player)
+            ),
+            (Outcome::Win(player, _), _) => {
+                write!(f, "{} wins (four in a row)", player_name(player))
+            }
+            (Outcome::Lose(player, _), _) => {
+                write!(f, "{} loses (three in a row)", player_name(player))
+            }
+            (Outcome::Ongoing, _) => f.write_str("ongoing"),
+            (Outcome::Draw, _) => f.write_str("draw"),
+            (Outcome::Invalid, _) => f.write_str("invalid position"),
+        }
+    }
+}
+
+/// Reads a diagram under the standard rules.
+pub fn parse_diagram(text: &str) -> Result<Position, ParseError> {
+    parse_diagram_with(Rules::STANDARD, text)
+}
+
+/// Reads a diagram in either form; indentation, blank lines and the cell
+/// numbers are ignored, so diagrams can be written inline in tests.
+pub fn parse_diagram_with(rules: Rules, text: &str) -> Result<Position, ParseError> {
+    let mut lines = text.lines().map(str::trim).filter(|line| !line.is_empty());
+    let mut rows = Vec::new();
+    let mut status = None;
+    for line in lines.by_ref() {
+        if line.chars().all(|ch| ch.is_ascii_digit() || ch == ' ') {
+            continue;
+        }
+        // Past the last row of the largest board, only the status is left.
+        let expected = (rows.len() <= 2 * usize::from(MAX_RADIUS))
+            .then(|| char::from(b'a' + rows.len() as u8));
+        match expected.and_then(|letter| line.strip_prefix(letter)) {
+            Some(cells) if cells.starts_with([' ', '(']) => {
+                rows.push(cells.trim_end_matches(|ch: char| ch.is_ascii_digit()))
+            }
+            _ => {
+                status = Some(line);
+                break;
+            }
+        }
+    }
+    if lines.next().is_some() {
+        return Err(ParseError::Malformed);
+    }
+
+    let radius = u8::try_from(rows.len() / 2).map_err(|_| ParseError::Malformed)?;
+    if rows.len().is_multiple_of(2) || !(MIN_RADIUS..=MAX_RADIUS).contains(&radius) {
+        return Err(ParseError::Malformed);
+    }
+    let board = Board::with_radius(radius).expect("radius was checked");
+    let (mut p0, mut p1) = (0, 0);
+    let mut idx = 0_u8;
+    for (column, row) in rows.iter().enumerate() {
+        let glyphs: Vec<char> = row
+            .chars()
+            .filter(|ch| !matches!(ch, ' ' | '(' | ')'))
+            .collect();
+        if glyphs.len() != board.column_len(column) {
+            return Err(ParseError::Malformed);
+        }
+        for glyph in glyphs {
+            match glyph {
+                'x' | 'X' | '●' | '◉' => p0 |= bit(idx),
+                'o' | 'O' | '○' | '◎' => p1 |= bit(idx),
+                '.' | '·' => {}
+                _ => return Err(ParseError::Malformed),
+            }
+            idx += 1;
+        }
+    }
+
+    let (p0_count, p1_count) = (p0.count_ones(), p1.count_ones());
+    let stated_turn = status.and_then(|status| {
+        let mut words = status.split_whitespace();
+        let player = match words.next()? {
+            "x" => 0,
+            "o" => 1,
+            _ => return None,
+        };
+        match words.next()? {
+            "to" => Some(player),
+            "wins" | "loses" => Some(player ^ 1),
+            _ => None,
+        }
+    });
+    let turn = stated_turn.unwrap_or(if p0_count > p1_count { 1 } else { 0 });
+    let ply = (p0 | p1).count_ones() as u8;
+    let pos = Position::from_bitboards_on(board, p0, p1, turn, ply).with_rules(rules);
+    let unswapped = match turn {
+        0 => p0_count == p1_count,
+        _ => p0_count == p1_count + 1,
+    };
+    let swapped = pos.swap_used() && rules.swap;
+    if !unswapped && !swapped {
+        return Err(ParseError::Parity {
+            p0: p0_count,
+            p1: p1_count,
+        });
+    }
+    Ok(pos)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::engine::{apply_move_with_meta, best_move};
+    use crate::notation::parse_moves;
+
+    #[test]
+    fn draws_the_opening_and_reads_it_back() {
+        let pos = Position::empty().apply(30).unwrap();
+        let text = Diagram::new(pos).last_move(30).to_string();
+        assert_eq!(
+            text,
+            "      1 2 3 4 5\n    a . . . . . 6\n   b . . . . . . 7\n  c . . . . . . . 8\n\
+             \x20d . . . . . . . . 9\ne . . . .(x). . . .\n f . . . . . . . . 9\n\
+             \x20 g . . . . . . . 8\n   h . . . . . . 7\n    i . . . . . 6\n      1 2 3 4 5\n\
+             o to move, swap available; last move e5"
+        );
+        assert_eq!(parse_diagram(&text), Ok(pos));
+        assert_eq!(parse_diagram(&format!("{pos:#}")), Ok(pos));
+    }
+
+    #[test]
+    fn round_trips_swaps_and_larger_boards() {
+        let swapped = Position::empty().apply(30).unwrap().apply_swap().unwrap();
+        assert_eq!(parse_diagram(&swapped.to_string()), Ok(swapped));
+
+        let board = Board::with_radius(6).unwrap();
+        let pos = Position::empty_on(board)
+            .apply(126)
+            .unwrap()
+            .apply(0)
+            .unwrap();
+        assert_eq!(parse_diagram(&pos.to_string()), Ok(pos));
+        assert_eq!(parse_diagram(&format!("{pos:#}")), Ok(pos));
+        // The last cell of a row, bracketed, next to a two-digit number.
+        let text = Diagram::new(pos).last_move(56).to_string();
+        assert!(text.contains(" . .(.)13\n"), "{text}");
+        assert_eq!(parse_diagram(&text), Ok(pos));
+    }
+
+    #[test]
+    fn highlights_the_deciding_lines() {
+        let board = Board::standard();
+        let (mut pos, mut jp) = (Position::empty(), None);
+        for mv in parse_moves(board, "e1 a1 e2 a3 e4 i1 e5 i3 e3").unwrap() {
+            (pos, jp) = apply_move_with_meta(pos, mv).unwrap();
+        }
+        let text = Diagram::new(pos)
+            .last_move(28)
+            .outcome(outcome(pos, jp))
+            .to_string();
+        assert!(text.contains("e X X(X)X X . . . ."), "{text}");
+        assert!(
+            text.ends_with("x wins (four in a row); last move e3"),
+            "{text}"
+        );
+        assert_eq!(parse_diagram(&text), Ok(pos));
+    }
+
+    #[test]
+    fn tests_can_be_written_as_pictures() {
+        // o threatens to fill e1-e2-e3-e4; x has to block at e3.
+        let pos = parse_diagram(
+            "
+                a x . . . .
+               b . . . . . .
+              c . . . . . . .
+             d x . . . . . . .
+            e o o . o . . . . .
+             f . . . . . . . .
+              g . . . . . . .
+               h . . . . . .
+                i . . . . x
+            x to move
+            ",
+        )
+        .unwrap();
+        assert_eq!(
+            best_move(pos, 200.0),
+            notation::parse_cell(pos.board(), "e3").ok()
+        );
+    }
+
+    #[test]
+    fn rejects_malformed_diagrams() {
+        assert_eq!(parse_diagram(""), Err(ParseError::Malformed));
+        let short_row = Position::empty()
+            .to_string()
+            .replacen("a . . . . .", "a . . . .", 1);
+        assert_eq!(parse_diagram(&short_row), Err(ParseError::Malformed));
+        let parity = Position::empty()
+            .to_string()
+            .replacen("a .", "a o", 1)
+            .replace("x to move", "o to move");
+        assert_eq!(
+            parse_diagram(&parity),
+            Err(ParseError::Parity { p0: 0, p1: 1 })
+        );
+        // More rows than any board has, lettered on past `z`.
+        let rows: String = (0..200_u32)
+            .filter_map(|row| char::from_u32('a' as u32 + row))
+            .map(|letter| format!("{letter} .\n"))
+            .collect();
+        assert_eq!(parse_diagram(&rows), Err(ParseError::Malformed));
+    }
+}
@@ -0,0 +1,193 @@
+//! Exhaustive endgame solver.
+//!
+//! Once few cells are empty the whole game tree is small, and many of the
+//! remaining cells are poisoned (they would make three), so playouts are a
+//! poor guide while a full search is cheap. `search` plays every line out
+//! to the end with alpha-beta over win, draw and loss, remembering each
+//! position's bound so transpositions are searched once, and the move it
+//! picks never gives away a drawn or won ending.
+//!
+//! `engine::search` and `alphabeta::search` hand over to it once the board
+//! has `MAX_EMPTY_CELLS` or fewer empty cells, and mark their report as
+//! proven.
+
+use std::collections::HashMap;
+
+use crate::engine::{
+    apply_move_with_meta, candidate_moves, centered_moves, immediate_winning_moves,
+    legal_moves_with_swap, outcome, winner_from_outcome, Candidates, MoveStats, Outcome, Position,
+    SearchReport,
+};
+
+/// Empty cells at or below which the searches solve the position exactly.
+pub const MAX_EMPTY_CELLS: u32 = 16;
+
+#[derive(Clone, Copy)]
+enum Bound {
+    Exact,
+    Lower,
+    Upper,
+}
+
+struct Solver {
+    /// Zobrist key to a value for the side to move and what kind of bound
+    /// it is.
+    table: HashMap<u64, (i8, Bound)>,
+    nodes: u64,
+}
+
+impl Solver {
+    /// The value for the side to move: 1 for a forced win, 0 for a draw,
+    /// -1 for a loss. Only exact inside `(alpha, beta)`.
+    fn negamax(
+        &mut self,
+        pos: Position,
+        just_played: Option<(u8, u8)>,
+        mut alpha: i8,
+        beta: i8,
+    ) -> i8 {
+        self.nodes += 1;
+        let result = outcome(pos, just_played);
+        if let Some(winner) = winner_from_outcome(result) {
+            return if winner == pos.turn { 1 } else { -1 };
+        }
+        if !matches!(result, Outcome::Ongoing) {
+            return 0;
+        }
+        if !immediate_winning_moves(pos).is_empty() {
+            return 1;
+        }
+        let moves = match candidate_moves(pos) {
+            Candidates::LostIn(_) => return -1,
+            Candidates::Moves(moves) if moves.is_empty() => return 0,
+            Candidates::Moves(moves) => moves,
+        };
+
+        if let Some(&(value, bound)) = self.table.get(&pos.hash) {
+            match bound {
+                Bound::Exact => return value,
+                Bound::Lower if value >= beta => return value,
+                Bound::Upper if value <= alpha => return value,
+                _ => {}
+            }
+        }
+
+        let original_alpha = alpha;
+        let mut best = -1;
+        for mv in centered_moves(pos.board(), moves) {
+            let Ok((next, next_jp)) = apply_move_with_meta(pos, mv) else {
+                continue;
+            };
+            best = best.max(-self.negamax(next, next_jp, -beta, -alpha));
+            alpha = alpha.max(best);
+            if alpha >= beta {
+                break;
+            }
+        }
+        let bound = if best <= original_alpha {
+            Bound::Upper
+        } else if best >= beta {
+            Bound::Lower
+        } else {
+            Bound::Exact
+        };
+        self.table.insert(pos.hash, (best, bound));
+        best
+    }
+}
+
+/// Whether `pos` is small enough for `search` to be the automatic choice.
+pub fn in_range(pos: Position) -> bool {
+    (pos.board().mask() & !pos.occupied()).count_ones() <= MAX_EMPTY_CELLS
+}
+
+/// Solves `pos` to the end of the game. Every root move is given its exact
+/// value (1 win, 0 draw, -1 loss for the side to move), best first, and the
+/// report is marked proven. There is no time limit: within `in_range` a
+/// solve takes milliseconds, but far from the end it may not finish.
+pub fn search(pos: Position) -> SearchReport {
+    let legal = legal_moves_with_swap(pos);
+    let Some(fallback) = centered_moves(pos.board(), legal).next() else {
+        return SearchReport::unsearched(None, None);
+    };
+
+    let mut solver = Solver {
+        table: HashMap::new(),
+        nodes: 0,
+    };
+    let wins = immediate_winning_moves(pos);
+    let root_moves = match candidate_moves(pos) {
+        _ if !wins.is_empty() => wins,
+        Candidates::Moves(moves) if !moves.is_empty() => moves,
+        // Everything loses at once; any move will do.
+        _ => legal,
+    };
+    let mut moves: Vec<MoveStats> = centered_moves(pos.board(), root_moves)
+        .filter_map(|mv| {
+            let (next, next_jp) = apply_move_with_meta(pos, mv).ok()?;
+            let before = solver.nodes;
+            let value = -solver.negamax(next, next_jp, -1, 1);
+            Some(MoveStats {
+                mv,
+                visits: (solver.nodes - before).min(u64::from(u32::MAX)) as u32,
+                value: f64::from(value),
+            })
+        })
+        .collect();
+    // Stable, so equally good moves stay in centre-first order.
+    moves.sort_by(|a, b| b.value.total_cmp(&a.value));
+
+    let best = moves.first().copied();
+    SearchReport {
+        best_move: Some(best.map_or(fallback, |stats| stats.mv)),
+        iterations: solver.nodes.min(u64::from(u32::MAX)) as u32,
+        depth: 0,
+        value: Some(best.map_or(-1.0, |stats| stats.value)),
+        proven: true,
+        moves,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::board::Board;
+    use crate::engine::best_move_with_strength;
+    use crate::fixtures;
+    use crate::pns::{solve, Verdict};
+
+    #[test]
+    fn holds_a_drawn_ending() {
+        let pos = fixtures::drawn_ending();
+        assert!(in_range(pos));
+        let report = crate::engine::search(pos, 50.0, 0);
+        assert!(report.proven);
+        assert_eq!(report.value, Some(0.0));
+        assert!(report.moves.len() > 1, "{report:?}");
+        let mv = best_move_with_strength(pos, 50.0, 0).unwrap();
+        let (next, _) = apply_move_with_meta(pos, mv).unwrap();
+        assert_eq!(solve(next, 10_000).verdict, Verdict::Draw, "\n{next}");
+
+        let opening = crate::engine::search(Position::empty().apply(30).unwrap(), 20.0, 0);
+        assert!(!opening.proven);
+    }
+
+    #[test]
+    fn agrees_with_proof_number_search() {
+        let board = Board::with_radius(2).unwrap();
+        for (pos, jp) in fixtures::random_games(board, 5, 30, 6, 6) {
+            if !matches!(outcome(pos, jp), Outcome::Ongoing) {
+                continue;
+            }
+            let expected = match solve(pos, 100_000).verdict {
+                Verdict::Win => 1.0,
+                Verdict::Draw => 0.0,
+                Verdict::Loss => -1.0,
+                Verdict::Unknown => continue,
+            };
+            let report = search(pos);
+            assert_eq!(report.value, Some(expected), "\n{pos}");
+            assert_eq!(report.moves[0].mv, report.best_move.unwrap());
+        }
+    }
+}
@@ -1,77 +1,348 @@
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 use std::sync::OnceLock;
 #[cfg(not(target_arch = "wasm32"))]
 use std::time::Instant;
 
-const BOARD_RADIUS: i32 = 4;
-const BOARD_CELLS: usize = 61;
+use crate::alphabeta;
+use crate::board::{bit, Bitboard, Board, MAX_CELLS};
+use crate::book;
+use crate::endgame;
+use crate::error::{MoveError, ParseError};
+use crate::json;
+use crate::moveset::MoveSet;
+use crate::notation;
+use crate::rules::Rules;
+use crate::swap;
+use crate::threats;
+
+/// Cell count of the standard radius-4 board.
+pub const BOARD_CELLS: usize = 61;
+/// The swap move on the standard board; other boards use
+/// `Board::swap_move`.
 pub const SWAP_MOVE: u8 = BOARD_CELLS as u8;
-const DIRS: [(i32, i32); 6] = [(1, 0), (0, 1), (-1, 1), (-1, 0), (0, -1), (1, -1)];
-const LINE_DIRS: [(i32, i32); 3] = [(1, 0), (0, 1), (1, -1)];
+pub(crate) const DIRS: [(i32, i32); 6] = [(1, 0), (0, 1), (-1, 1), (-1, 0), (0, -1), (1, -1)];
+
+pub(crate) const fn splitmix64(state: u64) -> (u64, u64) {
+    let state = state.wrapping_add(0x9e3779b97f4a7c15);
+    let mut z = state;
+    z = (z ^ (z >> 30)).wrapping_mul(0xbf58476d1ce4e5b9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94d049bb133111eb);
+    (state, z ^ (z >> 31))
+}
+
+const fn zobrist_stone_keys() -> [[u64; MAX_CELLS]; 2] {
+    let mut keys = [[0_u64; MAX_CELLS]; 2];
+    let mut state = 0x59a7_a1a7_0000_0001_u64;
+    let mut player = 0;
+    while player < 2 {
+        let mut idx = 0;
+        while idx < MAX_CELLS {
+            let (next_state, key) = splitmix64(state);
+            state = next_state;
+            keys[player][idx] = key;
+            idx += 1;
+        }
+        player += 1;
+    }
+    keys
+}
+
+const ZOBRIST_STONES: [[u64; MAX_CELLS]; 2] = zobrist_stone_keys();
+const ZOBRIST_TURN: u64 = splitmix64(0x7475_726e).1;
+const ZOBRIST_SWAP: u64 = splitmix64(0x7377_6170).1;
 
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub enum Outcome {
     Ongoing,
     Draw,
-    Win(u8, [u8; 4]),
-    Lose(u8, [u8; 3]),
+    Win(u8, Lines),
+    Lose(u8, Lines),
     Invalid,
 }
 
-#[derive(Serialize)]
-struct OutcomeResponse {
-    state: &'static str,
-    winner: Option<u8>,
-    loser: Option<u8>,
-    line: Vec<u8>,
+impl Outcome {
+    /// Every line the deciding player completed, for a decided game.
+    pub fn lines(&self) -> Option<Lines> {
+        match *self {
+            Self::Win(_, lines) | Self::Lose(_, lines) => Some(lines),
+            _ => None,
+        }
+    }
+
+    /// Which rule decided the game, for a decided game.
+    pub fn deciding_rule(&self) -> Option<DecidingRule> {
+        match *self {
+            Self::Win(_, lines) if lines.has_three() => Some(DecidingRule::FourDespiteThree),
+            Self::Win(..) => Some(DecidingRule::Four),
+            Self::Lose(..) => Some(DecidingRule::Three),
+            _ => None,
+        }
+    }
+}
+
+/// The rule that ended a game.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum DecidingRule {
+    /// Four or more in a row wins.
+    Four,
+    /// The same move also made exactly three in a row, but four takes
+    /// precedence and the game is still a win.
+    FourDespiteThree,
+    /// Exactly three in a row loses.
+    Three,
+}
+
+impl DecidingRule {
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Four => "four",
+            Self::FourDespiteThree => "four-despite-three",
+            Self::Three => "three",
+        }
+    }
+}
+
+/// The lines of three or more in a row that ended a game, one bitboard per
+/// maximal run. Lines through a single last move lie on different axes, so
+/// there are at most three; a whole-board check of a position that fails
+/// `validate` may find more, and keeps only the first three.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Lines {
+    runs: [Bitboard; 3],
+    len: u8,
+}
+
+impl Lines {
+    /// Lines from their bitboards, keeping at most three.
+    pub(crate) fn from_runs(runs: impl IntoIterator<Item = Bitboard>) -> Self {
+        let mut lines = Self::default();
+        for run in runs.into_iter().take(lines.runs.len()) {
+            lines.runs[lines.len as usize] = run;
+            lines.len += 1;
+        }
+        lines
+    }
+
+    /// Adds a completed three-cell window, joining it to the run it extends.
+    /// Windows on one axis overlap in two cells; lines on different axes
+    /// share at most one.
+    fn add_window(&mut self, window: Bitboard) {
+        let mut merged = window;
+        let mut kept = 0;
+        for i in 0..self.len as usize {
+            let run = self.runs[i];
+            if (run & window).count_ones() >= 2 {
+                merged |= run;
+            } else {
+                self.runs[kept] = run;
+                kept += 1;
+            }
+        }
+        self.len = kept as u8;
+        if kept < self.runs.len() {
+            self.runs[kept] = merged;
+            self.len += 1;
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.len as usize
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = Bitboard> + '_ {
+        self.runs[..self.len as usize].iter().copied()
+    }
+
+    /// Whether any line is four or longer.
+    pub fn has_four(&self) -> bool {
+        self.iter().any(|run| run.count_ones() >= 4)
+    }
+
+    /// Whether any line is exactly three long.
+    pub fn has_three(&self) -> bool {
+        self.iter().any(|run| run.count_ones() == 3)
+    }
+
+    /// The cells of each line, in index order.
+    pub fn cells(&self) -> Vec<Vec<u8>> {
+        self.iter().map(bit_indices).collect()
+    }
+}
+
+pub(crate) fn bit_indices(mut bits: Bitboard) -> Vec<u8> {
+    let mut cells = Vec::with_capacity(bits.count_ones() as usize);
+    while bits != 0 {
+        cells.push(bits.trailing_zeros() as u8);
+        bits &= bits - 1;
+    }
+    cells
 }
 
-#[derive(Clone, Copy, Debug)]
+#[derive(Clone, Copy, PartialEq, Eq)]
 pub struct Position {
-    pub p0: u64,
-    pub p1: u64,
+    pub p0: Bitboard,
+    pub p1: Bitboard,
     pub turn: u8,
     pub ply: u8,
+    /// Zobrist key over stones, side to move and swap availability, kept
+    /// up to date incrementally by `apply`/`apply_swap`.
+    pub hash: u64,
+    board: &'static Board,
+    rules: Rules,
 }
 
 impl Position {
-    #[cfg(any(test, target_arch = "wasm32"))]
+    /// An empty standard board.
     pub fn empty() -> Self {
-        Self {
-            p0: 0,
-            p1: 0,
-            turn: 0,
-            ply: 0,
+        Self::empty_on(Board::standard())
+    }
+
+    pub fn empty_on(board: &'static Board) -> Self {
+        Self::from_bitboards_on(board, 0, 0, 0, 0)
+    }
+
+    /// A position on the standard board.
+    pub fn from_bitboards(p0: Bitboard, p1: Bitboard, turn: u8, ply: u8) -> Self {
+        Self::from_bitboards_on(Board::standard(), p0, p1, turn, ply)
+    }
+
+    pub fn from_bitboards_on(
+        board: &'static Board,
+        p0: Bitboard,
+        p1: Bitboard,
+        turn: u8,
+        ply: u8,
+    ) -> Self {
+        let mut pos = Self {
+            p0,
+            p1,
+            turn,
+            ply,
+            hash: 0,
+            board,
+            rules: Rules::STANDARD,
+        };
+        pos.hash = pos.zobrist();
+        pos
+    }
+
+    /// The same stones played under `rules`.
+    pub fn with_rules(mut self, rules: Rules) -> Self {
+        self.rules = rules;
+        self.hash = self.zobrist();
+        self
+    }
+
+    pub fn board(self) -> &'static Board {
+        self.board
+    }
+
+    pub fn rules(self) -> Rules {
+        self.rules
+    }
+
+    /// Computes the Zobrist key from scratch.
+    pub fn zobrist(self) -> u64 {
+        let mut hash = self.swap_key();
+        if self.turn == 1 {
+            hash ^= ZOBRIST_TURN;
         }
+        for (player, bits) in [self.p0, self.p1].into_iter().enumerate() {
+            let mut rest = bits;
+            while rest != 0 {
+                let idx = rest.trailing_zeros() as usize;
+                hash ^= ZOBRIST_STONES[player][idx];
+                rest &= rest - 1;
+            }
+        }
+        hash
     }
 
-    pub fn occupied(self) -> u64 {
+    fn swap_key(self) -> u64 {
+        if self.can_swap() {
+            ZOBRIST_SWAP
+        } else {
+            0
+        }
+    }
+
+    pub fn occupied(self) -> Bitboard {
         self.p0 | self.p1
     }
 
-    pub fn legal_moves(self) -> Vec<u8> {
-        let mut moves = Vec::with_capacity(BOARD_CELLS);
-        let occupied = self.occupied();
-        for idx in 0..BOARD_CELLS as u8 {
-            if occupied & (1_u64 << idx) == 0 {
-                moves.push(idx);
+    /// The move index that means "swap" on this position's board.
+    pub fn swap_move(self) -> u8 {
+        self.board.swap_move()
+    }
+
+    /// Empty cells, minus whatever the rules forbid: cells too close to the
+    /// centre for the opening stone, and anything but a block (or a win)
+    /// when the forced-block rule applies.
+    pub fn legal_moves(self) -> MoveSet {
+        let mut cells = !self.occupied() & self.board.mask();
+        if self.ply == 0 && self.rules.first_move_min_distance > 0 {
+            cells &= !self
+                .board
+                .cells_within(self.rules.first_move_min_distance - 1);
+        }
+        if self.rules.forced_block {
+            let completes_four = |bits: Bitboard, mv: u8| {
+                has_line4_from_move(self.board, bits | bit(mv), mv).is_some()
+            };
+            let opponent = self.stones(self.turn ^ 1);
+            let threats = MoveSet::from_cells(self.swap_move(), cells)
+                .filter(|mv| completes_four(opponent, mv))
+                .cells();
+            if threats != 0 {
+                let ours = self.stones(self.turn);
+                let wins = MoveSet::from_cells(self.swap_move(), cells)
+                    .filter(|mv| completes_four(ours, mv))
+                    .cells();
+                cells &= threats | wins;
             }
         }
-        moves
+        MoveSet::from_cells(self.swap_move(), cells)
+    }
+
+    /// Whether the second player took over the opening stone. This follows
+    /// from the stone counts and side to move: after a swap, player 1 is one
+    /// stone ahead on player 0's turn, and level on their own.
+    pub fn swap_used(self) -> bool {
+        let (p0, p1) = (self.p0.count_ones(), self.p1.count_ones());
+        if self.turn == 0 {
+            p1 == p0 + 1
+        } else {
+            p0 == p1 && p0 > 0
+        }
     }
 
     pub fn can_swap(self) -> bool {
-        self.ply == 1 && self.turn == 1
+        self.rules.swap && self.ply == 1 && self.turn == 1
     }
 
-    pub fn apply(self, mv: u8) -> Option<Self> {
-        if mv as usize >= BOARD_CELLS {
-            return None;
+    /// Why `mv` is not in `legal_moves_with_swap`.
+    fn move_error(self, mv: u8) -> MoveError {
+        if mv == self.swap_move() {
+            MoveError::SwapNotAllowed
+        } else if mv > self.swap_move() {
+            MoveError::OffBoard(mv)
+        } else if self.occupied() & bit(mv) != 0 {
+            MoveError::Occupied(mv)
+        } else {
+            MoveError::Forbidden(mv)
         }
-        let mask = 1_u64 << mv;
-        if self.occupied() & mask != 0 {
-            return None;
+    }
+
+    pub fn apply(self, mv: u8) -> Result<Self, MoveError> {
+        if !self.legal_moves().contains(mv) {
+            return Err(self.move_error(mv));
         }
+        let mask = bit(mv);
         let mut next = self;
         if self.turn == 0 {
             next.p0 |= mask;
@@ -80,20 +351,25 @@ impl Position {
         }
         next.turn ^= 1;
         next.ply += 1;
-        Some(next)
+        next.hash ^= ZOBRIST_STONES[self.turn as usize][mv as usize]
+            ^ ZOBRIST_TURN
+            ^ self.swap_key()
+            ^ next.swap_key();
+        Ok(next)
     }
 
-    pub fn apply_swap(self) -> Option<Self> {
+    pub fn apply_swap(self) -> Result<Self, MoveError> {
         if !self.can_swap() {
-            return None;
+            return Err(MoveError::SwapNotAllowed);
         }
         let mut next = self;
         std::mem::swap(&mut next.p0, &mut next.p1);
         next.turn ^= 1;
-        Some(next)
+        next.hash = next.zobrist();
+        Ok(next)
     }
 
-    pub fn stones(self, player: u8) -> u64 {
+    pub fn stones(self, player: u8) -> Bitboard {
         if player == 0 {
             self.p0
         } else {
@@ -102,220 +378,133 @@ impl Position {
     }
 }
 
-fn axial_cells() -> Vec<(i32, i32)> {
-    let mut cells = Vec::with_capacity(BOARD_CELLS);
-    for q in -BOARD_RADIUS..=BOARD_RADIUS {
-        let r_min = (-BOARD_RADIUS).max(-q - BOARD_RADIUS);
-        let r_max = BOARD_RADIUS.min(-q + BOARD_RADIUS);
-        for r in r_min..=r_max {
-            cells.push((q, r));
-        }
-    }
-    cells
-}
-
-fn idx_maps() -> (Vec<(i32, i32)>, std::collections::HashMap<(i32, i32), u8>) {
-    let cells = axial_cells();
-    let mut map = std::collections::HashMap::new();
-    for (idx, c) in cells.iter().enumerate() {
-        map.insert(*c, idx as u8);
-    }
-    (cells, map)
-}
-
-fn all_three_lines() -> Vec<[u8; 3]> {
-    let (cells, map) = idx_maps();
-    let mut lines = Vec::new();
-    for &(q, r) in &cells {
-        for &(dq, dr) in &LINE_DIRS {
-            let s0 = (q, r);
-            let s1 = (q + dq, r + dr);
-            let s2 = (q + 2 * dq, r + 2 * dr);
-            if let (Some(&a), Some(&b), Some(&c)) = (map.get(&s0), map.get(&s1), map.get(&s2)) {
-                lines.push([a, b, c]);
-            }
-        }
+/// Lists stones by cell name (see `notation`) rather than as raw bitboards.
+impl std::fmt::Debug for Position {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let names = |bits: Bitboard| {
+            let cells = MoveSet::from_cells(self.swap_move(), bits).to_vec();
+            format!("[{}]", notation::moves_name(self.board, &cells))
+        };
+        f.debug_struct("Position")
+            .field("radius", &self.board.radius())
+            .field("p0", &format_args!("{}", names(self.p0)))
+            .field("p1", &format_args!("{}", names(self.p1)))
+            .field("turn", &self.turn)
+            .field("ply", &self.ply)
+            .field("rules", &format_args!("{}", self.rules))
+            .finish()
     }
-    lines.sort_unstable();
-    lines.dedup();
-    lines
 }
 
-fn all_four_lines() -> Vec<[u8; 4]> {
-    let (cells, map) = idx_maps();
-    let mut lines = Vec::new();
-    for &(q, r) in &cells {
-        for &(dq, dr) in &LINE_DIRS {
-            let s0 = (q, r);
-            let s1 = (q + dq, r + dr);
-            let s2 = (q + 2 * dq, r + 2 * dr);
-            let s3 = (q + 3 * dq, r + 3 * dr);
-            if let (Some(&a), Some(&b), Some(&c), Some(&d)) =
-                (map.get(&s0), map.get(&s1), map.get(&s2), map.get(&s3))
-            {
-                lines.push([a, b, c, d]);
-            }
-        }
-    }
-    lines.sort_unstable();
-    lines.dedup();
-    lines
+pub(crate) fn has_line3(board: &Board, bits: Bitboard) -> Option<[u8; 3]> {
+    board
+        .three_line_masks()
+        .iter()
+        .position(|&mask| bits & mask == mask)
+        .map(|i| board.three_lines()[i])
 }
 
-fn three_lines() -> &'static [[u8; 3]] {
-    static THREE_LINES: OnceLock<Vec<[u8; 3]>> = OnceLock::new();
-    THREE_LINES.get_or_init(all_three_lines).as_slice()
+pub(crate) fn has_line4(board: &Board, bits: Bitboard) -> Option<[u8; 4]> {
+    board
+        .four_line_masks()
+        .iter()
+        .position(|&mask| bits & mask == mask)
+        .map(|i| board.four_lines()[i])
 }
 
-fn four_lines() -> &'static [[u8; 4]] {
-    static FOUR_LINES: OnceLock<Vec<[u8; 4]>> = OnceLock::new();
-    FOUR_LINES.get_or_init(all_four_lines).as_slice()
+pub(crate) fn has_line4_from_move(board: &Board, bits: Bitboard, mv: u8) -> Option<[u8; 4]> {
+    let (masks, lines) = board.win_checks(mv);
+    masks
+        .iter()
+        .position(|&mask| bits & mask == mask)
+        .map(|slot| lines[slot])
 }
 
-fn stepped_neighbors() -> &'static [[[Option<u8>; 3]; 6]; BOARD_CELLS] {
-    static STEPPED_NEIGHBORS: OnceLock<[[[Option<u8>; 3]; 6]; BOARD_CELLS]> = OnceLock::new();
-    STEPPED_NEIGHBORS.get_or_init(|| {
-        let (cells, map) = idx_maps();
-        let mut stepped = [[[(None); 3]; 6]; BOARD_CELLS];
-        for (idx, &(q, r)) in cells.iter().enumerate() {
-            for (dir_idx, &(dq, dr)) in DIRS.iter().enumerate() {
-                for step in 1..=3 {
-                    let n = (q + dq * step, r + dr * step);
-                    stepped[idx][dir_idx][(step - 1) as usize] = map.get(&n).copied();
-                }
-            }
-        }
-        stepped
-    })
+pub(crate) fn has_line3_from_move(board: &Board, bits: Bitboard, mv: u8) -> Option<[u8; 3]> {
+    let (masks, lines) = board.lose_checks(mv);
+    masks
+        .iter()
+        .position(|&mask| bits & mask == mask)
+        .map(|slot| lines[slot])
 }
 
-fn has_line3(bits: u64) -> Option<[u8; 3]> {
-    for &l in three_lines() {
-        if l.iter().all(|&i| bits & (1_u64 << i) != 0) {
-            return Some(l);
+/// Every line of three or more through `mv` in `bits`.
+fn lines_through(board: &Board, bits: Bitboard, mv: u8) -> Lines {
+    let mut lines = Lines::default();
+    for &mask in board.lose_checks(mv).0 {
+        if bits & mask == mask {
+            lines.add_window(mask);
         }
     }
-    None
-}
-
-fn has_line4(bits: u64) -> Option<[u8; 4]> {
-    for &l in four_lines() {
-        if l.iter().all(|&i| bits & (1_u64 << i) != 0) {
-            return Some(l);
-        }
+    // The windows through `mv` reach two cells either side of it; a longer
+    // run continues past them.
+    for run in &mut lines.runs[..lines.len as usize] {
+        *run = extend_run(board, bits, *run);
     }
-    None
+    lines
 }
 
-fn contiguous_in_direction(bits: u64, from: u8, dir_idx: usize) -> Vec<u8> {
-    let mut run = Vec::with_capacity(3);
-    for maybe_idx in stepped_neighbors()[from as usize][dir_idx] {
-        let Some(idx) = maybe_idx else { break };
-        if bits & (1_u64 << idx) == 0 {
-            break;
+fn extend_run(board: &Board, bits: Bitboard, mut run: Bitboard) -> Bitboard {
+    let mut frontier = run;
+    while frontier != 0 {
+        let cell = frontier.trailing_zeros() as u8;
+        frontier &= frontier - 1;
+        for &mask in board.lose_checks(cell).0 {
+            if bits & mask == mask && mask & !run != 0 && (mask & run).count_ones() >= 2 {
+                frontier |= mask & !run;
+                run |= mask;
+            }
         }
-        run.push(idx);
     }
     run
 }
 
-fn window_containing(chain: &[u8], center_idx: usize, width: usize) -> Option<&[u8]> {
-    if chain.len() < width {
-        return None;
-    }
-    let start = center_idx.saturating_sub(width - 1);
-    let max_start = center_idx.min(chain.len() - width);
-    let start = start.min(max_start);
-    Some(&chain[start..start + width])
-}
-
-fn has_line4_from_move(bits: u64, mv: u8) -> Option<[u8; 4]> {
-    for &(dir_pos, dir_neg) in &[(0_usize, 3_usize), (1, 4), (2, 5)] {
-        let mut negative = contiguous_in_direction(bits, mv, dir_neg);
-        let positive = contiguous_in_direction(bits, mv, dir_pos);
-        if negative.len() + 1 + positive.len() < 4 {
-            continue;
-        }
-        negative.reverse();
-        let center = negative.len();
-        let mut chain = Vec::with_capacity(negative.len() + 1 + positive.len());
-        chain.extend(negative);
-        chain.push(mv);
-        chain.extend(positive);
-        if let Some(line) = window_containing(&chain, center, 4) {
-            return line.try_into().ok();
+/// Every line of three or more anywhere in `bits`.
+fn lines_in(board: &Board, bits: Bitboard) -> Lines {
+    let mut lines = Lines::default();
+    for &mask in board.three_line_masks() {
+        if bits & mask == mask {
+            lines.add_window(mask);
         }
     }
-    None
+    lines
 }
 
-fn has_line3_from_move(bits: u64, mv: u8) -> Option<[u8; 3]> {
-    for &(dir_pos, dir_neg) in &[(0_usize, 3_usize), (1, 4), (2, 5)] {
-        let mut negative = contiguous_in_direction(bits, mv, dir_neg);
-        let positive = contiguous_in_direction(bits, mv, dir_pos);
-        if negative.len() + 1 + positive.len() < 3 {
-            continue;
-        }
-        negative.reverse();
-        let center = negative.len();
-        let mut chain = Vec::with_capacity(negative.len() + 1 + positive.len());
-        chain.extend(negative);
-        chain.push(mv);
-        chain.extend(positive);
-        if let Some(line) = window_containing(&chain, center, 3) {
-            return line.try_into().ok();
-        }
+/// Four or more wins even if the same move also makes three.
+fn decided(player: u8, lines: Lines) -> Option<Outcome> {
+    if lines.has_four() {
+        Some(Outcome::Win(player, lines))
+    } else if !lines.is_empty() {
+        Some(Outcome::Lose(player, lines))
+    } else {
+        None
     }
-    None
 }
 
 pub fn outcome(pos: Position, just_played: Option<(u8, u8)>) -> Outcome {
-    if let Some((player, last_move)) = just_played {
-        let bits = pos.stones(player);
-        if let Some(line4) = has_line4_from_move(bits, last_move) {
-            return Outcome::Win(player, line4);
-        }
-        if let Some(line3) = has_line3_from_move(bits, last_move) {
-            return Outcome::Lose(player, line3);
-        }
+    let board = pos.board;
+    let result = if let Some((player, last_move)) = just_played {
+        decided(player, lines_through(board, pos.stones(player), last_move))
     } else {
-        if let Some(line4) = has_line4(pos.p0) {
-            return Outcome::Win(0, line4);
-        }
-        if let Some(line3) = has_line3(pos.p0) {
-            return Outcome::Lose(0, line3);
-        }
-        if let Some(line4) = has_line4(pos.p1) {
-            return Outcome::Win(1, line4);
-        }
-        if let Some(line3) = has_line3(pos.p1) {
-            return Outcome::Lose(1, line3);
-        }
+        decided(0, lines_in(board, pos.p0)).or_else(|| decided(1, lines_in(board, pos.p1)))
+    };
+    if let Some(result) = result {
+        return result;
     }
-    if pos.ply as usize >= BOARD_CELLS {
+    if pos.ply as usize >= board.cell_count() {
         return Outcome::Draw;
     }
     Outcome::Ongoing
 }
 
-fn center_distances() -> &'static [f64; BOARD_CELLS] {
-    static CENTER_DISTANCES: OnceLock<[f64; BOARD_CELLS]> = OnceLock::new();
-    CENTER_DISTANCES.get_or_init(|| {
-        let mut distances = [0.0; BOARD_CELLS];
-        for (idx, (q, r)) in axial_cells().into_iter().enumerate() {
-            let x = f64::from(q) + f64::from(r) / 2.0;
-            let y = f64::from(r) * (3f64.sqrt() / 2.0);
-            distances[idx] = (x * x + y * y).sqrt();
-        }
-        distances
-    })
-}
-
-fn distance_to_center(idx: u8) -> f64 {
-    if idx as usize >= BOARD_CELLS {
-        return f64::INFINITY;
-    }
-    center_distances()[idx as usize]
+/// Iterates `moves` from the centre outwards, with the swap move last.
+pub(crate) fn centered_moves(board: &'static Board, moves: MoveSet) -> impl Iterator<Item = u8> {
+    board
+        .center_order()
+        .iter()
+        .copied()
+        .filter(move |&mv| moves.contains(mv))
+        .chain(moves.has_swap().then_some(board.swap_move()))
 }
 
 #[derive(Clone, Copy)]
@@ -352,25 +541,24 @@ impl SearchConfig {
     }
 }
 
-fn legal_moves_with_swap(pos: Position) -> Vec<u8> {
-    let mut moves = pos.legal_moves();
-    if pos.can_swap() {
-        moves.push(SWAP_MOVE);
-    }
-    moves
+pub fn legal_moves_with_swap(pos: Position) -> MoveSet {
+    MoveSet::new(pos.swap_move(), pos.legal_moves().cells(), pos.can_swap())
 }
 
-fn apply_move_with_meta(pos: Position, mv: u8) -> Option<(Position, Option<(u8, u8)>)> {
-    if mv == SWAP_MOVE {
+pub fn apply_move_with_meta(
+    pos: Position,
+    mv: u8,
+) -> Result<(Position, Option<(u8, u8)>), MoveError> {
+    if mv == pos.swap_move() {
         let next = pos.apply_swap()?;
-        Some((next, None))
+        Ok((next, None))
     } else {
         let next = pos.apply(mv)?;
-        Some((next, Some((pos.turn, mv))))
+        Ok((next, Some((pos.turn, mv))))
     }
 }
 
-fn winner_from_outcome(result: Outcome) -> Option<u8> {
+pub(crate) fn winner_from_outcome(result: Outcome) -> Option<u8> {
     match result {
         Outcome::Win(player, _) => Some(player),
         Outcome::Lose(player, _) => Some(player ^ 1),
@@ -378,81 +566,86 @@ fn winner_from_outcome(result: Outcome) -> Option<u8> {
     }
 }
 
-fn losing_player_from_outcome(result: Outcome) -> Option<u8> {
-    match result {
-        Outcome::Lose(player, _) => Some(player),
-        _ => None,
-    }
+/// Cells where the side to move completes four. Swap never wins, since it
+/// leaves a single stone on the board.
+pub(crate) fn immediate_winning_moves(pos: Position) -> MoveSet {
+    let bits = pos.stones(pos.turn);
+    pos.legal_moves()
+        .filter(|mv| has_line4_from_move(pos.board, bits | bit(mv), mv).is_some())
 }
 
-fn immediate_winning_moves(pos: Position) -> Vec<u8> {
-    let us = pos.turn;
-    legal_moves_with_swap(pos)
-        .into_iter()
-        .filter(|&mv| {
-            let Some((next, jp)) = apply_move_with_meta(pos, mv) else {
-                return false;
-            };
-            matches!(outcome(next, jp), Outcome::Win(w, _) if w == us)
-        })
-        .collect()
+/// Cells where the side to move makes three without also making four.
+pub(crate) fn immediate_losing_moves(pos: Position) -> MoveSet {
+    let bits = pos.stones(pos.turn);
+    pos.legal_moves().filter(|mv| {
+        let bits = bits | bit(mv);
+        has_line4_from_move(pos.board, bits, mv).is_none()
+            && has_line3_from_move(pos.board, bits, mv).is_some()
+    })
 }
 
-fn immediate_losing_moves(pos: Position) -> Vec<u8> {
-    let us = pos.turn;
-    legal_moves_with_swap(pos)
-        .into_iter()
-        .filter(|&mv| {
-            let Some((next, jp)) = apply_move_with_meta(pos, mv) else {
-                return false;
-            };
-            matches!(outcome(next, jp), Outcome::Lose(l, _) if l == us)
-        })
-        .collect()
+fn has_immediate_winning_reply(pos: Position) -> bool {
+    let bits = pos.stones(pos.turn);
+    pos.legal_moves()
+        .iter()
+        .any(|mv| has_line4_from_move(pos.board, bits | bit(mv), mv).is_some())
 }
 
-fn has_immediate_winning_reply(pos: Position) -> bool {
-    let player = pos.turn;
-    for mv in legal_moves_with_swap(pos) {
-        let Some((next, jp)) = apply_move_with_meta(pos, mv) else {
-            continue;
-        };
-        if matches!(outcome(next, jp), Outcome::Win(w, _) if w == player) {
-            return true;
-        }
-    }
-    false
+pub(crate) fn one_ply_safe_moves(pos: Position) -> MoveSet {
+    let losing = immediate_losing_moves(pos);
+    (legal_moves_with_swap(pos) - losing).filter(|mv| {
+        apply_move_with_meta(pos, mv).is_ok_and(|(next, _)| !has_immediate_winning_reply(next))
+    })
 }
 
-fn one_ply_safe_moves(pos: Position) -> Vec<u8> {
-    let all = legal_moves_with_swap(pos);
-    let us = pos.turn;
-    all.into_iter()
-        .filter(|&mv| {
-            let Some((next, jp)) = apply_move_with_meta(pos, mv) else {
-                return false;
-            };
-            if matches!(outcome(next, jp), Outcome::Lose(l, _) if l == us) {
-                return false;
+/// What is left to try at a node once the tactics have been applied.
+pub(crate) enum Candidates {
+    Moves(MoveSet),
+    /// Every move loses within this many plies.
+    LostIn(i32),
+}
+
+/// The moves worth searching once immediate wins have been ruled out:
+/// those that neither make three nor leave the opponent a four. Cheaper
+/// than `one_ply_safe_moves`, as the opponent's winning cells are found
+/// once rather than after every move.
+pub(crate) fn candidate_moves(pos: Position) -> Candidates {
+    let moves = legal_moves_with_swap(pos) - immediate_losing_moves(pos);
+    if moves.is_empty() && !pos.legal_moves().is_empty() {
+        return Candidates::LostIn(1);
+    }
+    let opponent = pos.stones(pos.turn ^ 1);
+    let threats: Bitboard =
+        MoveSet::from_cells(pos.swap_move(), !pos.occupied() & pos.board().mask())
+            .filter(|mv| has_line4_from_move(pos.board(), opponent | bit(mv), mv).is_some())
+            .cells();
+    match threats.count_ones() {
+        0 => Candidates::Moves(moves),
+        1 => {
+            let blocks = moves.filter(|mv| threats & bit(mv) != 0);
+            if blocks.is_empty() {
+                Candidates::LostIn(2)
+            } else {
+                Candidates::Moves(blocks)
             }
-            !has_immediate_winning_reply(next)
-        })
-        .collect()
+        }
+        _ => Candidates::LostIn(2),
+    }
 }
 
 #[derive(Clone, Copy)]
-struct Rng64 {
+pub(crate) struct Rng64 {
     state: u64,
 }
 
 impl Rng64 {
-    fn new(seed: u64) -> Self {
+    pub(crate) fn new(seed: u64) -> Self {
         Self {
             state: if seed == 0 { 0x9e3779b97f4a7c15 } else { seed },
         }
     }
 
-    fn next_u64(&mut self) -> u64 {
+    pub(crate) fn next_u64(&mut self) -> u64 {
         let mut x = self.state;
         x ^= x << 13;
         x ^= x >> 7;
@@ -461,65 +654,110 @@ impl Rng64 {
         x
     }
 
-    fn gen_index(&mut self, len: usize) -> usize {
+    pub(crate) fn gen_index(&mut self, len: usize) -> usize {
         if len <= 1 {
             0
         } else {
             (self.next_u64() as usize) % len
         }
     }
+
+    pub(crate) fn choose(&mut self, moves: MoveSet) -> Option<u8> {
+        moves.choose(self.next_u64())
+    }
 }
 
+/// A search node. Nodes are shared between every move order that reaches the
+/// same position, so the tree is really a DAG keyed by Zobrist hash.
 #[derive(Clone)]
 struct Node {
     pos: Position,
     just_played: Option<(u8, u8)>,
-    incoming_mv: Option<u8>,
-    children: Vec<usize>,
-    untried_moves: Vec<u8>,
+    result: Outcome,
+    edges: Vec<(u8, usize)>,
+    untried_moves: MoveSet,
     visits: u32,
+    /// Sum of playout values from the point of view of the player who moved
+    /// into this node, so the statistics stay valid whichever parent is used.
     value_sum: f64,
 }
 
-fn centered_move_sort(moves: &mut [u8]) {
-    moves.sort_by(|a, b| {
-        distance_to_center(*a)
-            .partial_cmp(&distance_to_center(*b))
-            .unwrap_or(std::cmp::Ordering::Equal)
-    });
+impl Node {
+    fn new(pos: Position, just_played: Option<(u8, u8)>) -> Self {
+        let result = outcome(pos, just_played);
+        let untried_moves = if matches!(result, Outcome::Ongoing) {
+            legal_moves_with_swap(pos)
+        } else {
+            MoveSet::empty(pos.swap_move())
+        };
+        Self {
+            pos,
+            just_played,
+            result,
+            edges: Vec::new(),
+            untried_moves,
+            visits: 0,
+            value_sum: 0.0,
+        }
+    }
+
+    fn mover(&self) -> u8 {
+        self.pos.turn ^ 1
+    }
+}
+
+/// Nodes plus a transposition table from Zobrist key to node index.
+struct SearchGraph {
+    nodes: Vec<Node>,
+    table: std::collections::HashMap<u64, usize>,
+}
+
+impl SearchGraph {
+    fn new(root: Position) -> Self {
+        let mut table = std::collections::HashMap::new();
+        table.insert(root.hash, 0);
+        Self {
+            nodes: vec![Node::new(root, None)],
+            table,
+        }
+    }
+
+    /// Returns the node for `pos`, creating it if this position has not been
+    /// seen yet. The flag is true when a new node was created.
+    fn node_for(&mut self, pos: Position, just_played: Option<(u8, u8)>) -> (usize, bool) {
+        if let Some(&idx) = self.table.get(&pos.hash) {
+            if self.nodes[idx].pos == pos {
+                return (idx, false);
+            }
+        }
+        let idx = self.nodes.len();
+        self.nodes.push(Node::new(pos, just_played));
+        self.table.insert(pos.hash, idx);
+        (idx, true)
+    }
 }
 
 fn rollout_choice(pos: Position, rng: &mut Rng64) -> Option<u8> {
     let wins = immediate_winning_moves(pos);
     if !wins.is_empty() {
-        let mut ordered = wins;
-        centered_move_sort(&mut ordered);
-        return ordered.first().copied();
+        return centered_moves(pos.board, wins).next();
     }
 
     let safe = one_ply_safe_moves(pos);
     if !safe.is_empty() {
-        let mut ordered = safe;
-        centered_move_sort(&mut ordered);
-        let top_k = ordered.len().min(3);
-        return Some(ordered[rng.gen_index(top_k)]);
+        let top_k = safe.len().min(3);
+        return centered_moves(pos.board, safe).nth(rng.gen_index(top_k));
     }
 
-    let losing = immediate_losing_moves(pos);
-    let losing_mask = losing
-        .iter()
-        .fold(0_u64, |mask, &mv| if mv < SWAP_MOVE { mask | (1_u64 << mv) } else { mask });
-    let mut all = legal_moves_with_swap(pos);
+    let all = legal_moves_with_swap(pos);
     if all.is_empty() {
         return None;
     }
-    all.retain(|&mv| mv == SWAP_MOVE || (losing_mask & (1_u64 << mv)) == 0);
-    if all.is_empty() {
-        let mut fallback = legal_moves_with_swap(pos);
-        centered_move_sort(&mut fallback);
-        return fallback.first().copied();
+    let not_losing = all - immediate_losing_moves(pos);
+    if not_losing.is_empty() {
+        return centered_moves(pos.board, all).next();
     }
-    Some(all[rng.gen_index(all.len())])
+    rng.choose(not_losing)
 }
 
 fn rollout(
@@ -541,7 +779,7 @@ fn rollout(
         let Some(mv) = rollout_choice(pos, rng) else {
             return 0.0;
         };
-        let Some((next, next_jp)) = apply_move_with_meta(pos, mv) else {
+        let Ok((next, next_jp)) = apply_move_with_meta(pos, mv) else {
             return 0.0;
         };
         pos = next;
@@ -552,9 +790,9 @@ fn rollout(
 
 fn mcts_select_child(nodes: &[Node], node_idx: usize, c: f64) -> usize {
     let parent_visits = f64::from(nodes[node_idx].visits.max(1));
-    let mut best_child = nodes[node_idx].children[0];
+    let mut best_child = nodes[node_idx].edges[0].1;
     let mut best_score = f64::NEG_INFINITY;
-    for &child_idx in &nodes[node_idx].children {
+    for &(_, child_idx) in &nodes[node_idx].edges {
         let child = &nodes[child_idx];
         if child.visits == 0 {
             return child_idx;
@@ -570,25 +808,206 @@ fn mcts_select_child(nodes: &[Node], node_idx: usize, c: f64) -> usize {
     best_child
 }
 
+/// What a search found: the move it picked and the statistics behind it.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct SearchReport {
+    pub best_move: Option<u8>,
+    /// Playouts run, or positions visited by alpha-beta; 0 when the move was
+    /// found without searching.
+    pub iterations: u32,
+    /// Deepest alpha-beta iteration completed; always 0 for MCTS.
+    #[serde(default)]
+    pub depth: u8,
+    /// Expected result of `best_move` for the side to move, from -1 (loss)
+    /// to 1 (win); `None` when nothing was searched.
+    pub value: Option<f64>,
+    /// `value` is the exact game-theoretic result rather than an estimate:
+    /// an immediate win or threat sequence, a forced result found by
+    /// alpha-beta, or an ending solved by `endgame::search`.
+    #[serde(default)]
+    pub proven: bool,
+    /// The root moves searched, best first.
+    pub moves: Vec<MoveStats>,
+}
+
+impl SearchReport {
+    /// A report for a proven win found without a full search.
+    pub(crate) fn won(best_move: Option<u8>) -> Self {
+        Self {
+            proven: true,
+            ..Self::unsearched(best_move, Some(1.0))
+        }
+    }
+
+    pub(crate) fn unsearched(best_move: Option<u8>, value: Option<f64>) -> Self {
+        Self {
+            best_move,
+            iterations: 0,
+            depth: 0,
+            value,
+            proven: false,
+            moves: Vec::new(),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub struct MoveStats {
+    #[serde(rename = "move")]
+    pub mv: u8,
+    /// Playouts through this move, or positions alpha-beta visited below it.
+    pub visits: u32,
+    /// Mean playout value for the side to move. For alpha-beta it is the
+    /// move's score, exact for the best move and an upper bound for the rest.
+    pub value: f64,
+}
+
+/// Which search picks the engine's moves.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Searcher {
+    /// Monte Carlo tree search with playouts (`search`).
+    #[default]
+    Mcts,
+    /// Iterative-deepening negamax (`alphabeta::search`).
+    AlphaBeta,
+}
+
+impl Searcher {
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Mcts => "mcts",
+            Self::AlphaBeta => "alpha-beta",
+        }
+    }
+
+    /// Runs this searcher on `pos`; see `search` and `alphabeta::search`
+    /// for what `strength` controls in each.
+    pub fn search(self, pos: Position, budget_ms: f64, strength: u8) -> SearchReport {
+        match self {
+            Self::Mcts => search(pos, budget_ms, strength),
+            Self::AlphaBeta => alphabeta::search(pos, budget_ms, strength),
+        }
+    }
+
+    /// `best_move_with` under the default `MoveOptions`.
+    pub fn best_move(self, pos: Position, budget_ms: f64, strength: u8) -> Option<u8> {
+        self.best_move_with(pos, budget_ms, strength, MoveOptions::default())
+    }
+
+    /// The move to play: from the opening book when `options` allow it and
+    /// the book knows the position, from a search otherwise. With
+    /// `options.swap_table`, a swap decision the swap table covers follows
+    /// the table, and a reply that keeps the colours is searched with the
+    /// swap left out.
+    pub fn best_move_with(
+        self,
+        pos: Position,
+        budget_ms: f64,
+        strength: u8,
+        options: MoveOptions,
+    ) -> Option<u8> {
+        let book_move = || {
+            options
+                .book
+                .then(|| book::book_move(pos, options.seed))
+                .flatten()
+        };
+        let advice = options.swap_table.then(|| swap::precomputed(pos)).flatten();
+        match advice {
+            Some(advice) if advice.swap => Some(pos.swap_move()),
+            Some(_) => book_move().filter(|&mv| mv != pos.swap_move()).or_else(|| {
+                self.search(swap::without_swap(pos), budget_ms, strength)
+                    .best_move
+            }),
+            None => book_move().or_else(|| self.search(pos, budget_ms, strength).best_move),
+        }
+    }
+}
+
+/// What `Searcher::best_move_with` may use besides the search.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct MoveOptions {
+    /// Play from the embedded opening book (`book`). On by default.
+    pub book: bool,
+    /// Follow the precomputed swap decisions (`swap::precomputed`). On by
+    /// default; when off, swapping is just one more move for the search.
+    pub swap_table: bool,
+    /// Picks among weighted book moves: the same seed and position always
+    /// give the same move.
+    pub seed: u64,
+}
+
+impl Default for MoveOptions {
+    fn default() -> Self {
+        Self {
+            book: true,
+            swap_table: true,
+            seed: 0,
+        }
+    }
+}
+
+impl std::str::FromStr for Searcher {
+    type Err = ParseError;
+
+    fn from_str(name: &str) -> Result<Self, Self::Err> {
+        match name.trim() {
+            "mcts" => Ok(Self::Mcts),
+            "alpha-beta" | "alphabeta" => Ok(Self::AlphaBeta),
+            _ => Err(ParseError::UnknownSearcher),
+        }
+    }
+}
+
+/// The MCTS move, or a book move; see `Searcher::best_move`.
 pub fn best_move_with_strength(pos: Position, budget_ms: f64, strength: u8) -> Option<u8> {
+    Searcher::Mcts.best_move(pos, budget_ms, strength)
+}
+
+/// The report for a root position no searcher needs to search: no moves
+/// left, an immediate win, an ending small enough to solve exactly, a short
+/// forced win by threats, or only one move that does not lose at once.
+/// Every searcher checks this first, in this order.
+pub(crate) fn settled_at_root(pos: Position) -> Option<SearchReport> {
     let legal = legal_moves_with_swap(pos);
     if legal.is_empty() {
-        return None;
+        return Some(SearchReport::unsearched(None, None));
     }
-
     let wins = immediate_winning_moves(pos);
     if !wins.is_empty() {
-        let mut ordered = wins;
-        centered_move_sort(&mut ordered);
-        return ordered.first().copied();
+        return Some(SearchReport::won(centered_moves(pos.board, wins).next()));
+    }
+    if endgame::in_range(pos) {
+        return Some(endgame::search(pos));
+    }
+    if let Some(line) = threats::find_forced_win(pos, threats::PRECHECK_MOVES) {
+        return Some(SearchReport::won(line.first().copied()));
     }
-
     let safe = one_ply_safe_moves(pos);
-    if !safe.is_empty() {
-        if safe.len() == 1 {
-            return safe.first().copied();
-        }
+    if safe.len() == 1 {
+        return Some(SearchReport::unsearched(safe.first(), None));
     }
+    None
+}
+
+/// Runs the search and reports its statistics along with the move.
+pub fn search(pos: Position, budget_ms: f64, strength: u8) -> SearchReport {
+    let fold = |bits: Bitboard| bits as u64 ^ (bits >> 64) as u64;
+    let seed = fold(pos.p0)
+        ^ fold(pos.p1).rotate_left(7)
+        ^ u64::from(pos.ply).rotate_left(17)
+        ^ u64::from(strength).rotate_left(29);
+    search_seeded(pos, budget_ms, strength, seed)
+}
+
+/// `search` with playouts drawn from `seed` rather than from the position,
+/// so that several searches of one position can be averaged.
+pub fn search_seeded(pos: Position, budget_ms: f64, strength: u8, seed: u64) -> SearchReport {
+    if let Some(report) = settled_at_root(pos) {
+        return report;
+    }
+    let legal = legal_moves_with_swap(pos);
 
     let config = SearchConfig::from_strength(strength);
     let min_budget = 10.0;
@@ -597,23 +1016,10 @@ pub fn best_move_with_strength(pos: Position, budget_ms: f64, strength: u8) -> O
         ((adjusted_budget * config.iterations_per_ms) as usize).clamp(120, 250_000);
     let deadline = now_ms() + adjusted_budget;
     let root_player = pos.turn;
-    let seed = pos.p0
-        ^ pos.p1.rotate_left(7)
-        ^ u64::from(pos.ply).rotate_left(17)
-        ^ u64::from(strength).rotate_left(29);
     let mut rng = Rng64::new(seed);
 
-    let mut root_untried = legal;
-    centered_move_sort(&mut root_untried);
-    let mut nodes = vec![Node {
-        pos,
-        just_played: None,
-        incoming_mv: None,
-        children: Vec::new(),
-        untried_moves: root_untried,
-        visits: 0,
-        value_sum: 0.0,
-    }];
+    let mut graph = SearchGraph::new(pos);
+    graph.nodes[0].untried_moves = legal;
 
     let mut iterations = 0usize;
     while iterations < max_iterations && now_ms() < deadline {
@@ -621,49 +1027,40 @@ pub fn best_move_with_strength(pos: Position, budget_ms: f64, strength: u8) -> O
         let mut node_idx = 0usize;
         let mut path = vec![0usize];
 
-        while nodes[node_idx].untried_moves.is_empty() && !nodes[node_idx].children.is_empty() {
-            node_idx = mcts_select_child(&nodes, node_idx, config.exploration);
-            path.push(node_idx);
-        }
-
-        if !nodes[node_idx].untried_moves.is_empty() {
-            let pick_idx = rng.gen_index(nodes[node_idx].untried_moves.len());
-            let mv = nodes[node_idx].untried_moves.swap_remove(pick_idx);
-            if let Some((next, jp)) = apply_move_with_meta(nodes[node_idx].pos, mv) {
-                let mut untried = legal_moves_with_swap(next);
-                centered_move_sort(&mut untried);
-                let new_idx = nodes.len();
-                nodes.push(Node {
-                    pos: next,
-                    just_played: jp,
-                    incoming_mv: Some(mv),
-                    children: Vec::new(),
-                    untried_moves: untried,
-                    visits: 0,
-                    value_sum: 0.0,
-                });
-                nodes[node_idx].children.push(new_idx);
-                node_idx = new_idx;
+        // Descend until a new position is added. Expanding into a position
+        // that is already in the table just links the edge and keeps going,
+        // so every playout starts from a distinct position.
+        loop {
+            let node = &mut graph.nodes[node_idx];
+            if let Some(mv) = rng.choose(node.untried_moves) {
+                node.untried_moves.remove(mv);
+                let Ok((next, jp)) = apply_move_with_meta(node.pos, mv) else {
+                    continue;
+                };
+                let (child_idx, created) = graph.node_for(next, jp);
+                graph.nodes[node_idx].edges.push((mv, child_idx));
+                node_idx = child_idx;
+                path.push(node_idx);
+                if created {
+                    break;
+                }
+            } else if !node.edges.is_empty() {
+                node_idx = mcts_select_child(&graph.nodes, node_idx, config.exploration);
                 path.push(node_idx);
+            } else {
+                break;
             }
         }
 
-        let leaf = &nodes[node_idx];
-        let result = outcome(leaf.pos, leaf.just_played);
-        let mut value = if let Some(winner) = winner_from_outcome(result) {
+        let leaf = &graph.nodes[node_idx];
+        let value = if let Some(winner) = winner_from_outcome(leaf.result) {
             if winner == root_player {
                 1.0
             } else {
                 -1.0
             }
-        } else if matches!(result, Outcome::Draw) {
+        } else if matches!(leaf.result, Outcome::Draw) {
             0.0
-        } else if let Some(loser) = losing_player_from_outcome(result) {
-            if loser == root_player {
-                -1.0
-            } else {
-                1.0
-            }
         } else {
             rollout(
                 leaf.pos,
@@ -674,36 +1071,55 @@ pub fn best_move_with_strength(pos: Position, budget_ms: f64, strength: u8) -> O
             )
         };
 
-        for idx in path.into_iter().rev() {
-            nodes[idx].visits += 1;
-            nodes[idx].value_sum += value;
-            value = -value;
+        for idx in path {
+            let node = &mut graph.nodes[idx];
+            node.visits += 1;
+            node.value_sum += if node.mover() == root_player {
+                value
+            } else {
+                -value
+            };
         }
     }
 
-    if nodes[0].children.is_empty() {
-        let mut fallback = legal_moves_with_swap(pos);
-        centered_move_sort(&mut fallback);
-        return fallback.first().copied();
+    let nodes = &graph.nodes;
+    if nodes[0].edges.is_empty() {
+        let fallback = centered_moves(pos.board, legal_moves_with_swap(pos)).next();
+        return SearchReport::unsearched(fallback, None);
     }
 
-    let mut best_child = nodes[0].children[0];
-    for &c in &nodes[0].children[1..] {
-        if nodes[c].visits > nodes[best_child].visits
-            || (nodes[c].visits == nodes[best_child].visits
-                && nodes[c].value_sum > nodes[best_child].value_sum)
-        {
-            best_child = c;
-        }
+    let mut edges = nodes[0].edges.clone();
+    // Most visits first, ties to the better value; a stable sort keeps the
+    // earliest expanded of any exact tie first, as before.
+    edges.sort_by(|&(_, a), &(_, b)| {
+        let (a, b) = (&nodes[a], &nodes[b]);
+        b.visits
+            .cmp(&a.visits)
+            .then(b.value_sum.total_cmp(&a.value_sum))
+    });
+    let moves: Vec<MoveStats> = edges
+        .iter()
+        .map(|&(mv, child)| MoveStats {
+            mv,
+            visits: nodes[child].visits,
+            value: nodes[child].value_sum / f64::from(nodes[child].visits.max(1)),
+        })
+        .collect();
+    SearchReport {
+        best_move: Some(moves[0].mv),
+        iterations: iterations as u32,
+        depth: 0,
+        proven: false,
+        value: Some(moves[0].value),
+        moves,
     }
-    nodes[best_child].incoming_mv
 }
 
 pub fn best_move(pos: Position, budget_ms: f64) -> Option<u8> {
     best_move_with_strength(pos, budget_ms, 2)
 }
 
-fn now_ms() -> f64 {
+pub(crate) fn now_ms() -> f64 {
     #[cfg(target_arch = "wasm32")]
     {
         js_sys::Date::now()
@@ -715,47 +1131,66 @@ fn now_ms() -> f64 {
     }
 }
 
-pub fn parse_board_hex(board_hex: &str) -> Result<Position, &'static str> {
-    if board_hex.len() != 32 {
-        return Err("board hex must be exactly 32 chars");
+/// Hex digits per player in the packed board format: 16 for boards that
+/// fit in 64 bits, 32 otherwise.
+fn board_hex_digits(board: &Board) -> usize {
+    if board.cell_count() <= 64 {
+        16
+    } else {
+        32
     }
+}
 
-    let mut bits = String::with_capacity(128);
-    for ch in board_hex.chars() {
-        let Some(v) = ch.to_digit(16) else {
-            return Err("invalid hex");
-        };
-        bits.push(if v & 0b1000 != 0 { '1' } else { '0' });
-        bits.push(if v & 0b0100 != 0 { '1' } else { '0' });
-        bits.push(if v & 0b0010 != 0 { '1' } else { '0' });
-        bits.push(if v & 0b0001 != 0 { '1' } else { '0' });
-    }
+/// Parses the historical packed hex format on the standard board.
+pub fn parse_board_hex(board_hex: &str) -> Result<Position, ParseError> {
+    parse_board_hex_on(Board::standard(), board_hex)
+}
 
-    let p0_bits = &bits[0..64];
-    let p1_bits = &bits[64..128];
+pub fn parse_board_hex_on(board: &'static Board, board_hex: &str) -> Result<Position, ParseError> {
+    parse_board_hex_with(board, Rules::STANDARD, board_hex)
+}
 
-    let mut p0: u64 = 0;
-    let mut p1: u64 = 0;
-    for (i, ch) in p0_bits.chars().rev().enumerate() {
-        if ch == '1' {
-            p0 |= 1_u64 << i;
-        }
+/// Parses a packed hex board played under `rules`. A lone second-player
+/// stone (a swapped opening) is only accepted when swap is enabled.
+pub fn parse_board_hex_with(
+    board: &'static Board,
+    rules: Rules,
+    board_hex: &str,
+) -> Result<Position, ParseError> {
+    let digits = board_hex_digits(board);
+    if board_hex.len() != 2 * digits {
+        return Err(ParseError::Length {
+            expected: 2 * digits,
+            found: board_hex.len(),
+        });
     }
-    for (i, ch) in p1_bits.chars().rev().enumerate() {
-        if ch == '1' {
-            p1 |= 1_u64 << i;
-        }
+    if let Some((position, found)) = board_hex
+        .chars()
+        .enumerate()
+        .find(|(_, ch)| !ch.is_ascii_hexdigit())
+    {
+        return Err(ParseError::BadDigit { position, found });
     }
 
+    let (p0_hex, p1_hex) = board_hex.split_at(digits);
+    let p0 = Bitboard::from_str_radix(p0_hex, 16).expect("digits were validated");
+    let p1 = Bitboard::from_str_radix(p1_hex, 16).expect("digits were validated");
+
+    if (p0 | p1) & !board.mask() != 0 {
+        return Err(ParseError::OffBoard);
+    }
     if p0 & p1 != 0 {
-        return Err("players overlap on occupied cells");
+        return Err(ParseError::Overlap);
     }
 
     let p0_count = p0.count_ones();
     let p1_count = p1.count_ones();
-    let is_swapped_opening = p0_count == 0 && p1_count == 1;
+    let is_swapped_opening = rules.swap && p0_count == 0 && p1_count == 1;
     if !(is_swapped_opening || (p0_count >= p1_count && p0_count <= p1_count + 1)) {
-        return Err("invalid move parity");
+        return Err(ParseError::Parity {
+            p0: p0_count,
+            p1: p1_count,
+        });
     }
     let ply = (p0_count + p1_count) as u8;
     let turn = if is_swapped_opening || p0_count == p1_count {
@@ -763,55 +1198,174 @@ pub fn parse_board_hex(board_hex: &str) -> Result<Position, &'static str> {
     } else {
         1
     };
-    Ok(Position { p0, p1, turn, ply })
+    Ok(Position::from_bitboards_on(board, p0, p1, turn, ply).with_rules(rules))
+}
+
+pub fn encode_board_hex(pos: Position) -> String {
+    let digits = board_hex_digits(pos.board);
+    format!("{:0digits$x}{:0digits$x}", pos.p0, pos.p1)
 }
 
+/// The `invalid` outcome payload, carrying the reason a board or move was
+/// rejected.
+pub fn encode_error(error: &dyn std::error::Error) -> String {
+    json::encode_invalid(error)
+}
+
+/// The outcome as JSON, in the shape documented in `json`.
 pub fn encode_outcome(outcome: Outcome) -> String {
-    let payload = match outcome {
-        Outcome::Ongoing => OutcomeResponse {
-            state: "ongoing",
-            winner: None,
-            loser: None,
-            line: vec![],
-        },
-        Outcome::Draw => OutcomeResponse {
-            state: "draw",
-            winner: None,
-            loser: None,
-            line: vec![],
-        },
-        Outcome::Win(player, line) => OutcomeResponse {
-            state: "win",
-            winner: Some(player),
-            loser: None,
-            line: line.to_vec(),
-        },
-        Outcome::Lose(player, line) => OutcomeResponse {
-            state: "lose",
-            winner: Some(player ^ 1),
-            loser: Some(player),
-            line: line.to_vec(),
-        },
-        Outcome::Invalid => OutcomeResponse {
-            state: "invalid",
-            winner: None,
-            loser: None,
-            line: vec![],
-        },
-    };
-    serde_json::to_string(&payload).unwrap_or_else(|_| "{\"state\":\"invalid\"}".to_string())
+    serde_json::to_string(&outcome).unwrap_or_else(|_| "{\"state\":\"invalid\"}".to_string())
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::board::{MAX_RADIUS, MIN_RADIUS};
+    use crate::fixtures;
+
+    /// The original `Vec`-building line scan, kept as a reference for the
+    /// table-driven detection.
+    mod line_scan {
+        use super::super::DIRS;
+        use crate::board::{bit, Bitboard, Board};
+        use std::collections::HashMap;
+
+        const LINE_DIRS: [(i32, i32); 3] = [(1, 0), (0, 1), (1, -1)];
+
+        fn idx_map(board: &Board) -> HashMap<(i32, i32), u8> {
+            board
+                .axial_cells()
+                .iter()
+                .enumerate()
+                .map(|(idx, &cell)| (cell, idx as u8))
+                .collect()
+        }
 
-    fn position_after_moves(moves: &[u8]) -> Position {
-        let mut pos = Position::empty();
-        for &mv in moves {
-            pos = pos.apply(mv).expect("move must be legal");
+        pub(super) fn all_three_lines(board: &Board) -> Vec<[u8; 3]> {
+            let (cells, map) = (board.axial_cells(), idx_map(board));
+            let mut lines = Vec::new();
+            for &(q, r) in cells {
+                for &(dq, dr) in &LINE_DIRS {
+                    let s0 = (q, r);
+                    let s1 = (q + dq, r + dr);
+                    let s2 = (q + 2 * dq, r + 2 * dr);
+                    if let (Some(&a), Some(&b), Some(&c)) =
+                        (map.get(&s0), map.get(&s1), map.get(&s2))
+                    {
+                        lines.push([a, b, c]);
+                    }
+                }
+            }
+            lines.sort_unstable();
+            lines.dedup();
+            lines
+        }
+
+        pub(super) fn all_four_lines(board: &Board) -> Vec<[u8; 4]> {
+            let (cells, map) = (board.axial_cells(), idx_map(board));
+            let mut lines = Vec::new();
+            for &(q, r) in cells {
+                for &(dq, dr) in &LINE_DIRS {
+                    let s0 = (q, r);
+                    let s1 = (q + dq, r + dr);
+                    let s2 = (q + 2 * dq, r + 2 * dr);
+                    let s3 = (q + 3 * dq, r + 3 * dr);
+                    if let (Some(&a), Some(&b), Some(&c), Some(&d)) =
+                        (map.get(&s0), map.get(&s1), map.get(&s2), map.get(&s3))
+                    {
+                        lines.push([a, b, c, d]);
+                    }
+                }
+            }
+            lines.sort_unstable();
+            lines.dedup();
+            lines
+        }
+
+        fn stepped_neighbors(board: &Board, from: u8, dir_idx: usize) -> [Option<u8>; 3] {
+            let (q, r) = board.axial_cells()[from as usize];
+            let (dq, dr) = DIRS[dir_idx];
+            let mut stepped = [None; 3];
+            for step in 1..=3 {
+                stepped[(step - 1) as usize] = board.index(q + dq * step, r + dr * step);
+            }
+            stepped
+        }
+
+        fn contiguous_in_direction(
+            board: &Board,
+            bits: Bitboard,
+            from: u8,
+            dir_idx: usize,
+        ) -> Vec<u8> {
+            let mut run = Vec::with_capacity(3);
+            for maybe_idx in stepped_neighbors(board, from, dir_idx) {
+                let Some(idx) = maybe_idx else { break };
+                if bits & bit(idx) == 0 {
+                    break;
+                }
+                run.push(idx);
+            }
+            run
+        }
+
+        fn window_containing(chain: &[u8], center_idx: usize, width: usize) -> Option<&[u8]> {
+            if chain.len() < width {
+                return None;
+            }
+            let start = center_idx.saturating_sub(width - 1);
+            let max_start = center_idx.min(chain.len() - width);
+            let start = start.min(max_start);
+            Some(&chain[start..start + width])
+        }
+
+        pub(super) fn has_line4_from_move(
+            board: &Board,
+            bits: Bitboard,
+            mv: u8,
+        ) -> Option<[u8; 4]> {
+            for &(dir_pos, dir_neg) in &[(0_usize, 3_usize), (1, 4), (2, 5)] {
+                let mut negative = contiguous_in_direction(board, bits, mv, dir_neg);
+                let positive = contiguous_in_direction(board, bits, mv, dir_pos);
+                if negative.len() + 1 + positive.len() < 4 {
+                    continue;
+                }
+                negative.reverse();
+                let center = negative.len();
+                let mut chain = Vec::with_capacity(negative.len() + 1 + positive.len());
+                chain.extend(negative);
+                chain.push(mv);
+                chain.extend(positive);
+                if let Some(line) = window_containing(&chain, center, 4) {
+                    return line.try_into().ok();
+                }
+            }
+            None
+        }
+
+        pub(super) fn has_line3_from_move(
+            board: &Board,
+            bits: Bitboard,
+            mv: u8,
+        ) -> Option<[u8; 3]> {
+            for &(dir_pos, dir_neg) in &[(0_usize, 3_usize), (1, 4), (2, 5)] {
+                let mut negative = contiguous_in_direction(board, bits, mv, dir_neg);
+                let positive = contiguous_in_direction(board, bits, mv, dir_pos);
+                if negative.len() + 1 + positive.len() < 3 {
+                    continue;
+                }
+                negative.reverse();
+                let center = negative.len();
+                let mut chain = Vec::with_capacity(negative.len() + 1 + positive.len());
+                chain.extend(negative);
+                chain.push(mv);
+                chain.extend(positive);
+                if let Some(line) = window_containing(&chain, center, 3) {
+                    return line.try_into().ok();
+                }
+            }
+            None
         }
-        pos
     }
 
     #[test]
@@ -821,6 +1375,60 @@ mod tests {
         assert_eq!(p.turn, 0);
     }
 
+    #[test]
+    fn parse_errors_name_the_problem() {
+        let zeros = "0".repeat(32);
+        assert_eq!(
+            parse_board_hex("00"),
+            Err(ParseError::Length {
+                expected: 32,
+                found: 2
+            })
+        );
+        let bad = format!("{}g", &zeros[..31]);
+        assert_eq!(
+            parse_board_hex(&bad),
+            Err(ParseError::BadDigit {
+                position: 31,
+                found: 'g'
+            })
+        );
+        let off_board = format!("8{}", &zeros[1..]);
+        assert_eq!(parse_board_hex(&off_board), Err(ParseError::OffBoard));
+        let overlap = "0000000000000001".repeat(2);
+        assert_eq!(parse_board_hex(&overlap), Err(ParseError::Overlap));
+        let parity = format!("0000000000000003{}", &zeros[16..]);
+        assert_eq!(
+            parse_board_hex(&parity),
+            Err(ParseError::Parity { p0: 2, p1: 0 })
+        );
+        assert!(encode_error(&ParseError::Overlap).contains("\"error\":\"players overlap"));
+    }
+
+    #[test]
+    fn move_errors_name_the_problem() {
+        let pos = fixtures::game_after_moves(&[30]).position();
+        assert_eq!(pos.apply(30), Err(MoveError::Occupied(30)));
+        assert_eq!(
+            pos.apply(SWAP_MOVE + 1),
+            Err(MoveError::OffBoard(SWAP_MOVE + 1))
+        );
+        let next = pos.apply(31).unwrap();
+        assert_eq!(next.apply_swap(), Err(MoveError::SwapNotAllowed));
+        assert_eq!(
+            apply_move_with_meta(next, SWAP_MOVE),
+            Err(MoveError::SwapNotAllowed)
+        );
+    }
+
+    #[test]
+    fn board_hex_round_trips() {
+        let pos = fixtures::game_after_moves(&[30, 0, 60]).position();
+        let parsed = parse_board_hex(&encode_board_hex(pos)).unwrap();
+        assert_eq!((parsed.p0, parsed.p1), (pos.p0, pos.p1));
+        assert_eq!((parsed.turn, parsed.ply), (pos.turn, pos.ply));
+    }
+
     #[test]
     fn legal_move_count() {
         let p = Position::empty();
@@ -831,7 +1439,7 @@ mod tests {
     fn swapped_opening_parses() {
         // Only P1 has one stone: valid board after swap choice.
         let mut p1: u64 = 0;
-        p1 |= 1_u64 << 30;
+        p1 |= 1 << 30;
         let bits = format!("{:064b}{:064b}", 0_u64, p1)
             .chars()
             .collect::<Vec<_>>();
@@ -847,35 +1455,242 @@ mod tests {
 
     #[test]
     fn swap_is_legal_only_for_second_player_after_first_move() {
-        let p = position_after_moves(&[30]);
+        let p = fixtures::game_after_moves(&[30]).position();
         assert!(p.can_swap());
         let next = p.apply_swap().unwrap();
         assert_eq!(next.p0, 0);
-        assert_eq!(next.p1, 1_u64 << 30);
+        assert_eq!(next.p1, 1 << 30);
         assert_eq!(next.turn, 0);
         assert!(!next.can_swap());
     }
 
+    #[test]
+    fn rules_restrict_swap_and_opening() {
+        let rules: Rules = "no-swap,no-centre".parse().unwrap();
+        let pos = Position::empty().with_rules(rules);
+        assert!(!pos.legal_moves().contains(30));
+        assert_eq!(pos.apply(30), Err(MoveError::Forbidden(30)));
+        assert_eq!(pos.legal_moves().len(), 60);
+
+        let opened = pos.apply(22).unwrap();
+        assert!(!opened.can_swap());
+        assert!(!legal_moves_with_swap(opened).has_swap());
+        assert!(opened.legal_moves().contains(30));
+
+        let swapped = encode_board_hex(Position::empty().apply(30).unwrap().apply_swap().unwrap());
+        assert!(parse_board_hex(&swapped).is_ok());
+        assert_eq!(
+            parse_board_hex_with(Board::standard(), rules, &swapped),
+            Err(ParseError::Parity { p0: 0, p1: 1 })
+        );
+    }
+
+    #[test]
+    fn forced_block_narrows_moves_to_the_block() {
+        // Player 1 threatens 26-35-43-50 at 50.
+        let moves = [0, 26, 10, 35, 20, 43];
+        let rules = Rules {
+            forced_block: true,
+            ..Rules::STANDARD
+        };
+        let mut pos = Position::empty().with_rules(rules);
+        for mv in moves {
+            pos = pos.apply(mv).unwrap();
+        }
+        assert_eq!(pos.legal_moves().to_vec(), vec![50]);
+        assert_eq!(pos.apply(60), Err(MoveError::Forbidden(60)));
+        assert_eq!(best_move(pos, 50.0), Some(50));
+        assert_eq!(
+            fixtures::game_after_moves(&moves)
+                .position()
+                .legal_moves()
+                .len(),
+            55
+        );
+    }
+
+    #[test]
+    fn debug_lists_stones_by_name() {
+        let pos = fixtures::game_after_moves(&[30, 0]).position();
+        assert_eq!(
+            format!("{pos:?}"),
+            "Position { radius: 4, p0: [e5], p1: [a1], turn: 0, ply: 2, rules: swap,no-block }"
+        );
+    }
+
+    #[test]
+    fn zobrist_is_incremental_and_order_independent() {
+        let a = fixtures::game_after_moves(&[30, 0, 40, 1]).position();
+        let b = fixtures::game_after_moves(&[40, 1, 30, 0]).position();
+        assert_eq!(a.hash, b.hash);
+        assert_eq!(a.hash, a.zobrist());
+        assert_ne!(
+            a.hash,
+            fixtures::game_after_moves(&[30, 1, 0, 40]).position().hash
+        );
+
+        let opened = fixtures::game_after_moves(&[30]).position();
+        let swapped = opened.apply_swap().unwrap();
+        assert_eq!(swapped.hash, swapped.zobrist());
+        assert_ne!(opened.hash, swapped.hash);
+        let played = opened.apply(31).unwrap();
+        assert_eq!(played.hash, played.zobrist());
+    }
+
+    #[test]
+    fn line_tables_match_line_scan() {
+        for radius in MIN_RADIUS..=MAX_RADIUS {
+            let board = Board::with_radius(radius).unwrap();
+            assert_eq!(line_scan::all_three_lines(board), board.three_lines());
+            assert_eq!(line_scan::all_four_lines(board), board.four_lines());
+        }
+    }
+
+    #[test]
+    fn move_detection_matches_line_scan() {
+        let mut rng = Rng64::new(0x5eed);
+        for radius in MIN_RADIUS..=MAX_RADIUS {
+            let board = Board::with_radius(radius).unwrap();
+            let cells = board.cell_count() as u8;
+            for _ in 0..1000 {
+                let density = 2 + rng.gen_index(5) as u32;
+                let mut bits: Bitboard = 0;
+                for idx in 0..cells {
+                    if rng.gen_index(density as usize) == 0 {
+                        bits |= bit(idx);
+                    }
+                }
+                for mv in 0..cells {
+                    let bits = bits | bit(mv);
+                    assert_eq!(
+                        has_line4_from_move(board, bits, mv),
+                        line_scan::has_line4_from_move(board, bits, mv)
+                    );
+                    assert_eq!(
+                        has_line3_from_move(board, bits, mv),
+                        line_scan::has_line3_from_move(board, bits, mv)
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn tactical_move_sets_agree_with_outcome() {
+        let mut rng = Rng64::new(0xfeed);
+        for _ in 0..300 {
+            let mut pos = Position::empty();
+            let mut jp = None;
+            while matches!(outcome(pos, jp), Outcome::Ongoing) {
+                let us = pos.turn;
+                let (mut wins, mut losses) = (MoveSet::EMPTY, MoveSet::EMPTY);
+                for mv in legal_moves_with_swap(pos) {
+                    let (next, next_jp) = apply_move_with_meta(pos, mv).unwrap();
+                    match outcome(next, next_jp) {
+                        Outcome::Win(w, _) if w == us => wins.insert(mv),
+                        Outcome::Lose(l, _) if l == us => losses.insert(mv),
+                        _ => {}
+                    }
+                }
+                assert_eq!(immediate_winning_moves(pos), wins);
+                assert_eq!(immediate_losing_moves(pos), losses);
+                let mv = rng.choose(legal_moves_with_swap(pos)).unwrap();
+                (pos, jp) = apply_move_with_meta(pos, mv).unwrap();
+            }
+        }
+    }
+
     #[test]
     fn engine_takes_immediate_win() {
-        // Player 0 can win immediately with 50:
-        // 26-35-43-50 is a line of four on r = -4.
-        let pos = position_after_moves(&[26, 0, 35, 1, 43, 5]);
+        // Player 0 can win immediately with 43, filling the gap in
+        // 26-35-43-50, a line of four on r = -4.
+        let pos = fixtures::game_after_moves(&[26, 0, 35, 1, 50, 5]).position();
         assert_eq!(pos.turn, 0);
-        assert_eq!(best_move(pos, 200.0), Some(50));
+        assert!(matches!(outcome(pos, None), Outcome::Ongoing));
+        assert_eq!(best_move(pos, 200.0), Some(43), "\n{pos}");
     }
 
     #[test]
     fn engine_blocks_opponents_immediate_win() {
-        // Player 1 threatens 26-35-43-50; only 50 blocks the immediate win.
-        let pos = position_after_moves(&[0, 26, 10, 35, 20, 43]);
+        // Player 1 threatens to fill 43 in 26-35-43-50; only 43 blocks it.
+        let pos = fixtures::game_after_moves(&[0, 26, 10, 35, 20, 50]).position();
         assert_eq!(pos.turn, 0);
-        assert_eq!(best_move(pos, 200.0), Some(50));
+        assert!(matches!(outcome(pos, None), Outcome::Ongoing));
+        assert_eq!(best_move(pos, 200.0), Some(43), "\n{pos}");
+    }
+
+    #[test]
+    fn larger_boards_detect_lines_above_bit_64() {
+        let board = Board::with_radius(5).unwrap();
+        let column: Vec<u8> = (-5..=-2).map(|r| board.index(5, r).unwrap()).collect();
+        assert!(column.iter().all(|&idx| idx >= 64));
+        let mut pos = Position::empty_on(board);
+        for mv in [column[0], 0, column[1], 2, column[3], 4] {
+            pos = pos.apply(mv).unwrap();
+        }
+        let (next, jp) = apply_move_with_meta(pos, column[2]).unwrap();
+        assert!(matches!(outcome(next, jp), Outcome::Win(0, _)));
+        assert_eq!(best_move(pos, 100.0), Some(column[2]));
+
+        let parsed = parse_board_hex_on(board, &encode_board_hex(next)).unwrap();
+        assert_eq!((parsed.p0, parsed.p1), (next.p0, next.p1));
+        assert!(parse_board_hex(&encode_board_hex(next)).is_err());
+    }
+
+    #[test]
+    fn reports_every_line_and_the_deciding_rule() {
+        let board = Board::standard();
+        let at = |q, r| board.index(q, r).unwrap();
+        let cells = |idxs: &[u8]| idxs.iter().fold(0, |acc, &idx| acc | bit(idx));
+        // The centre completes a four up its column and a three along r = 0.
+        let four = [at(0, -3), at(0, -2), at(0, -1), at(0, 0)];
+        let three = [at(-2, 0), at(-1, 0), at(0, 0)];
+        let p0 = cells(&four) | cells(&three);
+        let pos = Position::from_bitboards(p0, cells(&[0, 2, 4, 58, 60]), 1, 11);
+        let result = outcome(pos, Some((0, at(0, 0))));
+        assert!(matches!(result, Outcome::Win(0, _)));
+        assert_eq!(result.deciding_rule(), Some(DecidingRule::FourDespiteThree));
+        let mut lines = result.lines().unwrap().cells();
+        lines.sort();
+        let mut expected = vec![four.to_vec(), three.to_vec()];
+        expected.iter_mut().for_each(|line| line.sort());
+        expected.sort();
+        assert_eq!(lines, expected);
+        // The whole-board check finds the same lines.
+        assert_eq!(outcome(pos, None), result);
+
+        let json = encode_outcome(result);
+        assert!(json.contains("\"rule\":\"four-despite-three\""), "{json}");
+        let mut line = four.to_vec();
+        line.sort();
+        assert!(json.contains(&format!("\"line\":{line:?}").replace(' ', "")));
+
+        // Filling the gap in x x _ x x makes one run of five, not two fours.
+        let run: Vec<u8> = (-4..=0).map(|r| at(0, r)).collect();
+        let p0 = cells(&[run[0], run[1], run[3], run[4]]);
+        let pos = Position::from_bitboards(p0, cells(&[0, 2, 4, 60]), 0, 8)
+            .apply(run[2])
+            .unwrap();
+        let result = outcome(pos, Some((0, run[2])));
+        assert_eq!(result.deciding_rule(), Some(DecidingRule::Four));
+        assert_eq!(result.lines().unwrap().cells(), vec![run]);
+    }
+
+    #[test]
+    fn search_plays_legal_moves_on_every_board_size() {
+        for radius in MIN_RADIUS..=MAX_RADIUS {
+            let board = Board::with_radius(radius).unwrap();
+            let pos = Position::empty_on(board).apply(0).unwrap();
+            let legal = legal_moves_with_swap(pos);
+            assert!(legal.contains(board.swap_move()));
+            let mv = best_move_with_strength(pos, 30.0, 1).unwrap();
+            assert!(legal.contains(mv));
+        }
     }
 
     #[test]
     fn best_move_handles_swap_position_without_panicking() {
-        let pos = position_after_moves(&[30]);
+        let pos = fixtures::game_after_moves(&[30]).position();
         assert!(pos.can_swap());
         let mv = best_move_with_strength(pos, 60.0, 2);
         assert!(mv.is_some());
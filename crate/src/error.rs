@@ -0,0 +1,281 @@
+//! Error types for parsing boards, rule specs, game records, share codes
+//! and opening books and for playing moves.
+
+use std::error::Error;
+use std::fmt;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ParseError {
+    /// The board string is not two players' worth of hex digits.
+    Length { expected: usize, found: usize },
+    /// A character that is not a hex digit.
+    BadDigit { position: usize, found: char },
+    /// A stone on a bit past the last cell of the board.
+    OffBoard,
+    /// Both players have a stone on the same cell.
+    Overlap,
+    /// The stone counts cannot arise from alternating moves.
+    Parity { p0: u32, p1: u32 },
+    /// A house-rule option that is not recognised.
+    UnknownRule,
+    /// An `opening-distance=` value that is not a number.
+    BadOpeningDistance,
+    /// A cell name that is not on the board, e.g. `j1` on the standard board.
+    BadCoordinate,
+    /// Text that is neither board hex nor a known position-string version.
+    UnknownFormat,
+    /// A position string whose fields do not have the expected shape.
+    Malformed,
+    /// A position string whose side to move, move count or swap state
+    /// disagrees with its stones or the rules.
+    Inconsistent,
+    /// A searcher name other than `mcts` or `alpha-beta`.
+    UnknownSearcher,
+    /// A JSON position that could not be read, with serde's reason.
+    Json(String),
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match *self {
+            Self::Length { expected, found } => write!(
+                f,
+                "board hex has {found} digits, expected {expected} for this board"
+            ),
+            Self::BadDigit { position, found } => {
+                write!(f, "invalid hex digit {found:?} at position {position}")
+            }
+            Self::OffBoard => f.write_str("stones outside the board"),
+            Self::Overlap => f.write_str("players overlap on occupied cells"),
+            Self::Parity { p0, p1 } => write!(
+                f,
+                "invalid move parity: player 0 has {p0} stones, player 1 has {p1}"
+            ),
+            Self::UnknownRule => f.write_str("unknown rule option"),
+            Self::BadOpeningDistance => f.write_str("invalid opening distance"),
+            Self::BadCoordinate => f.write_str("not a cell on this board"),
+            Self::UnknownFormat => f.write_str("unknown position format"),
+            Self::Malformed => f.write_str("malformed position string"),
+            Self::Inconsistent => {
+                f.write_str("position string fields disagree with the stones or rules")
+            }
+            Self::UnknownSearcher => f.write_str("unknown searcher"),
+            Self::Json(ref reason) => write!(f, "invalid JSON position: {reason}"),
+        }
+    }
+}
+
+impl Error for ParseError {}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum MoveError {
+    /// The cell already holds a stone.
+    Occupied(u8),
+    /// Neither a cell on this board nor its swap move.
+    OffBoard(u8),
+    /// Swap was requested but is not available here.
+    SwapNotAllowed,
+    /// The game has already been decided.
+    GameOver,
+    /// An empty cell the rules forbid right now: an opening restriction or
+    /// a block the player was obliged to make elsewhere.
+    Forbidden(u8),
+}
+
+impl fmt::Display for MoveError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match *self {
+            Self::Occupied(mv) => write!(f, "cell {mv} is already occupied"),
+            Self::OffBoard(mv) => write!(f, "move {mv} is not on the board"),
+            Self::SwapNotAllowed => f.write_str("swap is not allowed now"),
+            Self::GameOver => f.write_str("the game is already over"),
+            Self::Forbidden(mv) => write!(f, "the rules do not allow cell {mv} now"),
+        }
+    }
+}
+
+impl Error for MoveError {}
+
+/// Why a position could not have arisen in play.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum InvalidPosition {
+    /// Both players have a stone on the same cell.
+    Overlap,
+    /// A stone on a bit past the last cell of the board.
+    OffBoard,
+    /// The stone counts and ply cannot arise with this side to move.
+    StoneCount { p0: u32, p1: u32 },
+    /// The stones can only be explained by a swap, which the rules forbid.
+    SwapNotAllowed,
+    /// Every stone of the opening player is on a cell the opening rule
+    /// forbids.
+    OpeningRestricted,
+    /// Both players have three or four in a row.
+    BothPlayersTerminal,
+    /// The side to move already has a line, but only the player who just
+    /// moved can have made one.
+    WrongMover { player: u8 },
+    /// No single last stone accounts for all of the player's lines, so play
+    /// went on after the game was decided.
+    PlayContinued { player: u8 },
+}
+
+impl fmt::Display for InvalidPosition {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match *self {
+            Self::Overlap => f.write_str("players overlap on occupied cells"),
+            Self::OffBoard => f.write_str("stones outside the board"),
+            Self::StoneCount { p0, p1 } => write!(
+                f,
+                "player 0 has {p0} stones and player 1 has {p1}, which does not fit the side to move"
+            ),
+            Self::SwapNotAllowed => f.write_str("the stones need a swap, but swap is disabled"),
+            Self::OpeningRestricted => {
+                f.write_str("the opening player has no stone where the first move was allowed")
+            }
+            Self::BothPlayersTerminal => f.write_str("both players have a line"),
+            Self::WrongMover { player } => write!(
+                f,
+                "player {player} has a line but is the side to move"
+            ),
+            Self::PlayContinued { player } => write!(
+                f,
+                "player {player} has lines no single last move could make; play went on after the end"
+            ),
+        }
+    }
+}
+
+impl Error for InvalidPosition {}
+
+/// Why a game record could not be read.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum RecordError {
+    /// A header line that is not `[Name "value"]`.
+    Header { line: usize },
+    /// A tag name that is not letters, digits and underscores.
+    TagName(String),
+    /// A value for the named tag with a line break or other control
+    /// character.
+    TagValue(String),
+    /// A `Radius`, `Rules` or `Start` tag whose value cannot be used.
+    Setup(ParseError),
+    /// A move in the move list that is not a move name.
+    Notation { ply: usize, error: ParseError },
+    /// A move the rules do not allow, numbered from 1.
+    IllegalMove { ply: usize, error: MoveError },
+    /// The `Result` tag disagrees with how the moves ended the game.
+    ResultMismatch {
+        recorded: &'static str,
+        actual: &'static str,
+    },
+}
+
+impl fmt::Display for RecordError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match *self {
+            Self::Header { line } => write!(f, "malformed tag on line {line}"),
+            Self::TagName(ref name) => write!(f, "invalid tag name {name:?}"),
+            Self::TagValue(ref name) => write!(f, "control character in tag {name:?}"),
+            Self::Setup(ref error) => write!(f, "bad game setup: {error}"),
+            Self::Notation { ply, ref error } => write!(f, "move {ply}: {error}"),
+            Self::IllegalMove { ply, error } => write!(f, "move {ply}: {error}"),
+            Self::ResultMismatch { recorded, actual } => {
+                write!(f, "the record says {recorded} but the moves give {actual}")
+            }
+        }
+    }
+}
+
+impl Error for RecordError {}
+
+/// Why an SGF-style variation tree could not be read.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum SgfError {
+    /// Text that is not SGF, at this byte offset.
+    Syntax { offset: usize },
+    /// The root does not declare `GM[Yavalath]`.
+    NotYavalath,
+    /// A `SZ`, `RU` or `YP` property whose value cannot be used.
+    Setup(ParseError),
+    /// A move that is not a move name, at this depth in the tree.
+    Notation { ply: usize, error: ParseError },
+    /// A `B` move when player 1 is to move, or `W` when player 0 is.
+    WrongPlayer { ply: usize },
+    /// A move the rules do not allow, at this depth in the tree.
+    IllegalMove { ply: usize, error: MoveError },
+}
+
+impl fmt::Display for SgfError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match *self {
+            Self::Syntax { offset } => write!(f, "SGF syntax error at byte {offset}"),
+            Self::NotYavalath => f.write_str("not a Yavalath game (expected GM[Yavalath])"),
+            Self::Setup(ref error) => write!(f, "bad game setup: {error}"),
+            Self::Notation { ply, ref error } => write!(f, "move {ply}: {error}"),
+            Self::WrongPlayer { ply } => write!(f, "move {ply} is played by the wrong player"),
+            Self::IllegalMove { ply, error } => write!(f, "move {ply}: {error}"),
+        }
+    }
+}
+
+impl Error for SgfError {}
+
+/// Why a shared game code could not be read.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CodeError {
+    /// A character outside the base64url alphabet.
+    BadCharacter { position: usize, found: char },
+    /// A code from a format version this build does not know.
+    UnknownVersion(u8),
+    /// Too short for its header, or a header naming an unsupported board.
+    Malformed,
+    /// A move the rules do not allow, numbered from 1.
+    IllegalMove { ply: usize, error: MoveError },
+}
+
+impl fmt::Display for CodeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match *self {
+            Self::BadCharacter { position, found } => {
+                write!(f, "invalid character {found:?} at position {position}")
+            }
+            Self::UnknownVersion(version) => write!(f, "unknown game code version {version}"),
+            Self::Malformed => f.write_str("malformed game code"),
+            Self::IllegalMove { ply, error } => write!(f, "move {ply}: {error}"),
+        }
+    }
+}
+
+impl Error for CodeError {}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum BookError {
+    /// The data does not start with the book magic bytes.
+    BadMagic,
+    /// A book from a format version this build does not know.
+    UnknownVersion(u8),
+    /// A header naming a board radius this build does not support.
+    UnsupportedBoard(u8),
+    /// The data ends partway through an entry.
+    Truncated,
+    /// Entries out of key order, or a key given twice.
+    Unsorted,
+    /// An entry with no moves, or a move of weight zero.
+    EmptyEntry,
+}
+
+impl fmt::Display for BookError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match *self {
+            Self::BadMagic => f.write_str("not an opening book"),
+            Self::UnknownVersion(version) => write!(f, "unknown book version {version}"),
+            Self::UnsupportedBoard(radius) => write!(f, "unsupported board radius {radius}"),
+            Self::Truncated => f.write_str("truncated opening book"),
+            Self::Unsorted => f.write_str("book entries are not in key order"),
+            Self::EmptyEntry => f.write_str("book entry without a move to play"),
+        }
+    }
+}
+
+impl Error for BookError {}
@@ -0,0 +1,81 @@
+//! Positions, games and game generators shared by the tests.
+
+use crate::board::Board;
+use crate::diagram::parse_diagram;
+use crate::engine::{apply_move_with_meta, one_ply_safe_moves, outcome, Outcome, Position, Rng64};
+use crate::fen::parse_position;
+use crate::game::Game;
+
+/// The game after `moves` from the empty standard board.
+pub fn game_after_moves(moves: &[u8]) -> Game {
+    let mut game = Game::new();
+    for &mv in moves {
+        game.play(mv).expect("move must be legal");
+    }
+    game
+}
+
+/// x to move and win with e4, which threatens both e3 (e1 e2 e3 e4) and d3
+/// (c2 d3 e4 f4).
+pub fn double_threat() -> Position {
+    parse_diagram(
+        "
+            a o . . . .
+           b . . . . . o
+          c . x . . . . .
+         d . . . . . . . .
+        e x x . . . . . . .
+         f . . . x . . . .
+          g . . . . . . o
+           h . . . . . .
+            i . . . . o
+        x to move
+        ",
+    )
+    .unwrap()
+}
+
+/// A standard-board middlegame that x, to move, wins by force.
+pub fn forced_win() -> Position {
+    parse_position("yav1 3o1/6/2ox3/2xx4/2o3x1x/4o2x/7/5o/2o2 x 12 -").unwrap()
+}
+
+/// A standard-board middlegame that o, to move, loses by force.
+pub fn forced_loss() -> Position {
+    parse_position("yav1 1x3/1o4/o6/7x/o3oxo2/1x2x1o1/1xx4/2o3/1x3 o 15 -").unwrap()
+}
+
+/// A radius-2 ending with four empty cells that o, to move, can hold to a
+/// draw.
+pub fn drawn_ending() -> Position {
+    parse_position("yav1 xxo/ooxx/1x1ox/oxx1/oo1 o 15 -").unwrap()
+}
+
+/// The ends of `games` random games on `board` that never make three when
+/// they can avoid it. Game `n` is `plies + n % spread` moves long, or
+/// shorter if it is won, lost or runs out of moves first, so every position
+/// is one a real game can reach; some may be over.
+pub fn random_games(
+    board: &'static Board,
+    seed: u64,
+    games: usize,
+    plies: usize,
+    spread: usize,
+) -> Vec<(Position, Option<(u8, u8)>)> {
+    let mut rng = Rng64::new(seed);
+    (0..games)
+        .map(|game| {
+            let (mut pos, mut jp) = (Position::empty_on(board), None);
+            for _ in 0..plies + game % spread {
+                let Some(mv) = rng.choose(one_ply_safe_moves(pos)) else {
+                    break;
+                };
+                (pos, jp) = apply_move_with_meta(pos, mv).unwrap();
+                if !matches!(outcome(pos, jp), Outcome::Ongoing) {
+                    break;
+                }
+            }
+            (pos, jp)
+        })
+        .collect()
+}
@@ -0,0 +1,185 @@
+use crate::engine::{apply_move_with_meta, legal_moves_with_swap, outcome, Outcome, Position};
+use crate::error::MoveError;
+use crate::moveset::MoveSet;
+
+/// A game played from a starting position, recording every move (including
+/// `SWAP_MOVE`) so it can be undone, redone or jumped through.
+#[derive(Clone, Debug)]
+pub struct Game {
+    start: Position,
+    moves: Vec<u8>,
+    states: Vec<(Position, Option<(u8, u8)>)>,
+    cursor: usize,
+}
+
+impl Default for Game {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Game {
+    pub fn new() -> Self {
+        Self::from_position(Position::empty())
+    }
+
+    pub fn from_position(start: Position) -> Self {
+        Self {
+            start,
+            moves: Vec::new(),
+            states: Vec::new(),
+            cursor: 0,
+        }
+    }
+
+    pub fn start(&self) -> Position {
+        self.start
+    }
+
+    pub fn position(&self) -> Position {
+        match self.cursor {
+            0 => self.start,
+            n => self.states[n - 1].0,
+        }
+    }
+
+    /// Number of moves played to reach the current position.
+    pub fn ply(&self) -> usize {
+        self.cursor
+    }
+
+    /// Moves played up to the current position.
+    pub fn moves(&self) -> &[u8] {
+        &self.moves[..self.cursor]
+    }
+
+    /// The whole recorded line, including moves that were undone but can
+    /// still be redone.
+    pub fn recorded_moves(&self) -> &[u8] {
+        &self.moves
+    }
+
+    pub fn last_move(&self) -> Option<u8> {
+        self.moves().last().copied()
+    }
+
+    /// The result of the current position, judged by the move that was just
+    /// played rather than by scanning both colours.
+    pub fn outcome(&self) -> Outcome {
+        match self.cursor {
+            0 => outcome(self.start, None),
+            n => {
+                let (pos, just_played) = self.states[n - 1];
+                outcome(pos, just_played)
+            }
+        }
+    }
+
+    pub fn is_over(&self) -> bool {
+        !matches!(self.outcome(), Outcome::Ongoing)
+    }
+
+    pub fn legal_moves(&self) -> MoveSet {
+        if self.is_over() {
+            return MoveSet::empty(self.position().swap_move());
+        }
+        legal_moves_with_swap(self.position())
+    }
+
+    /// Plays `mv` from the current position, discarding any redo history.
+    pub fn play(&mut self, mv: u8) -> Result<Outcome, MoveError> {
+        if self.is_over() {
+            return Err(MoveError::GameOver);
+        }
+        let next = apply_move_with_meta(self.position(), mv)?;
+        self.moves.truncate(self.cursor);
+        self.states.truncate(self.cursor);
+        self.moves.push(mv);
+        self.states.push(next);
+        self.cursor += 1;
+        Ok(self.outcome())
+    }
+
+    pub fn can_undo(&self) -> bool {
+        self.cursor > 0
+    }
+
+    pub fn can_redo(&self) -> bool {
+        self.cursor < self.moves.len()
+    }
+
+    /// Steps back one move, returning the move that was taken back.
+    pub fn undo(&mut self) -> Option<u8> {
+        if !self.can_undo() {
+            return None;
+        }
+        self.cursor -= 1;
+        Some(self.moves[self.cursor])
+    }
+
+    /// Replays the next recorded move, returning it.
+    pub fn redo(&mut self) -> Option<u8> {
+        if !self.can_redo() {
+            return None;
+        }
+        self.cursor += 1;
+        Some(self.moves[self.cursor - 1])
+    }
+
+    /// Moves the cursor to `ply` within the recorded line.
+    pub fn jump_to(&mut self, ply: usize) -> bool {
+        if ply > self.moves.len() {
+            return false;
+        }
+        self.cursor = ply;
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::engine::SWAP_MOVE;
+    use crate::fixtures;
+
+    #[test]
+    fn records_swap_and_undoes_it() {
+        let mut game = fixtures::game_after_moves(&[30, SWAP_MOVE]);
+        assert_eq!(game.moves(), &[30, SWAP_MOVE]);
+        assert_eq!(game.position().p1, 1 << 30);
+        assert_eq!(game.undo(), Some(SWAP_MOVE));
+        assert!(game.position().can_swap());
+        assert_eq!(game.redo(), Some(SWAP_MOVE));
+        assert_eq!(game.position().turn, 0);
+    }
+
+    #[test]
+    fn refuses_moves_after_a_win() {
+        // 43 completes 26-35-43-50 for player 0.
+        let mut game = fixtures::game_after_moves(&[26, 0, 35, 1, 50, 5]);
+        assert!(matches!(game.play(43), Ok(Outcome::Win(0, _))));
+        assert!(game.is_over());
+        assert!(game.legal_moves().is_empty());
+        assert_eq!(game.play(60), Err(MoveError::GameOver));
+        assert_eq!(game.ply(), 7);
+    }
+
+    #[test]
+    fn reports_loss_for_the_player_who_just_moved() {
+        // Player 1 completes 0-1-2 while player 0 has no line.
+        let game = fixtures::game_after_moves(&[30, 0, 40, 1, 50, 2]);
+        assert!(matches!(game.outcome(), Outcome::Lose(1, _)));
+    }
+
+    #[test]
+    fn playing_after_undo_discards_redo_line() {
+        let mut game = fixtures::game_after_moves(&[30, 0, 40]);
+        assert!(game.jump_to(1));
+        assert_eq!(game.position().ply, 1);
+        assert!(game.can_redo());
+        game.play(10).unwrap();
+        assert_eq!(game.recorded_moves(), &[30, 10]);
+        assert!(!game.can_redo());
+        assert!(!game.jump_to(3));
+    }
+}
@@ -0,0 +1,220 @@
+use std::sync::OnceLock;
+
+use crate::board::{bit, Bitboard, Board, MAX_RADIUS, MIN_RADIUS};
+use crate::engine::Position;
+
+/// One of the 12 symmetries of the hex board: an optional reflection across
+/// the `q = r` axis followed by `rotation` turns of 60 degrees about the
+/// centre cell.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Transform {
+    rotation: u8,
+    reflect: bool,
+}
+
+impl Transform {
+    pub const IDENTITY: Self = Self {
+        rotation: 0,
+        reflect: false,
+    };
+
+    pub fn new(rotation: u8, reflect: bool) -> Self {
+        Self {
+            rotation: rotation % 6,
+            reflect,
+        }
+    }
+
+    pub fn all() -> [Self; 12] {
+        std::array::from_fn(|i| Self::new((i % 6) as u8, i >= 6))
+    }
+
+    pub fn rotation(self) -> u8 {
+        self.rotation
+    }
+
+    pub fn reflect(self) -> bool {
+        self.reflect
+    }
+
+    fn index(self) -> usize {
+        self.rotation as usize + if self.reflect { 6 } else { 0 }
+    }
+
+    pub fn apply_axial(self, (q, r): (i32, i32)) -> (i32, i32) {
+        let (mut q, mut r) = if self.reflect { (r, q) } else { (q, r) };
+        for _ in 0..self.rotation {
+            (q, r) = (-r, q + r);
+        }
+        (q, r)
+    }
+
+    /// The transform that undoes `self`.
+    pub fn inverse(self) -> Self {
+        if self.reflect {
+            self
+        } else {
+            Self::new(6 - self.rotation, false)
+        }
+    }
+
+    /// The transform equivalent to applying `self` and then `next`.
+    pub fn then(self, next: Self) -> Self {
+        let turned = if next.reflect {
+            6 - self.rotation
+        } else {
+            self.rotation
+        };
+        Self::new(next.rotation + turned, self.reflect ^ next.reflect)
+    }
+
+    pub fn cell(self, board: &Board, idx: u8) -> u8 {
+        cell_maps(board)[self.index()][idx as usize]
+    }
+
+    /// Maps a move; the swap move is unaffected by board symmetry.
+    pub fn apply_move(self, board: &Board, mv: u8) -> u8 {
+        if mv == board.swap_move() {
+            mv
+        } else {
+            self.cell(board, mv)
+        }
+    }
+
+    pub fn bits(self, board: &Board, bits: Bitboard) -> Bitboard {
+        let map = &cell_maps(board)[self.index()];
+        let mut out = 0;
+        let mut rest = bits;
+        while rest != 0 {
+            let idx = rest.trailing_zeros() as usize;
+            out |= bit(map[idx]);
+            rest &= rest - 1;
+        }
+        out
+    }
+
+    pub fn position(self, pos: Position) -> Position {
+        let board = pos.board();
+        Position::from_bitboards_on(
+            board,
+            self.bits(board, pos.p0),
+            self.bits(board, pos.p1),
+            pos.turn,
+            pos.ply,
+        )
+        .with_rules(pos.rules())
+    }
+}
+
+/// For each transform, the image of every cell of `board`.
+fn cell_maps(board: &Board) -> &'static [Vec<u8>; 12] {
+    static CELL_MAPS: [OnceLock<[Vec<u8>; 12]>; (MAX_RADIUS - MIN_RADIUS + 1) as usize] =
+        [const { OnceLock::new() }; (MAX_RADIUS - MIN_RADIUS + 1) as usize];
+    CELL_MAPS[(board.radius() - MIN_RADIUS) as usize].get_or_init(|| {
+        Transform::all().map(|t| {
+            board
+                .axial_cells()
+                .iter()
+                .map(|&cell| {
+                    let (q, r) = t.apply_axial(cell);
+                    board
+                        .index(q, r)
+                        .expect("symmetry maps the board onto itself")
+                })
+                .collect()
+        })
+    })
+}
+
+/// Returns the representative of `pos` under board symmetry together with
+/// the transform that maps `pos` onto it. Symmetric positions share the same
+/// representative.
+pub fn canonical(pos: Position) -> (Position, Transform) {
+    let mut best = (pos, Transform::IDENTITY);
+    for t in Transform::all().into_iter().skip(1) {
+        let image = t.position(pos);
+        if (image.p0, image.p1) < (best.0.p0, best.0.p1) {
+            best = (image, t);
+        }
+    }
+    best
+}
+
+/// All distinct images of `pos` under the 12 board symmetries.
+pub fn symmetric_positions(pos: Position) -> Vec<Position> {
+    let mut images: Vec<Position> = Transform::all().map(|t| t.position(pos)).to_vec();
+    images.sort_unstable_by_key(|p| (p.p0, p.p1));
+    images.dedup();
+    images
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::engine::{outcome, Outcome};
+    use crate::fixtures;
+
+    #[test]
+    fn symmetries_work_on_every_board_size() {
+        for radius in MIN_RADIUS..=MAX_RADIUS {
+            let board = Board::with_radius(radius).unwrap();
+            let last = board.cell_count() as u8 - 1;
+            let pos = Position::empty_on(board).apply(last).unwrap();
+            assert_eq!(symmetric_positions(pos).len(), 6);
+            assert_eq!(
+                canonical(pos).0,
+                canonical(Transform::new(3, true).position(pos)).0
+            );
+        }
+    }
+
+    #[test]
+    fn transforms_are_distinct_and_invertible() {
+        let board = Board::standard();
+        let images: std::collections::HashSet<u8> =
+            Transform::all().iter().map(|t| t.cell(board, 0)).collect();
+        assert_eq!(images.len(), 6);
+        for a in Transform::all() {
+            assert_eq!(a.cell(board, 30), 30);
+            assert_eq!(a.then(a.inverse()), Transform::IDENTITY);
+            for b in Transform::all() {
+                for idx in [0_u8, 7, 22, 60] {
+                    assert_eq!(
+                        a.then(b).cell(board, idx),
+                        b.cell(board, a.cell(board, idx))
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn symmetric_positions_share_a_canonical_form() {
+        let pos = fixtures::game_after_moves(&[0, 22, 7]).position();
+        let (canon, _) = canonical(pos);
+        for t in Transform::all() {
+            let image = t.position(pos);
+            let (image_canon, to_canon) = canonical(image);
+            assert_eq!(image_canon, canon);
+            assert_eq!(to_canon.position(image), canon);
+        }
+        assert_eq!(
+            symmetric_positions(fixtures::game_after_moves(&[30]).position()).len(),
+            1
+        );
+        assert_eq!(symmetric_positions(pos).len(), 12);
+    }
+
+    #[test]
+    fn transforms_preserve_outcomes() {
+        // 43 completes 26-35-43-50 for player 0.
+        let pos = fixtures::game_after_moves(&[26, 0, 35, 1, 50, 5, 43]).position();
+        for t in Transform::all() {
+            let image = t.position(pos);
+            assert!(matches!(
+                outcome(image, Some((0, t.cell(pos.board(), 43)))),
+                Outcome::Win(0, _)
+            ));
+        }
+    }
+}
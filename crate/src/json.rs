@@ -0,0 +1,302 @@
+//! Serde support for the public engine types.
+//!
+//! The JSON shapes are stable and described by the schema in `SCHEMA`
+//! (`schema/yavalath.schema.json`). Cells are indices in the board's
+//! column-major order (see `notation`), as everywhere else at the wasm
+//! boundary.
+//!
+//! A `Position` is its board radius, each player's stones, the side to move
+//! and the rules; the ply and Zobrist key follow from those:
+//!
+//! ```text
+//! {"radius":4,"p0":[30],"p1":[],"turn":1,
+//!  "rules":{"swap":true,"forced_block":false,"first_move_min_distance":0}}
+//! ```
+//!
+//! `rules` may be left out for the standard rules. An `Outcome` has the shape
+//! `check_game_outcome` has always returned, with `state` one of `ongoing`,
+//! `draw`, `win`, `lose` or `invalid`:
+//!
+//! ```text
+//! {"state":"win","winner":0,"loser":null,"line":[27,28,29,30],
+//!  "lines":[[27,28,29,30],[13,21,30]],"rule":"four-despite-three"}
+//! ```
+//!
+//! `Rules`, `SearchReport` and `MoveStats` use their field names.
+
+use serde::de::Error as _;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+use crate::board::{bit, Bitboard, Board};
+use crate::engine::{bit_indices, DecidingRule, Lines, Outcome, Position};
+use crate::error::ParseError;
+use crate::rules::Rules;
+
+/// JSON schema (draft 2020-12) for every shape in this module.
+pub const SCHEMA: &str = include_str!("../schema/yavalath.schema.json");
+
+#[derive(Serialize, Deserialize)]
+struct PositionJson {
+    radius: u8,
+    p0: Vec<u8>,
+    p1: Vec<u8>,
+    turn: u8,
+    #[serde(default)]
+    rules: Rules,
+}
+
+impl Serialize for Position {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        PositionJson {
+            radius: self.board().radius(),
+            p0: bit_indices(self.p0),
+            p1: bit_indices(self.p1),
+            turn: self.turn,
+            rules: self.rules(),
+        }
+        .serialize(serializer)
+    }
+}
+
+/// Checks the stones are on the board and do not overlap, as parsing does;
+/// whether the position is reachable is left to `validate`.
+impl<'de> Deserialize<'de> for Position {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let json = PositionJson::deserialize(deserializer)?;
+        let board = Board::with_radius(json.radius)
+            .ok_or_else(|| D::Error::custom("unsupported board radius"))?;
+        let (p0, p1) = (
+            stones(board, &json.p0).map_err(D::Error::custom)?,
+            stones(board, &json.p1).map_err(D::Error::custom)?,
+        );
+        if p0 & p1 != 0 {
+            return Err(D::Error::custom(ParseError::Overlap));
+        }
+        if json.turn > 1 {
+            return Err(D::Error::custom("turn must be 0 or 1"));
+        }
+        let ply = (p0 | p1).count_ones() as u8;
+        Ok(Position::from_bitboards_on(board, p0, p1, json.turn, ply).with_rules(json.rules))
+    }
+}
+
+fn stones(board: &Board, cells: &[u8]) -> Result<Bitboard, ParseError> {
+    cells.iter().try_fold(0, |acc, &idx| {
+        if (idx as usize) < board.cell_count() {
+            Ok(acc | bit(idx))
+        } else {
+            Err(ParseError::OffBoard)
+        }
+    })
+}
+
+impl Serialize for Lines {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.cells().serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for Lines {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let cells = Vec::<Vec<u8>>::deserialize(deserializer)?;
+        if cells.len() > 3 {
+            return Err(D::Error::custom("at most three lines"));
+        }
+        if cells.iter().flatten().any(|&idx| usize::from(idx) >= 128) {
+            return Err(D::Error::custom(ParseError::OffBoard));
+        }
+        Ok(Lines::from_runs(cells.iter().map(|line| {
+            line.iter().fold(0, |acc: Bitboard, &idx| acc | bit(idx))
+        })))
+    }
+}
+
+/// The outcome payload shared with `encode_outcome` and `encode_error`.
+#[derive(Serialize, Deserialize)]
+struct OutcomeJson {
+    state: String,
+    #[serde(default)]
+    winner: Option<u8>,
+    #[serde(default)]
+    loser: Option<u8>,
+    /// The line that decided the game, kept for older callers.
+    #[serde(default)]
+    line: Vec<u8>,
+    /// Every line the deciding player completed.
+    #[serde(default)]
+    lines: Lines,
+    #[serde(default)]
+    rule: Option<DecidingRule>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    error: Option<String>,
+}
+
+impl OutcomeJson {
+    fn new(state: &str) -> Self {
+        Self {
+            state: state.to_string(),
+            winner: None,
+            loser: None,
+            line: Vec::new(),
+            lines: Lines::default(),
+            rule: None,
+            error: None,
+        }
+    }
+}
+
+impl Serialize for Outcome {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut json = match *self {
+            Outcome::Ongoing => OutcomeJson::new("ongoing"),
+            Outcome::Draw => OutcomeJson::new("draw"),
+            Outcome::Win(player, _) => OutcomeJson {
+                winner: Some(player),
+                ..OutcomeJson::new("win")
+            },
+            Outcome::Lose(player, _) => OutcomeJson {
+                winner: Some(player ^ 1),
+                loser: Some(player),
+                ..OutcomeJson::new("lose")
+            },
+            Outcome::Invalid => OutcomeJson::new("invalid"),
+        };
+        if let Some(lines) = self.lines() {
+            let rule = self.deciding_rule();
+            let deciding = lines.iter().find(|run| match rule {
+                Some(DecidingRule::Three) => run.count_ones() == 3,
+                _ => run.count_ones() >= 4,
+            });
+            json.line = deciding.map(bit_indices).unwrap_or_default();
+            json.lines = lines;
+            json.rule = rule;
+        }
+        json.serialize(serializer)
+    }
+}
+
+/// Reads the outcome back from its lines; `line` and `rule` are derived and
+/// ignored.
+impl<'de> Deserialize<'de> for Outcome {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let json = OutcomeJson::deserialize(deserializer)?;
+        let player = |player: Option<u8>, field| {
+            player
+                .filter(|&p| p <= 1)
+                .ok_or_else(|| D::Error::custom(format!("{} outcome needs a {field}", json.state)))
+        };
+        match json.state.as_str() {
+            "ongoing" => Ok(Outcome::Ongoing),
+            "draw" => Ok(Outcome::Draw),
+            "win" => Ok(Outcome::Win(player(json.winner, "winner")?, json.lines)),
+            "lose" => Ok(Outcome::Lose(player(json.loser, "loser")?, json.lines)),
+            "invalid" => Ok(Outcome::Invalid),
+            other => Err(D::Error::custom(format!("unknown outcome state {other:?}"))),
+        }
+    }
+}
+
+/// An `invalid` outcome carrying the reason a board or move was rejected.
+pub(crate) fn encode_invalid(error: &dyn std::error::Error) -> String {
+    let json = OutcomeJson {
+        error: Some(error.to_string()),
+        ..OutcomeJson::new("invalid")
+    };
+    serde_json::to_string(&json).unwrap_or_else(|_| "{\"state\":\"invalid\"}".to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::engine::{apply_move_with_meta, outcome, search, SearchReport};
+    use crate::notation::parse_moves;
+
+    fn played(moves: &str) -> (Position, Option<(u8, u8)>) {
+        let board = Board::standard();
+        parse_moves(board, moves)
+            .unwrap()
+            .into_iter()
+            .fold((Position::empty(), None), |(pos, _), mv| {
+                apply_move_with_meta(pos, mv).unwrap()
+            })
+    }
+
+    #[test]
+    fn position_shape_and_round_trip() {
+        let (pos, _) = played("e5 swap d4");
+        let json = serde_json::to_string(&pos).unwrap();
+        assert_eq!(
+            json,
+            "{\"radius\":4,\"p0\":[21],\"p1\":[30],\"turn\":1,\"rules\":\
+             {\"swap\":true,\"forced_block\":false,\"first_move_min_distance\":0}}"
+        );
+        assert_eq!(serde_json::from_str::<Position>(&json).unwrap(), pos);
+
+        let board = Board::with_radius(5).unwrap();
+        let pos = Position::empty_on(board)
+            .with_rules("no-swap,block".parse().unwrap())
+            .apply(90)
+            .unwrap();
+        let json = serde_json::to_string(&pos).unwrap();
+        assert_eq!(serde_json::from_str::<Position>(&json).unwrap(), pos);
+    }
+
+    #[test]
+    fn position_rejects_bad_boards() {
+        let parse = |json: &str| serde_json::from_str::<Position>(json).map_err(|e| e.to_string());
+        assert!(parse(r#"{"radius":4,"p0":[30],"p1":[],"turn":1}"#).is_ok());
+        assert!(parse(r#"{"radius":9,"p0":[],"p1":[],"turn":0}"#)
+            .unwrap_err()
+            .contains("radius"));
+        assert!(parse(r#"{"radius":4,"p0":[61],"p1":[],"turn":1}"#)
+            .unwrap_err()
+            .contains("outside"));
+        assert!(parse(r#"{"radius":4,"p0":[30],"p1":[30],"turn":0}"#)
+            .unwrap_err()
+            .contains("overlap"));
+    }
+
+    #[test]
+    fn outcome_round_trips() {
+        // Red fills the gap in e1 e2 _ e4 e5, making a run of five.
+        let (pos, jp) = played("e1 a1 e2 a3 e4 i1 e5 i3 e3");
+        let won = outcome(pos, jp);
+        assert_eq!(won.deciding_rule(), Some(DecidingRule::Four));
+        for result in [Outcome::Ongoing, Outcome::Draw, Outcome::Invalid, won] {
+            let json = serde_json::to_string(&result).unwrap();
+            assert_eq!(serde_json::from_str::<Outcome>(&json).unwrap(), result);
+        }
+        let json = serde_json::to_string(&won).unwrap();
+        assert!(json.contains("\"lines\":[[26,27,28,29,30]]"), "{json}");
+        assert!(serde_json::from_str::<Outcome>(r#"{"state":"win"}"#).is_err());
+    }
+
+    #[test]
+    fn search_reports_round_trip() {
+        let (pos, _) = played("e5 d4");
+        let report = search(pos, 20.0, 0);
+        assert_eq!(report.best_move, report.moves.first().map(|m| m.mv));
+        assert!(report.iterations > 0);
+        let json = serde_json::to_string(&report).unwrap();
+        assert!(json.contains("\"move\":"));
+        assert_eq!(serde_json::from_str::<SearchReport>(&json).unwrap(), report);
+    }
+
+    #[test]
+    fn schema_describes_every_shape() {
+        let schema: serde_json::Value = serde_json::from_str(SCHEMA).unwrap();
+        for name in [
+            "Rules",
+            "Position",
+            "Outcome",
+            "SearchReport",
+            "MoveStats",
+            "SolveReport",
+            "ProofLine",
+            "SwapAdvice",
+            "ThreePlayerOutcome",
+        ] {
+            assert!(schema["$defs"][name].is_object(), "{name} missing");
+        }
+    }
+}
@@ -1,19 +1,77 @@
 use wasm_bindgen::prelude::*;
 
+pub mod alphabeta;
+pub mod board;
+pub mod book;
+pub mod code;
+pub mod diagram;
+pub mod endgame;
 pub mod engine;
+pub mod error;
+pub mod fen;
+#[cfg(test)]
+mod fixtures;
+pub mod game;
+pub mod geometry;
+pub mod json;
+pub mod moveset;
+pub mod notation;
+pub mod pns;
+pub mod record;
+pub mod rules;
+pub mod sgf;
+pub mod svg;
+pub mod swap;
+pub mod threats;
+pub mod three_player;
+pub mod validate;
+
+/// Turns an engine error into the exception thrown to JS.
+fn js_error(error: impl std::error::Error) -> JsValue {
+    JsValue::from_str(&error.to_string())
+}
+
+/// Reads a position string (`yav1 ...`, see `fen`) or the historical board
+/// hex on the standard board.
+fn parse_position(text: &str) -> Result<engine::Position, error::ParseError> {
+    fen::parse_position_or_hex(board::Board::standard(), rules::Rules::STANDARD, text)
+}
+
+/// The outcome of a parsed position, or the reason it could not have been
+/// reached in play.
+fn checked_outcome(parsed: Result<engine::Position, error::ParseError>) -> String {
+    match parsed {
+        Ok(pos) => match validate::validate(pos) {
+            Ok(()) => engine::encode_outcome(engine::outcome(pos, None)),
+            Err(err) => engine::encode_error(&err),
+        },
+        Err(err) => engine::encode_error(&err),
+    }
+}
 
 #[wasm_bindgen]
 pub fn check_game_outcome(board_hex: &str) -> String {
-    let Ok(pos) = engine::parse_board_hex(board_hex) else {
-        return engine::encode_outcome(engine::Outcome::Invalid);
-    };
+    checked_outcome(parse_position(board_hex))
+}
 
-    engine::encode_outcome(engine::outcome(pos, None))
+/// `{"valid":true}`, or `{"valid":false,"reason":...}` for a board that is
+/// malformed or could not arise in play.
+#[wasm_bindgen]
+pub fn validate_position(board: &str, rules: &str) -> String {
+    let reason = match parse_with_rules(board, rules) {
+        Ok(pos) => validate::validate(pos).err().map(|err| err.to_string()),
+        Err(err) => Some(err.to_string()),
+    };
+    let payload = match reason {
+        None => serde_json::json!({ "valid": true }),
+        Some(reason) => serde_json::json!({ "valid": false, "reason": reason }),
+    };
+    payload.to_string()
 }
 
 #[wasm_bindgen]
 pub fn pick_move(board_hex: &str, thinking_time_ms: f64) -> i32 {
-    let Ok(pos) = engine::parse_board_hex(board_hex) else {
+    let Ok(pos) = parse_position(board_hex) else {
         return -1;
     };
 
@@ -24,7 +82,35 @@ pub fn pick_move(board_hex: &str, thinking_time_ms: f64) -> i32 {
 
 #[wasm_bindgen]
 pub fn pick_move_with_strength(board_hex: &str, thinking_time_ms: f64, strength: u8) -> i32 {
-    let Ok(pos) = engine::parse_board_hex(board_hex) else {
+    let Ok(pos) = parse_position(board_hex) else {
+        return -1;
+    };
+
+    engine::best_move_with_strength(pos, thinking_time_ms, strength)
+        .map(i32::from)
+        .unwrap_or(-1)
+}
+
+/// Parses a board under house rules given as a spec such as
+/// `"no-swap,block"`; see `rules::Rules`.
+fn parse_with_rules(board_hex: &str, rules: &str) -> Result<engine::Position, error::ParseError> {
+    let rules = rules.parse::<rules::Rules>()?;
+    fen::parse_position_or_hex(board::Board::standard(), rules, board_hex)
+}
+
+#[wasm_bindgen]
+pub fn check_game_outcome_with_rules(board_hex: &str, rules: &str) -> String {
+    checked_outcome(parse_with_rules(board_hex, rules))
+}
+
+#[wasm_bindgen]
+pub fn pick_move_with_rules(
+    board_hex: &str,
+    thinking_time_ms: f64,
+    strength: u8,
+    rules: &str,
+) -> i32 {
+    let Ok(pos) = parse_with_rules(board_hex, rules) else {
         return -1;
     };
 
@@ -32,3 +118,532 @@ pub fn pick_move_with_strength(board_hex: &str, thinking_time_ms: f64, strength:
         .map(i32::from)
         .unwrap_or(-1)
 }
+
+/// As `pick_move_with_rules`, with the searcher named: `"mcts"` or
+/// `"alpha-beta"`. Returns -1 if the board, rules or searcher cannot be read.
+#[wasm_bindgen]
+pub fn pick_move_with_searcher(
+    board_hex: &str,
+    thinking_time_ms: f64,
+    strength: u8,
+    rules: &str,
+    searcher: &str,
+) -> i32 {
+    let (Ok(pos), Ok(searcher)) = (
+        parse_with_rules(board_hex, rules),
+        searcher.parse::<engine::Searcher>(),
+    ) else {
+        return -1;
+    };
+
+    searcher
+        .best_move(pos, thinking_time_ms, strength)
+        .map(i32::from)
+        .unwrap_or(-1)
+}
+
+#[wasm_bindgen]
+pub fn legal_moves_with_rules(board_hex: &str, rules: &str) -> Vec<u8> {
+    parse_with_rules(board_hex, rules)
+        .map(|pos| engine::legal_moves_with_swap(pos).to_vec())
+        .unwrap_or_default()
+}
+
+/// The search's report for a position as JSON: the move, playouts and
+/// per-move statistics (`engine::SearchReport`). Throws if the board cannot
+/// be read.
+#[wasm_bindgen]
+pub fn analyse_position(
+    board: &str,
+    rules: &str,
+    thinking_time_ms: f64,
+    strength: u8,
+) -> Result<String, JsValue> {
+    let pos = parse_with_rules(board, rules).map_err(js_error)?;
+    let report = engine::search(pos, thinking_time_ms, strength);
+    serde_json::to_string(&report).map_err(js_error)
+}
+
+/// As `analyse_position`, with the searcher named: `"mcts"` or
+/// `"alpha-beta"`. Reports from alpha-beta also give the depth reached.
+#[wasm_bindgen]
+pub fn analyse_position_with_searcher(
+    board: &str,
+    rules: &str,
+    thinking_time_ms: f64,
+    strength: u8,
+    searcher: &str,
+) -> Result<String, JsValue> {
+    let pos = parse_with_rules(board, rules).map_err(js_error)?;
+    let searcher = searcher.parse::<engine::Searcher>().map_err(js_error)?;
+    let report = searcher.search(pos, thinking_time_ms, strength);
+    serde_json::to_string(&report).map_err(js_error)
+}
+
+/// Whether the engine would swap after the first move in `board`, as
+/// JSON (`swap::SwapAdvice`): the decision, the value of each choice to the
+/// second player and how confident it is. Standard boards are answered
+/// from a precomputed table; others take up to `thinking_time_ms` of
+/// search. `undefined` when the side to move has no swap to decide.
+#[wasm_bindgen]
+pub fn swap_advice(
+    board: &str,
+    rules: &str,
+    thinking_time_ms: f64,
+    strength: u8,
+) -> Result<Option<String>, JsValue> {
+    let pos = parse_with_rules(board, rules).map_err(js_error)?;
+    swap::evaluate(pos, thinking_time_ms, strength)
+        .map(|advice| serde_json::to_string(&advice).map_err(js_error))
+        .transpose()
+}
+
+/// The shortest win for the side to move made only of four-threats, in
+/// at most `max_moves` of its own moves (`threats::find_forced_win`): the
+/// whole forcing line, both sides' moves. `undefined` when there is none.
+#[wasm_bindgen]
+pub fn forced_win(board: &str, rules: &str, max_moves: u8) -> Result<Option<Vec<u8>>, JsValue> {
+    let pos = parse_with_rules(board, rules).map_err(js_error)?;
+    Ok(threats::find_forced_win(pos, max_moves))
+}
+
+/// Solves a board exactly with proof-number search (`pns::solve`), as JSON:
+/// the verdict for the side to move, the winning move and the winner's
+/// strategy. Gives `unknown` once `max_nodes` positions have been searched.
+#[wasm_bindgen]
+pub fn solve_position(board: &str, rules: &str, max_nodes: u32) -> Result<String, JsValue> {
+    let pos = parse_with_rules(board, rules).map_err(js_error)?;
+    serde_json::to_string(&pns::solve(pos, max_nodes)).map_err(js_error)
+}
+
+/// A board (position string, JSON or hex) as a JSON position.
+#[wasm_bindgen]
+pub fn position_to_json(board: &str, rules: &str) -> Result<String, JsValue> {
+    let pos = parse_with_rules(board, rules).map_err(js_error)?;
+    serde_json::to_string(&pos).map_err(js_error)
+}
+
+/// Parses SVG options given as JSON (see `svg::SvgOptions`); an empty
+/// string means the defaults.
+fn svg_options(options: &str) -> Result<svg::SvgOptions, JsValue> {
+    if options.trim().is_empty() {
+        return Ok(svg::SvgOptions::default());
+    }
+    serde_json::from_str(options).map_err(js_error)
+}
+
+/// A standalone SVG drawing of a board, with highlights given as JSON such
+/// as `{"last_move":30,"heatmap":[[22,0.5]],"coordinates":true}`.
+#[wasm_bindgen]
+pub fn render_svg(board: &str, rules: &str, options: &str) -> Result<String, JsValue> {
+    let pos = parse_with_rules(board, rules).map_err(js_error)?;
+    Ok(svg::render_svg(pos, &svg_options(options)?))
+}
+
+/// The header tags of a game record as a JSON object, after checking the
+/// whole record.
+#[wasm_bindgen]
+pub fn record_tags(text: &str) -> Result<String, JsValue> {
+    let record = record::parse_record(text).map_err(js_error)?;
+    let tags: serde_json::Map<String, serde_json::Value> = record
+        .tags()
+        .iter()
+        .map(|(name, value)| (name.clone(), value.clone().into()))
+        .collect();
+    Ok(serde_json::Value::Object(tags).to_string())
+}
+
+/// The JSON schema for positions, outcomes and search reports.
+#[wasm_bindgen]
+pub fn json_schema() -> String {
+    json::SCHEMA.to_string()
+}
+
+/// The name of a move on the board of the given radius (`"e5"`, `"swap"`),
+/// or an empty string if there is no such move.
+#[wasm_bindgen]
+pub fn move_to_notation(mv: u8, radius: u8) -> String {
+    board::Board::with_radius(radius)
+        .and_then(|board| notation::move_name(board, mv))
+        .unwrap_or_default()
+}
+
+/// The move index for a cell name or `"swap"`, or -1 if it is not a move on
+/// the board of the given radius.
+#[wasm_bindgen]
+pub fn notation_to_move(text: &str, radius: u8) -> i32 {
+    board::Board::with_radius(radius)
+        .and_then(|board| notation::parse_move(board, text).ok())
+        .map(i32::from)
+        .unwrap_or(-1)
+}
+
+#[wasm_bindgen(js_name = Game)]
+pub struct GameHandle {
+    inner: game::Game,
+    options: engine::MoveOptions,
+}
+
+#[wasm_bindgen(js_class = Game)]
+impl GameHandle {
+    #[wasm_bindgen(constructor)]
+    pub fn new() -> Self {
+        Self::from_game(game::Game::new())
+    }
+
+    /// A new game on the hex-hex board of the given radius.
+    pub fn with_radius(radius: u8) -> Result<GameHandle, JsValue> {
+        let board = board::Board::with_radius(radius)
+            .ok_or_else(|| JsValue::from_str("unsupported board radius"))?;
+        Ok(Self::from_game(game::Game::from_position(
+            engine::Position::empty_on(board),
+        )))
+    }
+
+    /// A new game on the given radius under house rules given as a spec
+    /// such as `"no-swap,no-centre"`.
+    pub fn with_rules(radius: u8, rules: &str) -> Result<GameHandle, JsValue> {
+        let board = board::Board::with_radius(radius)
+            .ok_or_else(|| JsValue::from_str("unsupported board radius"))?;
+        let rules = rules.parse::<rules::Rules>().map_err(js_error)?;
+        Ok(Self::from_game(game::Game::from_position(
+            engine::Position::empty_on(board).with_rules(rules),
+        )))
+    }
+
+    /// A game loaded from a record (see `record`), positioned after its last
+    /// move. Throws the reason if a move is illegal or the result does not
+    /// match.
+    pub fn from_record(text: &str) -> Result<GameHandle, JsValue> {
+        let record = record::parse_record(text).map_err(js_error)?;
+        Ok(Self::from_game(record.game().clone()))
+    }
+
+    /// The game so far as a record, with extra header tags given as a JSON
+    /// object such as `{"X":"Alice","O":"Bob","Date":"2026.10.18"}`. Throws
+    /// on a tag name that is not letters, digits and underscores, or a value
+    /// with a line break or other control character.
+    pub fn to_record(&self, tags: &str) -> Result<String, JsValue> {
+        let mut record = record::GameRecord::from_game(&self.inner);
+        if !tags.trim().is_empty() {
+            let tags: std::collections::BTreeMap<String, String> =
+                serde_json::from_str(tags).map_err(js_error)?;
+            for (name, value) in &tags {
+                record.set_tag(name, value).map_err(js_error)?;
+            }
+        }
+        Ok(record.to_string())
+    }
+
+    /// A game restored from a share code (see `code`), with its whole move
+    /// history so it can be undone.
+    pub fn from_code(code: &str) -> Result<GameHandle, JsValue> {
+        Ok(Self::from_game(code::decode_game(code).map_err(js_error)?))
+    }
+
+    /// A compact URL-safe code for the moves so far, for share links. Throws
+    /// if the game started from a set-up position rather than an empty board.
+    pub fn to_code(&self) -> Result<String, JsValue> {
+        code::encode_game(&self.inner)
+            .ok_or_else(|| JsValue::from_str("only games from an empty board have a code"))
+    }
+
+    /// The rules in force, as a spec string.
+    pub fn rules(&self) -> String {
+        self.inner.position().rules().to_string()
+    }
+
+    pub fn radius(&self) -> u8 {
+        self.inner.position().board().radius()
+    }
+
+    /// The move index that means "swap" on this game's board.
+    pub fn swap_move(&self) -> u8 {
+        self.inner.position().swap_move()
+    }
+
+    /// Plays `mv`, throwing the reason if it is not allowed.
+    pub fn play(&mut self, mv: u8) -> Result<(), JsValue> {
+        self.inner.play(mv).map(|_| ()).map_err(js_error)
+    }
+
+    /// Plays a move written in notation, e.g. `"e5"` or `"swap"`.
+    pub fn play_notation(&mut self, text: &str) -> Result<(), JsValue> {
+        let mv = notation::parse_move(self.inner.position().board(), text).map_err(js_error)?;
+        self.play(mv)
+    }
+
+    pub fn undo(&mut self) -> i32 {
+        self.inner.undo().map(i32::from).unwrap_or(-1)
+    }
+
+    pub fn redo(&mut self) -> i32 {
+        self.inner.redo().map(i32::from).unwrap_or(-1)
+    }
+
+    pub fn jump_to(&mut self, ply: usize) -> bool {
+        self.inner.jump_to(ply)
+    }
+
+    pub fn ply(&self) -> usize {
+        self.inner.ply()
+    }
+
+    pub fn can_undo(&self) -> bool {
+        self.inner.can_undo()
+    }
+
+    pub fn can_redo(&self) -> bool {
+        self.inner.can_redo()
+    }
+
+    pub fn can_swap(&self) -> bool {
+        !self.inner.is_over() && self.inner.position().can_swap()
+    }
+
+    pub fn turn(&self) -> u8 {
+        self.inner.position().turn
+    }
+
+    pub fn moves(&self) -> Vec<u8> {
+        self.inner.moves().to_vec()
+    }
+
+    /// The moves so far in notation, e.g. `"e5 swap d4"`.
+    pub fn moves_notation(&self) -> String {
+        notation::moves_name(self.inner.position().board(), self.inner.moves())
+    }
+
+    pub fn legal_moves(&self) -> Vec<u8> {
+        self.inner.legal_moves().to_vec()
+    }
+
+    pub fn board_hex(&self) -> String {
+        engine::encode_board_hex(self.inner.position())
+    }
+
+    /// The current position as a `yav1` position string.
+    pub fn position_string(&self) -> String {
+        fen::encode_position(self.inner.position())
+    }
+
+    /// The current position as JSON (see `json`).
+    pub fn position_json(&self) -> String {
+        serde_json::to_string(&self.inner.position()).unwrap_or_default()
+    }
+
+    pub fn outcome(&self) -> String {
+        engine::encode_outcome(self.inner.outcome())
+    }
+
+    /// The current position as SVG, marking the last move and any finished
+    /// line; `options` is JSON as for `render_svg` and may override both.
+    pub fn svg(&self, options: &str) -> Result<String, JsValue> {
+        let mut options = svg_options(options)?;
+        options.last_move = options.last_move.or(self.inner.last_move());
+        options.outcome = options.outcome.or(Some(self.inner.outcome()));
+        Ok(svg::render_svg(self.inner.position(), &options))
+    }
+
+    pub fn pick_move(&self, thinking_time_ms: f64, strength: u8) -> i32 {
+        if self.inner.is_over() {
+            return -1;
+        }
+        engine::Searcher::Mcts
+            .best_move_with(
+                self.inner.position(),
+                thinking_time_ms,
+                strength,
+                self.options,
+            )
+            .map(i32::from)
+            .unwrap_or(-1)
+    }
+
+    /// Turns opening-book moves on or off for this game's `pick_move*`.
+    /// On by default.
+    pub fn set_book_enabled(&mut self, enabled: bool) {
+        self.options.book = enabled;
+    }
+
+    /// Picks among weighted book moves; the same seed and position always
+    /// give the same move. 0 by default.
+    pub fn set_book_seed(&mut self, seed: u32) {
+        self.options.seed = u64::from(seed);
+    }
+
+    /// Turns the precomputed swap decisions used by this game's
+    /// `pick_move*` on or off. On by default.
+    pub fn set_swap_table_enabled(&mut self, enabled: bool) {
+        self.options.swap_table = enabled;
+    }
+
+    /// The forcing line from the current position, as for `forced_win`.
+    pub fn forced_win(&self, max_moves: u8) -> Option<Vec<u8>> {
+        if self.inner.is_over() {
+            return None;
+        }
+        threats::find_forced_win(self.inner.position(), max_moves)
+    }
+
+    /// The swap decision in the current position, as for `swap_advice`.
+    pub fn swap_advice(
+        &self,
+        thinking_time_ms: f64,
+        strength: u8,
+    ) -> Result<Option<String>, JsValue> {
+        swap::evaluate(self.inner.position(), thinking_time_ms, strength)
+            .map(|advice| serde_json::to_string(&advice).map_err(js_error))
+            .transpose()
+    }
+
+    /// The current position solved as by `solve_position`.
+    pub fn solve(&self, max_nodes: u32) -> Result<String, JsValue> {
+        serde_json::to_string(&pns::solve(self.inner.position(), max_nodes)).map_err(js_error)
+    }
+
+    /// As `pick_move`, with the searcher named: `"mcts"` or `"alpha-beta"`.
+    pub fn pick_move_with_searcher(
+        &self,
+        thinking_time_ms: f64,
+        strength: u8,
+        searcher: &str,
+    ) -> Result<i32, JsValue> {
+        let searcher = searcher.parse::<engine::Searcher>().map_err(js_error)?;
+        if self.inner.is_over() {
+            return Ok(-1);
+        }
+        Ok(searcher
+            .best_move_with(
+                self.inner.position(),
+                thinking_time_ms,
+                strength,
+                self.options,
+            )
+            .map(i32::from)
+            .unwrap_or(-1))
+    }
+}
+
+impl GameHandle {
+    fn from_game(inner: game::Game) -> Self {
+        Self {
+            inner,
+            options: engine::MoveOptions::default(),
+        }
+    }
+}
+
+impl Default for GameHandle {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[wasm_bindgen(js_name = ThreePlayerGame)]
+pub struct ThreePlayerGameHandle {
+    pos: three_player::ThreePlayerPosition,
+    result: three_player::ThreePlayerOutcome,
+    moves: Vec<u8>,
+}
+
+#[wasm_bindgen(js_class = ThreePlayerGame)]
+impl ThreePlayerGameHandle {
+    #[wasm_bindgen(constructor)]
+    pub fn new() -> Self {
+        Self::on_board(board::Board::standard())
+    }
+
+    pub fn with_radius(radius: u8) -> Result<ThreePlayerGameHandle, JsValue> {
+        let board = board::Board::with_radius(radius)
+            .ok_or_else(|| JsValue::from_str("unsupported board radius"))?;
+        Ok(Self::on_board(board))
+    }
+
+    /// Plays `mv`, throwing the reason if it is not allowed.
+    pub fn play(&mut self, mv: u8) -> Result<(), JsValue> {
+        if self.result.is_over() {
+            return Err(js_error(error::MoveError::GameOver));
+        }
+        let next = self.pos.apply(mv).map_err(js_error)?;
+        self.result = three_player::outcome(next, Some((self.pos.turn, mv)));
+        self.pos = next;
+        self.moves.push(mv);
+        Ok(())
+    }
+
+    pub fn turn(&self) -> u8 {
+        self.pos.turn
+    }
+
+    /// Players still in the game.
+    pub fn alive(&self) -> Vec<u8> {
+        (0..three_player::PLAYERS as u8)
+            .filter(|&p| self.pos.is_alive(p))
+            .collect()
+    }
+
+    pub fn moves(&self) -> Vec<u8> {
+        self.moves.clone()
+    }
+
+    pub fn legal_moves(&self) -> Vec<u8> {
+        if self.result.is_over() {
+            return Vec::new();
+        }
+        self.pos.legal_moves().to_vec()
+    }
+
+    /// Cells the side to move must block, if any.
+    pub fn forced_moves(&self) -> Vec<u8> {
+        self.pos.forced_blocks().to_vec()
+    }
+
+    /// Stones per cell: the owning player, or -1 for an empty cell.
+    pub fn cells(&self) -> Vec<i8> {
+        (0..self.pos.board().cell_count() as u8)
+            .map(|idx| {
+                self.pos
+                    .stones
+                    .iter()
+                    .position(|&s| s & (1 << idx) != 0)
+                    .map_or(-1, |p| p as i8)
+            })
+            .collect()
+    }
+
+    /// The state after the last move, as JSON.
+    pub fn outcome(&self) -> String {
+        serde_json::to_string(&self.result).unwrap_or_default()
+    }
+
+    pub fn pick_move(&self, thinking_time_ms: f64, paranoid: bool) -> i32 {
+        if self.result.is_over() {
+            return -1;
+        }
+        let backup = if paranoid {
+            three_player::Backup::Paranoid
+        } else {
+            three_player::Backup::MaxN
+        };
+        three_player::best_move(self.pos, thinking_time_ms, backup)
+            .map(i32::from)
+            .unwrap_or(-1)
+    }
+}
+
+impl ThreePlayerGameHandle {
+    fn on_board(board: &'static board::Board) -> Self {
+        Self {
+            pos: three_player::ThreePlayerPosition::empty_on(board),
+            result: three_player::ThreePlayerOutcome::Ongoing,
+            moves: Vec::new(),
+        }
+    }
+}
+
+impl Default for ThreePlayerGameHandle {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -1,1599 +0,0 @@
-#![allow(dead_code)]
-
-pub static MIRROR_IDXS: [(u8, u8); 26] = [
-    (48, 59),
-    (30, 35),
-    (18, 17),
-    (1, 5),
-    (2, 4),
-    (14, 15),
-    (32, 27),
-    (56, 45),
-    (54, 53),
-    (36, 29),
-    (7, 11),
-    (13, 16),
-    (8, 10),
-    (26, 33),
-    (50, 51),
-    (60, 47),
-    (19, 23),
-    (25, 34),
-    (31, 28),
-    (20, 22),
-    (44, 57),
-    (37, 41),
-    (43, 58),
-    (49, 52),
-    (55, 46),
-    (38, 40),
-];
-
-pub static LOSING_MASKS: [u64; 43] = [
-    0x0041040000000000,
-    0x1041000000000000,
-    0x1040002000000000,
-    0x0800000041000000,
-    0x0000001041000000,
-    0x0000001040080000,
-    0x0000081000080000,
-    0x0020000800001000,
-    0x0000000800041000,
-    0x0000000000041080,
-    0x0000000002040080,
-    0x0002000002000080,
-    0x0000800020020000,
-    0x0000000020020040,
-    0x0000000000020042,
-    0x0000000000002042,
-    0x0000000080002002,
-    0x0080000080002000,
-    0x0000020000800800,
-    0x0000000000800820,
-    0x0000000000000821,
-    0x0000000000000025,
-    0x0000000000000105,
-    0x0000000000100104,
-    0x0000004000100100,
-    0x0400000400010000,
-    0x0000000400010010,
-    0x0000000000010018,
-    0x0000000000004018,
-    0x0000000004004008,
-    0x0000100004004000,
-    0x0010000010000400,
-    0x0000000010008400,
-    0x0000000000008600,
-    0x0000000100008200,
-    0x0004000100000200,
-    0x0000400200400000,
-    0x0000000208400000,
-    0x0000000208200000,
-    0x0100000008200000,
-    0x0208010000000000,
-    0x0208200000000000,
-    0x0008208000000000,
-];
-
-pub static WINNING_MASKS: [u64; 34] = [
-    0x1041040000000000,
-    0x1041002000000000,
-    0x0800001041000000,
-    0x0000001041080000,
-    0x0000081040080000,
-    0x0020000800041000,
-    0x0000000800041080,
-    0x0000000002041080,
-    0x0002000002040080,
-    0x0000800020020040,
-    0x0000000020020042,
-    0x0000000000022042,
-    0x0000000080002042,
-    0x0080000080002002,
-    0x0000020000800820,
-    0x0000000000800821,
-    0x0000000000000825,
-    0x0000000000000125,
-    0x0000000000100105,
-    0x0000004000100104,
-    0x0400000400010010,
-    0x0000000400010018,
-    0x0000000000014018,
-    0x0000000004004018,
-    0x0000100004004008,
-    0x0010000010008400,
-    0x0000000010008600,
-    0x0000000100008600,
-    0x0004000100008200,
-    0x0000400208400000,
-    0x0000000208600000,
-    0x0100000208200000,
-    0x0208210000000000,
-    0x0208208000000000,
-];
-
-pub static LOSE_CHECKS: [[u64; 9]; 61] = [
-    [2081, 4161, 131, 37, 73, 19, 261, 521, 1041],
-    [
-        1074003970, 131138, 262150, 8258, 16390, 2147491842, 524418, 131, 19,
-    ],
-    [
-        33562628, 262150, 8204, 16390, 32780, 4294983684, 37, 261, 1048836,
-    ],
-    [
-        8204, 32780, 8589967368, 73, 521, 2097672, 65560, 16408, 67125256,
-    ],
-    [
-        19,
-        1041,
-        4195344,
-        17179934736,
-        65560,
-        131120,
-        16408,
-        32816,
-        134250512,
-    ],
-    [
-        8390688,
-        2081,
-        37,
-        34359869472,
-        131120,
-        262240,
-        32816,
-        65632,
-        268501024,
-    ],
-    [
-        537002048,
-        131138,
-        8258,
-        16781376,
-        4161,
-        73,
-        68719738944,
-        262240,
-        65632,
-    ],
-    [
-        18014467228958848,
-        68719485056,
-        266368,
-        8576,
-        33816704,
-        562949986975872,
-        137439477888,
-        524418,
-        131,
-    ],
-    [
-        562952100905216,
-        2147500288,
-        8576,
-        17152,
-        67117312,
-        1125899973951744,
-        261,
-        1048836,
-        274878955776,
-    ],
-    [
-        17152,
-        134234624,
-        2251799947903488,
-        521,
-        2097672,
-        549757911552,
-        34304,
-        4295000576,
-        1125904201810432,
-    ],
-    [
-        1041,
-        4195344,
-        1099515823104,
-        4503599895806976,
-        268469248,
-        34304,
-        68608,
-        8590001152,
-        2251808403620864,
-    ],
-    [
-        2199031646208,
-        8390688,
-        2081,
-        9007199791613952,
-        536938496,
-        68608,
-        137216,
-        17180002304,
-        4503616807241728,
-    ],
-    [
-        9007233614483456,
-        34360004608,
-        266368,
-        4398063292416,
-        16781376,
-        4161,
-        18014399583227904,
-        1073876992,
-        137216,
-    ],
-    [
-        68719485056,
-        8576,
-        67117312,
-        8796126584832,
-        33562628,
-        8204,
-        8258,
-        2147491842,
-        36028799166455808,
-    ],
-    [
-        2147500288,
-        17152,
-        134234624,
-        16390,
-        4294983684,
-        72057598332911616,
-        16408,
-        67125256,
-        17592253169664,
-    ],
-    [
-        32780,
-        8589967368,
-        144115196665823232,
-        32816,
-        134250512,
-        35184506339328,
-        268469248,
-        34304,
-        4295000576,
-    ],
-    [
-        288230393331646464,
-        17179934736,
-        65560,
-        65632,
-        268501024,
-        70369012678656,
-        536938496,
-        68608,
-        8590001152,
-    ],
-    [
-        140738025357312,
-        537002048,
-        131138,
-        576460786663292928,
-        34359869472,
-        131120,
-        1073876992,
-        137216,
-        17180002304,
-    ],
-    [
-        34360004608,
-        266368,
-        33816704,
-        281476050714624,
-        1074003970,
-        262150,
-        1152921573326585856,
-        68719738944,
-        262240,
-    ],
-    [
-        1152921504640925696,
-        2181562368,
-        69793742848,
-        8864813023232,
-        137439477888,
-        524418,
-        0xFFFFFFFFFFFFFFFF,
-        0xFFFFFFFFFFFFFFFF,
-        0xFFFFFFFFFFFFFFFF,
-    ],
-    [
-        36028797087121408,
-        4363124736,
-        2182086656,
-        17594334576640,
-        1048836,
-        274878955776,
-        0xFFFFFFFFFFFFFFFF,
-        0xFFFFFFFFFFFFFFFF,
-        0xFFFFFFFFFFFFFFFF,
-    ],
-    [
-        4364173312,
-        35188669153280,
-        2097672,
-        549757911552,
-        8726249472,
-        72057594174242816,
-        0xFFFFFFFFFFFFFFFF,
-        0xFFFFFFFFFFFFFFFF,
-        0xFFFFFFFFFFFFFFFF,
-    ],
-    [
-        4195344,
-        1099515823104,
-        70377338306560,
-        8728346624,
-        17452498944,
-        144115188348485632,
-        0xFFFFFFFFFFFFFFFF,
-        0xFFFFFFFFFFFFFFFF,
-        0xFFFFFFFFFFFFFFFF,
-    ],
-    [
-        2199031646208,
-        8390688,
-        140754676613120,
-        17456693248,
-        34904997888,
-        288230376696971264,
-        0xFFFFFFFFFFFFFFFF,
-        0xFFFFFFFFFFFFFFFF,
-        0xFFFFFFFFFFFFFFFF,
-    ],
-    [
-        576460753393942528,
-        69809995776,
-        4398063292416,
-        16781376,
-        281509353226240,
-        34913386496,
-        0xFFFFFFFFFFFFFFFF,
-        0xFFFFFFFFFFFFFFFF,
-        0xFFFFFFFFFFFFFFFF,
-    ],
-    [
-        1152921504640925696,
-        2181562368,
-        2182086656,
-        33816704,
-        562949986975872,
-        8796126584832,
-        33562628,
-        0xFFFFFFFFFFFFFFFF,
-        0xFFFFFFFFFFFFFFFF,
-    ],
-    [
-        36028797087121408,
-        4363124736,
-        4364173312,
-        67117312,
-        1125899973951744,
-        67125256,
-        17592253169664,
-        0xFFFFFFFFFFFFFFFF,
-        0xFFFFFFFFFFFFFFFF,
-    ],
-    [
-        134234624,
-        2251799947903488,
-        134250512,
-        35184506339328,
-        8728346624,
-        8726249472,
-        72057594174242816,
-        0xFFFFFFFFFFFFFFFF,
-        0xFFFFFFFFFFFFFFFF,
-    ],
-    [
-        268501024,
-        70369012678656,
-        4503599895806976,
-        268469248,
-        17456693248,
-        17452498944,
-        144115188348485632,
-        0xFFFFFFFFFFFFFFFF,
-        0xFFFFFFFFFFFFFFFF,
-    ],
-    [
-        140738025357312,
-        537002048,
-        9007199791613952,
-        536938496,
-        34913386496,
-        34904997888,
-        288230376696971264,
-        0xFFFFFFFFFFFFFFFF,
-        0xFFFFFFFFFFFFFFFF,
-    ],
-    [
-        576460753393942528,
-        69809995776,
-        69793742848,
-        281476050714624,
-        1074003970,
-        18014399583227904,
-        1073876992,
-        0xFFFFFFFFFFFFFFFF,
-        0xFFFFFFFFFFFFFFFF,
-    ],
-    [
-        2181562368,
-        2182086656,
-        17594334576640,
-        562952100905216,
-        2147500288,
-        2147491842,
-        36028799166455808,
-        0xFFFFFFFFFFFFFFFF,
-        0xFFFFFFFFFFFFFFFF,
-    ],
-    [
-        4363124736,
-        4364173312,
-        35188669153280,
-        4294983684,
-        72057598332911616,
-        4295000576,
-        1125904201810432,
-        0xFFFFFFFFFFFFFFFF,
-        0xFFFFFFFFFFFFFFFF,
-    ],
-    [
-        8589967368,
-        144115196665823232,
-        8590001152,
-        2251808403620864,
-        70377338306560,
-        8728346624,
-        8726249472,
-        0xFFFFFFFFFFFFFFFF,
-        0xFFFFFFFFFFFFFFFF,
-    ],
-    [
-        288230393331646464,
-        17179934736,
-        17180002304,
-        4503616807241728,
-        140754676613120,
-        17456693248,
-        17452498944,
-        0xFFFFFFFFFFFFFFFF,
-        0xFFFFFFFFFFFFFFFF,
-    ],
-    [
-        9007233614483456,
-        34360004608,
-        576460786663292928,
-        34359869472,
-        281509353226240,
-        34913386496,
-        34904997888,
-        0xFFFFFFFFFFFFFFFF,
-        0xFFFFFFFFFFFFFFFF,
-    ],
-    [
-        69809995776,
-        69793742848,
-        8864813023232,
-        18014467228958848,
-        68719485056,
-        1152921573326585856,
-        68719738944,
-        0xFFFFFFFFFFFFFFFF,
-        0xFFFFFFFFFFFFFFFF,
-    ],
-    [
-        571883485396992,
-        1170936040555282432,
-        137439477888,
-        0xFFFFFFFFFFFFFFFF,
-        0xFFFFFFFFFFFFFFFF,
-        0xFFFFFFFFFFFFFFFF,
-        0xFFFFFFFFFFFFFFFF,
-        0xFFFFFFFFFFFFFFFF,
-        0xFFFFFFFFFFFFFFFF,
-    ],
-    [
-        1143766970793984,
-        36592021850292224,
-        274878955776,
-        0xFFFFFFFFFFFFFFFF,
-        0xFFFFFFFFFFFFFFFF,
-        0xFFFFFFFFFFFFFFFF,
-        0xFFFFFFFFFFFFFFFF,
-        0xFFFFFFFFFFFFFFFF,
-        0xFFFFFFFFFFFFFFFF,
-    ],
-    [
-        73184043700584448,
-        549757911552,
-        2287533941587968,
-        0xFFFFFFFFFFFFFFFF,
-        0xFFFFFFFFFFFFFFFF,
-        0xFFFFFFFFFFFFFFFF,
-        0xFFFFFFFFFFFFFFFF,
-        0xFFFFFFFFFFFFFFFF,
-        0xFFFFFFFFFFFFFFFF,
-    ],
-    [
-        1099515823104,
-        146368087401168896,
-        4575067883175936,
-        0xFFFFFFFFFFFFFFFF,
-        0xFFFFFFFFFFFFFFFF,
-        0xFFFFFFFFFFFFFFFF,
-        0xFFFFFFFFFFFFFFFF,
-        0xFFFFFFFFFFFFFFFF,
-        0xFFFFFFFFFFFFFFFF,
-    ],
-    [
-        2199031646208,
-        292736174802337792,
-        9150135766351872,
-        0xFFFFFFFFFFFFFFFF,
-        0xFFFFFFFFFFFFFFFF,
-        0xFFFFFFFFFFFFFFFF,
-        0xFFFFFFFFFFFFFFFF,
-        0xFFFFFFFFFFFFFFFF,
-        0xFFFFFFFFFFFFFFFF,
-    ],
-    [
-        18300271532703744,
-        4398063292416,
-        585472349604675584,
-        0xFFFFFFFFFFFFFFFF,
-        0xFFFFFFFFFFFFFFFF,
-        0xFFFFFFFFFFFFFFFF,
-        0xFFFFFFFFFFFFFFFF,
-        0xFFFFFFFFFFFFFFFF,
-        0xFFFFFFFFFFFFFFFF,
-    ],
-    [
-        571883485396992,
-        36600543065407488,
-        8864813023232,
-        8796126584832,
-        0xFFFFFFFFFFFFFFFF,
-        0xFFFFFFFFFFFFFFFF,
-        0xFFFFFFFFFFFFFFFF,
-        0xFFFFFFFFFFFFFFFF,
-        0xFFFFFFFFFFFFFFFF,
-    ],
-    [
-        1143766970793984,
-        73201086130814976,
-        17594334576640,
-        17592253169664,
-        0xFFFFFFFFFFFFFFFF,
-        0xFFFFFFFFFFFFFFFF,
-        0xFFFFFFFFFFFFFFFF,
-        0xFFFFFFFFFFFFFFFF,
-        0xFFFFFFFFFFFFFFFF,
-    ],
-    [
-        35188669153280,
-        35184506339328,
-        146402172261629952,
-        2287533941587968,
-        0xFFFFFFFFFFFFFFFF,
-        0xFFFFFFFFFFFFFFFF,
-        0xFFFFFFFFFFFFFFFF,
-        0xFFFFFFFFFFFFFFFF,
-        0xFFFFFFFFFFFFFFFF,
-    ],
-    [
-        70369012678656,
-        70377338306560,
-        292804344523259904,
-        4575067883175936,
-        0xFFFFFFFFFFFFFFFF,
-        0xFFFFFFFFFFFFFFFF,
-        0xFFFFFFFFFFFFFFFF,
-        0xFFFFFFFFFFFFFFFF,
-        0xFFFFFFFFFFFFFFFF,
-    ],
-    [
-        140738025357312,
-        140754676613120,
-        585608689046519808,
-        9150135766351872,
-        0xFFFFFFFFFFFFFFFF,
-        0xFFFFFFFFFFFFFFFF,
-        0xFFFFFFFFFFFFFFFF,
-        0xFFFFFFFFFFFFFFFF,
-        0xFFFFFFFFFFFFFFFF,
-    ],
-    [
-        18300271532703744,
-        1171217378093039616,
-        281476050714624,
-        281509353226240,
-        0xFFFFFFFFFFFFFFFF,
-        0xFFFFFFFFFFFFFFFF,
-        0xFFFFFFFFFFFFFFFF,
-        0xFFFFFFFFFFFFFFFF,
-        0xFFFFFFFFFFFFFFFF,
-    ],
-    [
-        571883485396992,
-        36600543065407488,
-        36592021850292224,
-        562952100905216,
-        562949986975872,
-        0xFFFFFFFFFFFFFFFF,
-        0xFFFFFFFFFFFFFFFF,
-        0xFFFFFFFFFFFFFFFF,
-        0xFFFFFFFFFFFFFFFF,
-    ],
-    [
-        1143766970793984,
-        73201086130814976,
-        73184043700584448,
-        1125899973951744,
-        1125904201810432,
-        0xFFFFFFFFFFFFFFFF,
-        0xFFFFFFFFFFFFFFFF,
-        0xFFFFFFFFFFFFFFFF,
-        0xFFFFFFFFFFFFFFFF,
-    ],
-    [
-        2251799947903488,
-        2251808403620864,
-        146368087401168896,
-        146402172261629952,
-        2287533941587968,
-        0xFFFFFFFFFFFFFFFF,
-        0xFFFFFFFFFFFFFFFF,
-        0xFFFFFFFFFFFFFFFF,
-        0xFFFFFFFFFFFFFFFF,
-    ],
-    [
-        4503599895806976,
-        4503616807241728,
-        292736174802337792,
-        292804344523259904,
-        4575067883175936,
-        0xFFFFFFFFFFFFFFFF,
-        0xFFFFFFFFFFFFFFFF,
-        0xFFFFFFFFFFFFFFFF,
-        0xFFFFFFFFFFFFFFFF,
-    ],
-    [
-        9007233614483456,
-        9007199791613952,
-        585472349604675584,
-        585608689046519808,
-        9150135766351872,
-        0xFFFFFFFFFFFFFFFF,
-        0xFFFFFFFFFFFFFFFF,
-        0xFFFFFFFFFFFFFFFF,
-        0xFFFFFFFFFFFFFFFF,
-    ],
-    [
-        18300271532703744,
-        1171217378093039616,
-        1170936040555282432,
-        18014467228958848,
-        18014399583227904,
-        0xFFFFFFFFFFFFFFFF,
-        0xFFFFFFFFFFFFFFFF,
-        0xFFFFFFFFFFFFFFFF,
-        0xFFFFFFFFFFFFFFFF,
-    ],
-    [
-        36600543065407488,
-        36592021850292224,
-        36028797087121408,
-        36028799166455808,
-        0xFFFFFFFFFFFFFFFF,
-        0xFFFFFFFFFFFFFFFF,
-        0xFFFFFFFFFFFFFFFF,
-        0xFFFFFFFFFFFFFFFF,
-        0xFFFFFFFFFFFFFFFF,
-    ],
-    [
-        73201086130814976,
-        73184043700584448,
-        72057598332911616,
-        72057594174242816,
-        0xFFFFFFFFFFFFFFFF,
-        0xFFFFFFFFFFFFFFFF,
-        0xFFFFFFFFFFFFFFFF,
-        0xFFFFFFFFFFFFFFFF,
-        0xFFFFFFFFFFFFFFFF,
-    ],
-    [
-        144115196665823232,
-        144115188348485632,
-        146368087401168896,
-        146402172261629952,
-        0xFFFFFFFFFFFFFFFF,
-        0xFFFFFFFFFFFFFFFF,
-        0xFFFFFFFFFFFFFFFF,
-        0xFFFFFFFFFFFFFFFF,
-        0xFFFFFFFFFFFFFFFF,
-    ],
-    [
-        288230393331646464,
-        288230376696971264,
-        292736174802337792,
-        292804344523259904,
-        0xFFFFFFFFFFFFFFFF,
-        0xFFFFFFFFFFFFFFFF,
-        0xFFFFFFFFFFFFFFFF,
-        0xFFFFFFFFFFFFFFFF,
-        0xFFFFFFFFFFFFFFFF,
-    ],
-    [
-        576460753393942528,
-        576460786663292928,
-        585472349604675584,
-        585608689046519808,
-        0xFFFFFFFFFFFFFFFF,
-        0xFFFFFFFFFFFFFFFF,
-        0xFFFFFFFFFFFFFFFF,
-        0xFFFFFFFFFFFFFFFF,
-        0xFFFFFFFFFFFFFFFF,
-    ],
-    [
-        1171217378093039616,
-        1170936040555282432,
-        1152921504640925696,
-        1152921573326585856,
-        0xFFFFFFFFFFFFFFFF,
-        0xFFFFFFFFFFFFFFFF,
-        0xFFFFFFFFFFFFFFFF,
-        0xFFFFFFFFFFFFFFFF,
-        0xFFFFFFFFFFFFFFFF,
-    ],
-];
-
-pub static WIN_CHECKS: [[u64; 12]; 61] = [
-    [
-        8390689, 16781377, 524419, 2085, 4169, 147, 293, 585, 1043, 1048837, 2097673, 4195345,
-    ],
-    [
-        281476050714626,
-        537002050,
-        1074003974,
-        139330,
-        278534,
-        2147491906,
-        4294983686,
-        36028799166455810,
-        137439477890,
-        524419,
-        147,
-        1043,
-    ],
-    [
-        8796126584836,
-        1074003974,
-        33562636,
-        278534,
-        40972,
-        4294983686,
-        8589967372,
-        72057598332911620,
-        2085,
-        293,
-        1048837,
-        274878955780,
-    ],
-    [
-        33562636,
-        40972,
-        8589967372,
-        144115196665823240,
-        4169,
-        585,
-        2097673,
-        549757911560,
-        17179934744,
-        81944,
-        67125272,
-        17592253169672,
-    ],
-    [
-        147,
-        1043,
-        4195345,
-        1099515823120,
-        288230393331646480,
-        17179934744,
-        34359869488,
-        81944,
-        163888,
-        67125272,
-        134250544,
-        35184506339344,
-    ],
-    [
-        2199031646240,
-        8390689,
-        2085,
-        293,
-        576460786663292960,
-        34359869488,
-        68719738976,
-        163888,
-        327776,
-        134250544,
-        268501088,
-        70369012678688,
-    ],
-    [
-        140738025357376,
-        537002050,
-        139330,
-        2147491906,
-        4398063292480,
-        16781377,
-        4169,
-        585,
-        1152921573326585920,
-        68719738976,
-        327776,
-        268501088,
-    ],
-    [
-        18014467228967040,
-        34360004736,
-        68719485312,
-        33820800,
-        67117440,
-        562949987238016,
-        137439477890,
-        524419,
-        147,
-        0xFFFFFFFFFFFFFFFF,
-        0xFFFFFFFFFFFFFFFF,
-        0xFFFFFFFFFFFFFFFF,
-    ],
-    [
-        562952100921600,
-        68719485312,
-        2147500800,
-        67117440,
-        134234880,
-        1125899973959936,
-        293,
-        1048837,
-        274878955780,
-        0xFFFFFFFFFFFFFFFF,
-        0xFFFFFFFFFFFFFFFF,
-        0xFFFFFFFFFFFFFFFF,
-    ],
-    [
-        2147500800,
-        134234880,
-        2251799947919872,
-        585,
-        2097673,
-        549757911560,
-        268469760,
-        4295001600,
-        1125904201843200,
-        0xFFFFFFFFFFFFFFFF,
-        0xFFFFFFFFFFFFFFFF,
-        0xFFFFFFFFFFFFFFFF,
-    ],
-    [
-        1043,
-        4195345,
-        1099515823120,
-        4503599895839744,
-        268469760,
-        536939520,
-        4295001600,
-        8590003200,
-        2251808403686400,
-        0xFFFFFFFFFFFFFFFF,
-        0xFFFFFFFFFFFFFFFF,
-        0xFFFFFFFFFFFFFFFF,
-    ],
-    [
-        2199031646240,
-        8390689,
-        2085,
-        9007199791679488,
-        536939520,
-        1073879040,
-        8590003200,
-        17180006400,
-        4503616807372800,
-        0xFFFFFFFFFFFFFFFF,
-        0xFFFFFFFFFFFFFFFF,
-        0xFFFFFFFFFFFFFFFF,
-    ],
-    [
-        9007233614745600,
-        34360004736,
-        33820800,
-        4398063292480,
-        16781377,
-        4169,
-        18014399583358976,
-        1073879040,
-        17180006400,
-        0xFFFFFFFFFFFFFFFF,
-        0xFFFFFFFFFFFFFFFF,
-        0xFFFFFFFFFFFFFFFF,
-    ],
-    [
-        18014467228967040,
-        68719485312,
-        67117440,
-        1125899973959936,
-        8796126584836,
-        33562636,
-        139330,
-        40972,
-        2147491906,
-        36028799166455810,
-        0xFFFFFFFFFFFFFFFF,
-        0xFFFFFFFFFFFFFFFF,
-    ],
-    [
-        562952100921600,
-        2147500800,
-        134234880,
-        2251799947919872,
-        278534,
-        4294983686,
-        72057598332911620,
-        81944,
-        67125272,
-        17592253169672,
-        0xFFFFFFFFFFFFFFFF,
-        0xFFFFFFFFFFFFFFFF,
-    ],
-    [
-        40972,
-        8589967372,
-        144115196665823240,
-        163888,
-        134250544,
-        35184506339344,
-        4503599895839744,
-        268469760,
-        4295001600,
-        1125904201843200,
-        0xFFFFFFFFFFFFFFFF,
-        0xFFFFFFFFFFFFFFFF,
-    ],
-    [
-        288230393331646480,
-        17179934744,
-        81944,
-        327776,
-        268501088,
-        70369012678688,
-        9007199791679488,
-        536939520,
-        8590003200,
-        2251808403686400,
-        0xFFFFFFFFFFFFFFFF,
-        0xFFFFFFFFFFFFFFFF,
-    ],
-    [
-        140738025357376,
-        537002050,
-        139330,
-        576460786663292960,
-        34359869488,
-        163888,
-        18014399583358976,
-        1073879040,
-        17180006400,
-        4503616807372800,
-        0xFFFFFFFFFFFFFFFF,
-        0xFFFFFFFFFFFFFFFF,
-    ],
-    [
-        9007233614745600,
-        34360004736,
-        33820800,
-        562949987238016,
-        281476050714626,
-        1074003974,
-        278534,
-        1152921573326585920,
-        68719738976,
-        327776,
-        0xFFFFFFFFFFFFFFFF,
-        0xFFFFFFFFFFFFFFFF,
-    ],
-    [
-        1152921506788409344,
-        69810520064,
-        2182610944,
-        8865886765056,
-        137439477890,
-        524419,
-        0xFFFFFFFFFFFFFFFF,
-        0xFFFFFFFFFFFFFFFF,
-        0xFFFFFFFFFFFFFFFF,
-        0xFFFFFFFFFFFFFFFF,
-        0xFFFFFFFFFFFFFFFF,
-        0xFFFFFFFFFFFFFFFF,
-    ],
-    [
-        36028801382088704,
-        2182610944,
-        4365221888,
-        17594368131072,
-        1048837,
-        274878955780,
-        0xFFFFFFFFFFFFFFFF,
-        0xFFFFFFFFFFFFFFFF,
-        0xFFFFFFFFFFFFFFFF,
-        0xFFFFFFFFFFFFFFFF,
-        0xFFFFFFFFFFFFFFFF,
-        0xFFFFFFFFFFFFFFFF,
-    ],
-    [
-        4365221888,
-        35188736262144,
-        2097673,
-        549757911560,
-        8730443776,
-        72057602764177408,
-        0xFFFFFFFFFFFFFFFF,
-        0xFFFFFFFFFFFFFFFF,
-        0xFFFFFFFFFFFFFFFF,
-        0xFFFFFFFFFFFFFFFF,
-        0xFFFFFFFFFFFFFFFF,
-        0xFFFFFFFFFFFFFFFF,
-    ],
-    [
-        4195345,
-        1099515823120,
-        70377472524288,
-        8730443776,
-        17460887552,
-        144115205528354816,
-        0xFFFFFFFFFFFFFFFF,
-        0xFFFFFFFFFFFFFFFF,
-        0xFFFFFFFFFFFFFFFF,
-        0xFFFFFFFFFFFFFFFF,
-        0xFFFFFFFFFFFFFFFF,
-        0xFFFFFFFFFFFFFFFF,
-    ],
-    [
-        2199031646240,
-        8390689,
-        140754945048576,
-        17460887552,
-        34921775104,
-        288230411056709632,
-        0xFFFFFFFFFFFFFFFF,
-        0xFFFFFFFFFFFFFFFF,
-        0xFFFFFFFFFFFFFFFF,
-        0xFFFFFFFFFFFFFFFF,
-        0xFFFFFFFFFFFFFFFF,
-        0xFFFFFFFFFFFFFFFF,
-    ],
-    [
-        576460822113419264,
-        69810520064,
-        4398063292480,
-        16781377,
-        281509890097152,
-        34921775104,
-        0xFFFFFFFFFFFFFFFF,
-        0xFFFFFFFFFFFFFFFF,
-        0xFFFFFFFFFFFFFFFF,
-        0xFFFFFFFFFFFFFFFF,
-        0xFFFFFFFFFFFFFFFF,
-        0xFFFFFFFFFFFFFFFF,
-    ],
-    [
-        1152921506788409344,
-        2182610944,
-        17594368131072,
-        33820800,
-        562949987238016,
-        8796126584836,
-        33562636,
-        0xFFFFFFFFFFFFFFFF,
-        0xFFFFFFFFFFFFFFFF,
-        0xFFFFFFFFFFFFFFFF,
-        0xFFFFFFFFFFFFFFFF,
-        0xFFFFFFFFFFFFFFFF,
-    ],
-    [
-        36028801382088704,
-        4365221888,
-        35188736262144,
-        67117440,
-        1125899973959936,
-        67125272,
-        17592253169672,
-        0xFFFFFFFFFFFFFFFF,
-        0xFFFFFFFFFFFFFFFF,
-        0xFFFFFFFFFFFFFFFF,
-        0xFFFFFFFFFFFFFFFF,
-        0xFFFFFFFFFFFFFFFF,
-    ],
-    [
-        134234880,
-        2251799947919872,
-        134250544,
-        35184506339344,
-        70377472524288,
-        8730443776,
-        72057602764177408,
-        0xFFFFFFFFFFFFFFFF,
-        0xFFFFFFFFFFFFFFFF,
-        0xFFFFFFFFFFFFFFFF,
-        0xFFFFFFFFFFFFFFFF,
-        0xFFFFFFFFFFFFFFFF,
-    ],
-    [
-        268501088,
-        70369012678688,
-        4503599895839744,
-        268469760,
-        140754945048576,
-        17460887552,
-        144115205528354816,
-        0xFFFFFFFFFFFFFFFF,
-        0xFFFFFFFFFFFFFFFF,
-        0xFFFFFFFFFFFFFFFF,
-        0xFFFFFFFFFFFFFFFF,
-        0xFFFFFFFFFFFFFFFF,
-    ],
-    [
-        140738025357376,
-        537002050,
-        9007199791679488,
-        536939520,
-        281509890097152,
-        34921775104,
-        288230411056709632,
-        0xFFFFFFFFFFFFFFFF,
-        0xFFFFFFFFFFFFFFFF,
-        0xFFFFFFFFFFFFFFFF,
-        0xFFFFFFFFFFFFFFFF,
-        0xFFFFFFFFFFFFFFFF,
-    ],
-    [
-        576460822113419264,
-        69810520064,
-        8865886765056,
-        281476050714626,
-        1074003974,
-        18014399583358976,
-        1073879040,
-        0xFFFFFFFFFFFFFFFF,
-        0xFFFFFFFFFFFFFFFF,
-        0xFFFFFFFFFFFFFFFF,
-        0xFFFFFFFFFFFFFFFF,
-        0xFFFFFFFFFFFFFFFF,
-    ],
-    [
-        1152921506788409344,
-        2182610944,
-        17594368131072,
-        562952100921600,
-        2147500800,
-        2147491906,
-        36028799166455810,
-        0xFFFFFFFFFFFFFFFF,
-        0xFFFFFFFFFFFFFFFF,
-        0xFFFFFFFFFFFFFFFF,
-        0xFFFFFFFFFFFFFFFF,
-        0xFFFFFFFFFFFFFFFF,
-    ],
-    [
-        36028801382088704,
-        4365221888,
-        35188736262144,
-        4294983686,
-        72057598332911620,
-        4295001600,
-        1125904201843200,
-        0xFFFFFFFFFFFFFFFF,
-        0xFFFFFFFFFFFFFFFF,
-        0xFFFFFFFFFFFFFFFF,
-        0xFFFFFFFFFFFFFFFF,
-        0xFFFFFFFFFFFFFFFF,
-    ],
-    [
-        8589967372,
-        144115196665823240,
-        8590003200,
-        2251808403686400,
-        70377472524288,
-        8730443776,
-        72057602764177408,
-        0xFFFFFFFFFFFFFFFF,
-        0xFFFFFFFFFFFFFFFF,
-        0xFFFFFFFFFFFFFFFF,
-        0xFFFFFFFFFFFFFFFF,
-        0xFFFFFFFFFFFFFFFF,
-    ],
-    [
-        288230393331646480,
-        17179934744,
-        17180006400,
-        4503616807372800,
-        140754945048576,
-        17460887552,
-        144115205528354816,
-        0xFFFFFFFFFFFFFFFF,
-        0xFFFFFFFFFFFFFFFF,
-        0xFFFFFFFFFFFFFFFF,
-        0xFFFFFFFFFFFFFFFF,
-        0xFFFFFFFFFFFFFFFF,
-    ],
-    [
-        9007233614745600,
-        34360004736,
-        576460786663292960,
-        34359869488,
-        281509890097152,
-        34921775104,
-        288230411056709632,
-        0xFFFFFFFFFFFFFFFF,
-        0xFFFFFFFFFFFFFFFF,
-        0xFFFFFFFFFFFFFFFF,
-        0xFFFFFFFFFFFFFFFF,
-        0xFFFFFFFFFFFFFFFF,
-    ],
-    [
-        576460822113419264,
-        69810520064,
-        8865886765056,
-        18014467228967040,
-        68719485312,
-        1152921573326585920,
-        68719738976,
-        0xFFFFFFFFFFFFFFFF,
-        0xFFFFFFFFFFFFFFFF,
-        0xFFFFFFFFFFFFFFFF,
-        0xFFFFFFFFFFFFFFFF,
-        0xFFFFFFFFFFFFFFFF,
-    ],
-    [
-        36600680504360960,
-        1171217515531993088,
-        137439477890,
-        0xFFFFFFFFFFFFFFFF,
-        0xFFFFFFFFFFFFFFFF,
-        0xFFFFFFFFFFFFFFFF,
-        0xFFFFFFFFFFFFFFFF,
-        0xFFFFFFFFFFFFFFFF,
-        0xFFFFFFFFFFFFFFFF,
-        0xFFFFFFFFFFFFFFFF,
-        0xFFFFFFFFFFFFFFFF,
-        0xFFFFFFFFFFFFFFFF,
-    ],
-    [
-        73201361008721920,
-        36600817943314432,
-        274878955780,
-        0xFFFFFFFFFFFFFFFF,
-        0xFFFFFFFFFFFFFFFF,
-        0xFFFFFFFFFFFFFFFF,
-        0xFFFFFFFFFFFFFFFF,
-        0xFFFFFFFFFFFFFFFF,
-        0xFFFFFFFFFFFFFFFF,
-        0xFFFFFFFFFFFFFFFF,
-        0xFFFFFFFFFFFFFFFF,
-        0xFFFFFFFFFFFFFFFF,
-    ],
-    [
-        73201635886628864,
-        549757911560,
-        146402722017443840,
-        0xFFFFFFFFFFFFFFFF,
-        0xFFFFFFFFFFFFFFFF,
-        0xFFFFFFFFFFFFFFFF,
-        0xFFFFFFFFFFFFFFFF,
-        0xFFFFFFFFFFFFFFFF,
-        0xFFFFFFFFFFFFFFFF,
-        0xFFFFFFFFFFFFFFFF,
-        0xFFFFFFFFFFFFFFFF,
-        0xFFFFFFFFFFFFFFFF,
-    ],
-    [
-        1099515823120,
-        146403271773257728,
-        292805444034887680,
-        0xFFFFFFFFFFFFFFFF,
-        0xFFFFFFFFFFFFFFFF,
-        0xFFFFFFFFFFFFFFFF,
-        0xFFFFFFFFFFFFFFFF,
-        0xFFFFFFFFFFFFFFFF,
-        0xFFFFFFFFFFFFFFFF,
-        0xFFFFFFFFFFFFFFFF,
-        0xFFFFFFFFFFFFFFFF,
-        0xFFFFFFFFFFFFFFFF,
-    ],
-    [
-        2199031646240,
-        292806543546515456,
-        585610888069775360,
-        0xFFFFFFFFFFFFFFFF,
-        0xFFFFFFFFFFFFFFFF,
-        0xFFFFFFFFFFFFFFFF,
-        0xFFFFFFFFFFFFFFFF,
-        0xFFFFFFFFFFFFFFFF,
-        0xFFFFFFFFFFFFFFFF,
-        0xFFFFFFFFFFFFFFFF,
-        0xFFFFFFFFFFFFFFFF,
-        0xFFFFFFFFFFFFFFFF,
-    ],
-    [
-        1171221776139550720,
-        4398063292480,
-        585613087093030912,
-        0xFFFFFFFFFFFFFFFF,
-        0xFFFFFFFFFFFFFFFF,
-        0xFFFFFFFFFFFFFFFF,
-        0xFFFFFFFFFFFFFFFF,
-        0xFFFFFFFFFFFFFFFF,
-        0xFFFFFFFFFFFFFFFF,
-        0xFFFFFFFFFFFFFFFF,
-        0xFFFFFFFFFFFFFFFF,
-        0xFFFFFFFFFFFFFFFF,
-    ],
-    [
-        36600680504360960,
-        36600817943314432,
-        8865886765056,
-        8796126584836,
-        0xFFFFFFFFFFFFFFFF,
-        0xFFFFFFFFFFFFFFFF,
-        0xFFFFFFFFFFFFFFFF,
-        0xFFFFFFFFFFFFFFFF,
-        0xFFFFFFFFFFFFFFFF,
-        0xFFFFFFFFFFFFFFFF,
-        0xFFFFFFFFFFFFFFFF,
-        0xFFFFFFFFFFFFFFFF,
-    ],
-    [
-        73201361008721920,
-        73201635886628864,
-        17594368131072,
-        17592253169672,
-        0xFFFFFFFFFFFFFFFF,
-        0xFFFFFFFFFFFFFFFF,
-        0xFFFFFFFFFFFFFFFF,
-        0xFFFFFFFFFFFFFFFF,
-        0xFFFFFFFFFFFFFFFF,
-        0xFFFFFFFFFFFFFFFF,
-        0xFFFFFFFFFFFFFFFF,
-        0xFFFFFFFFFFFFFFFF,
-    ],
-    [
-        35188736262144,
-        35184506339344,
-        146403271773257728,
-        146402722017443840,
-        0xFFFFFFFFFFFFFFFF,
-        0xFFFFFFFFFFFFFFFF,
-        0xFFFFFFFFFFFFFFFF,
-        0xFFFFFFFFFFFFFFFF,
-        0xFFFFFFFFFFFFFFFF,
-        0xFFFFFFFFFFFFFFFF,
-        0xFFFFFFFFFFFFFFFF,
-        0xFFFFFFFFFFFFFFFF,
-    ],
-    [
-        70369012678688,
-        70377472524288,
-        292806543546515456,
-        292805444034887680,
-        0xFFFFFFFFFFFFFFFF,
-        0xFFFFFFFFFFFFFFFF,
-        0xFFFFFFFFFFFFFFFF,
-        0xFFFFFFFFFFFFFFFF,
-        0xFFFFFFFFFFFFFFFF,
-        0xFFFFFFFFFFFFFFFF,
-        0xFFFFFFFFFFFFFFFF,
-        0xFFFFFFFFFFFFFFFF,
-    ],
-    [
-        140738025357376,
-        140754945048576,
-        585613087093030912,
-        585610888069775360,
-        0xFFFFFFFFFFFFFFFF,
-        0xFFFFFFFFFFFFFFFF,
-        0xFFFFFFFFFFFFFFFF,
-        0xFFFFFFFFFFFFFFFF,
-        0xFFFFFFFFFFFFFFFF,
-        0xFFFFFFFFFFFFFFFF,
-        0xFFFFFFFFFFFFFFFF,
-        0xFFFFFFFFFFFFFFFF,
-    ],
-    [
-        1171221776139550720,
-        1171217515531993088,
-        281476050714626,
-        281509890097152,
-        0xFFFFFFFFFFFFFFFF,
-        0xFFFFFFFFFFFFFFFF,
-        0xFFFFFFFFFFFFFFFF,
-        0xFFFFFFFFFFFFFFFF,
-        0xFFFFFFFFFFFFFFFF,
-        0xFFFFFFFFFFFFFFFF,
-        0xFFFFFFFFFFFFFFFF,
-        0xFFFFFFFFFFFFFFFF,
-    ],
-    [
-        36600680504360960,
-        36600817943314432,
-        562952100921600,
-        562949987238016,
-        0xFFFFFFFFFFFFFFFF,
-        0xFFFFFFFFFFFFFFFF,
-        0xFFFFFFFFFFFFFFFF,
-        0xFFFFFFFFFFFFFFFF,
-        0xFFFFFFFFFFFFFFFF,
-        0xFFFFFFFFFFFFFFFF,
-        0xFFFFFFFFFFFFFFFF,
-        0xFFFFFFFFFFFFFFFF,
-    ],
-    [
-        73201361008721920,
-        73201635886628864,
-        1125899973959936,
-        1125904201843200,
-        0xFFFFFFFFFFFFFFFF,
-        0xFFFFFFFFFFFFFFFF,
-        0xFFFFFFFFFFFFFFFF,
-        0xFFFFFFFFFFFFFFFF,
-        0xFFFFFFFFFFFFFFFF,
-        0xFFFFFFFFFFFFFFFF,
-        0xFFFFFFFFFFFFFFFF,
-        0xFFFFFFFFFFFFFFFF,
-    ],
-    [
-        2251799947919872,
-        2251808403686400,
-        146403271773257728,
-        146402722017443840,
-        0xFFFFFFFFFFFFFFFF,
-        0xFFFFFFFFFFFFFFFF,
-        0xFFFFFFFFFFFFFFFF,
-        0xFFFFFFFFFFFFFFFF,
-        0xFFFFFFFFFFFFFFFF,
-        0xFFFFFFFFFFFFFFFF,
-        0xFFFFFFFFFFFFFFFF,
-        0xFFFFFFFFFFFFFFFF,
-    ],
-    [
-        4503599895839744,
-        4503616807372800,
-        292806543546515456,
-        292805444034887680,
-        0xFFFFFFFFFFFFFFFF,
-        0xFFFFFFFFFFFFFFFF,
-        0xFFFFFFFFFFFFFFFF,
-        0xFFFFFFFFFFFFFFFF,
-        0xFFFFFFFFFFFFFFFF,
-        0xFFFFFFFFFFFFFFFF,
-        0xFFFFFFFFFFFFFFFF,
-        0xFFFFFFFFFFFFFFFF,
-    ],
-    [
-        9007233614745600,
-        9007199791679488,
-        585613087093030912,
-        585610888069775360,
-        0xFFFFFFFFFFFFFFFF,
-        0xFFFFFFFFFFFFFFFF,
-        0xFFFFFFFFFFFFFFFF,
-        0xFFFFFFFFFFFFFFFF,
-        0xFFFFFFFFFFFFFFFF,
-        0xFFFFFFFFFFFFFFFF,
-        0xFFFFFFFFFFFFFFFF,
-        0xFFFFFFFFFFFFFFFF,
-    ],
-    [
-        1171221776139550720,
-        1171217515531993088,
-        18014467228967040,
-        18014399583358976,
-        0xFFFFFFFFFFFFFFFF,
-        0xFFFFFFFFFFFFFFFF,
-        0xFFFFFFFFFFFFFFFF,
-        0xFFFFFFFFFFFFFFFF,
-        0xFFFFFFFFFFFFFFFF,
-        0xFFFFFFFFFFFFFFFF,
-        0xFFFFFFFFFFFFFFFF,
-        0xFFFFFFFFFFFFFFFF,
-    ],
-    [
-        36600680504360960,
-        36600817943314432,
-        36028801382088704,
-        36028799166455810,
-        0xFFFFFFFFFFFFFFFF,
-        0xFFFFFFFFFFFFFFFF,
-        0xFFFFFFFFFFFFFFFF,
-        0xFFFFFFFFFFFFFFFF,
-        0xFFFFFFFFFFFFFFFF,
-        0xFFFFFFFFFFFFFFFF,
-        0xFFFFFFFFFFFFFFFF,
-        0xFFFFFFFFFFFFFFFF,
-    ],
-    [
-        73201361008721920,
-        73201635886628864,
-        72057598332911620,
-        72057602764177408,
-        0xFFFFFFFFFFFFFFFF,
-        0xFFFFFFFFFFFFFFFF,
-        0xFFFFFFFFFFFFFFFF,
-        0xFFFFFFFFFFFFFFFF,
-        0xFFFFFFFFFFFFFFFF,
-        0xFFFFFFFFFFFFFFFF,
-        0xFFFFFFFFFFFFFFFF,
-        0xFFFFFFFFFFFFFFFF,
-    ],
-    [
-        144115196665823240,
-        144115205528354816,
-        146403271773257728,
-        146402722017443840,
-        0xFFFFFFFFFFFFFFFF,
-        0xFFFFFFFFFFFFFFFF,
-        0xFFFFFFFFFFFFFFFF,
-        0xFFFFFFFFFFFFFFFF,
-        0xFFFFFFFFFFFFFFFF,
-        0xFFFFFFFFFFFFFFFF,
-        0xFFFFFFFFFFFFFFFF,
-        0xFFFFFFFFFFFFFFFF,
-    ],
-    [
-        288230393331646480,
-        288230411056709632,
-        292806543546515456,
-        292805444034887680,
-        0xFFFFFFFFFFFFFFFF,
-        0xFFFFFFFFFFFFFFFF,
-        0xFFFFFFFFFFFFFFFF,
-        0xFFFFFFFFFFFFFFFF,
-        0xFFFFFFFFFFFFFFFF,
-        0xFFFFFFFFFFFFFFFF,
-        0xFFFFFFFFFFFFFFFF,
-        0xFFFFFFFFFFFFFFFF,
-    ],
-    [
-        576460822113419264,
-        576460786663292960,
-        585613087093030912,
-        585610888069775360,
-        0xFFFFFFFFFFFFFFFF,
-        0xFFFFFFFFFFFFFFFF,
-        0xFFFFFFFFFFFFFFFF,
-        0xFFFFFFFFFFFFFFFF,
-        0xFFFFFFFFFFFFFFFF,
-        0xFFFFFFFFFFFFFFFF,
-        0xFFFFFFFFFFFFFFFF,
-        0xFFFFFFFFFFFFFFFF,
-    ],
-    [
-        1171221776139550720,
-        1171217515531993088,
-        1152921506788409344,
-        1152921573326585920,
-        0xFFFFFFFFFFFFFFFF,
-        0xFFFFFFFFFFFFFFFF,
-        0xFFFFFFFFFFFFFFFF,
-        0xFFFFFFFFFFFFFFFF,
-        0xFFFFFFFFFFFFFFFF,
-        0xFFFFFFFFFFFFFFFF,
-        0xFFFFFFFFFFFFFFFF,
-        0xFFFFFFFFFFFFFFFF,
-    ],
-];
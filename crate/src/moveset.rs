@@ -0,0 +1,272 @@
+use std::ops::{BitAnd, BitOr, Sub};
+
+use crate::board::{bit, Bitboard};
+use crate::engine::SWAP_MOVE;
+
+/// A set of moves: a bitboard of cells plus a separate flag for the swap
+/// move. The set remembers which index its board uses for swap (the cell
+/// count), so iteration yields cells in index order followed by that move.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct MoveSet {
+    cells: Bitboard,
+    swap: bool,
+    swap_move: u8,
+}
+
+impl Default for MoveSet {
+    fn default() -> Self {
+        Self::EMPTY
+    }
+}
+
+impl MoveSet {
+    /// The empty set on the standard board.
+    pub const EMPTY: Self = Self::empty(SWAP_MOVE);
+
+    pub const fn empty(swap_move: u8) -> Self {
+        Self {
+            cells: 0,
+            swap: false,
+            swap_move,
+        }
+    }
+
+    pub fn new(swap_move: u8, cells: Bitboard, swap: bool) -> Self {
+        Self {
+            cells: cells & (bit(swap_move) - 1),
+            swap,
+            swap_move,
+        }
+    }
+
+    pub fn from_cells(swap_move: u8, cells: Bitboard) -> Self {
+        Self::new(swap_move, cells, false)
+    }
+
+    pub fn cells(self) -> Bitboard {
+        self.cells
+    }
+
+    pub fn has_swap(self) -> bool {
+        self.swap
+    }
+
+    pub fn swap_move(self) -> u8 {
+        self.swap_move
+    }
+
+    pub fn contains(self, mv: u8) -> bool {
+        if mv == self.swap_move {
+            self.swap
+        } else {
+            mv < self.swap_move && self.cells & bit(mv) != 0
+        }
+    }
+
+    pub fn insert(&mut self, mv: u8) {
+        if mv == self.swap_move {
+            self.swap = true;
+        } else if mv < self.swap_move {
+            self.cells |= bit(mv);
+        }
+    }
+
+    pub fn remove(&mut self, mv: u8) {
+        if mv == self.swap_move {
+            self.swap = false;
+        } else if mv < self.swap_move {
+            self.cells &= !bit(mv);
+        }
+    }
+
+    /// The moves of `self` for which `keep` returns true.
+    pub fn filter(self, mut keep: impl FnMut(u8) -> bool) -> Self {
+        let mut kept = Self::empty(self.swap_move);
+        for mv in self {
+            if keep(mv) {
+                kept.insert(mv);
+            }
+        }
+        kept
+    }
+
+    pub fn len(self) -> usize {
+        self.cells.count_ones() as usize + usize::from(self.swap)
+    }
+
+    pub fn is_empty(self) -> bool {
+        self.cells == 0 && !self.swap
+    }
+
+    pub fn union(self, other: Self) -> Self {
+        Self::new(
+            self.swap_move,
+            self.cells | other.cells,
+            self.swap || other.swap,
+        )
+    }
+
+    pub fn intersection(self, other: Self) -> Self {
+        Self::new(
+            self.swap_move,
+            self.cells & other.cells,
+            self.swap && other.swap,
+        )
+    }
+
+    pub fn difference(self, other: Self) -> Self {
+        Self::new(
+            self.swap_move,
+            self.cells & !other.cells,
+            self.swap && !other.swap,
+        )
+    }
+
+    pub fn first(self) -> Option<u8> {
+        self.iter().next()
+    }
+
+    /// The `n`-th move in iteration order.
+    pub fn nth(self, n: usize) -> Option<u8> {
+        let count = self.cells.count_ones() as usize;
+        if n < count {
+            let mut rest = self.cells;
+            for _ in 0..n {
+                rest &= rest - 1;
+            }
+            Some(rest.trailing_zeros() as u8)
+        } else if n == count && self.swap {
+            Some(self.swap_move)
+        } else {
+            None
+        }
+    }
+
+    /// Picks a move uniformly from the set using a caller-supplied random
+    /// number.
+    pub fn choose(self, random: u64) -> Option<u8> {
+        let len = self.len();
+        if len == 0 {
+            return None;
+        }
+        self.nth((random % len as u64) as usize)
+    }
+
+    pub fn iter(self) -> MoveSetIter {
+        MoveSetIter {
+            cells: self.cells,
+            swap: self.swap.then_some(self.swap_move),
+        }
+    }
+
+    pub fn to_vec(self) -> Vec<u8> {
+        self.iter().collect()
+    }
+}
+
+impl BitOr for MoveSet {
+    type Output = Self;
+
+    fn bitor(self, rhs: Self) -> Self {
+        self.union(rhs)
+    }
+}
+
+impl BitAnd for MoveSet {
+    type Output = Self;
+
+    fn bitand(self, rhs: Self) -> Self {
+        self.intersection(rhs)
+    }
+}
+
+impl Sub for MoveSet {
+    type Output = Self;
+
+    fn sub(self, rhs: Self) -> Self {
+        self.difference(rhs)
+    }
+}
+
+impl IntoIterator for MoveSet {
+    type Item = u8;
+    type IntoIter = MoveSetIter;
+
+    fn into_iter(self) -> MoveSetIter {
+        self.iter()
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct MoveSetIter {
+    cells: Bitboard,
+    swap: Option<u8>,
+}
+
+impl Iterator for MoveSetIter {
+    type Item = u8;
+
+    fn next(&mut self) -> Option<u8> {
+        if self.cells != 0 {
+            let idx = self.cells.trailing_zeros() as u8;
+            self.cells &= self.cells - 1;
+            Some(idx)
+        } else {
+            self.swap.take()
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = self.cells.count_ones() as usize + usize::from(self.swap.is_some());
+        (len, Some(len))
+    }
+}
+
+impl ExactSizeIterator for MoveSetIter {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn iterates_cells_then_swap() {
+        let mut set = MoveSet::EMPTY;
+        for mv in [SWAP_MOVE, 30, 2, 60] {
+            set.insert(mv);
+        }
+        assert_eq!(set.to_vec(), vec![2, 30, 60, SWAP_MOVE]);
+        assert_eq!(set.len(), 4);
+        assert_eq!(set.iter().len(), 4);
+        assert_eq!(set.nth(2), Some(60));
+        assert_eq!(set.nth(3), Some(SWAP_MOVE));
+        assert_eq!(set.nth(4), None);
+        assert_eq!(set.choose(5), Some(30));
+    }
+
+    #[test]
+    fn set_operations_track_swap_flag() {
+        let a = MoveSet::new(SWAP_MOVE, 0b0110, true);
+        let b = MoveSet::new(SWAP_MOVE, 0b0011, false);
+        assert_eq!(a | b, MoveSet::new(SWAP_MOVE, 0b0111, true));
+        assert_eq!(a & b, MoveSet::new(SWAP_MOVE, 0b0010, false));
+        assert_eq!(a - b, MoveSet::new(SWAP_MOVE, 0b0100, true));
+        let mut c = a;
+        c.remove(SWAP_MOVE);
+        c.remove(1);
+        assert_eq!(c.to_vec(), vec![2]);
+        assert!(MoveSet::EMPTY.choose(7).is_none());
+        assert_eq!(a.filter(|mv| mv != 1).to_vec(), vec![2, SWAP_MOVE]);
+    }
+
+    #[test]
+    fn swap_index_follows_the_board() {
+        // On a radius-5 board, 61 is an ordinary cell and 91 means swap.
+        let mut set = MoveSet::empty(91);
+        set.insert(61);
+        set.insert(91);
+        assert_eq!(set.to_vec(), vec![61, 91]);
+        assert!(set.has_swap());
+        set.remove(91);
+        assert_eq!(set.to_vec(), vec![61]);
+    }
+}
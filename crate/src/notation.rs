@@ -0,0 +1,130 @@
+//! Human-readable cell names.
+//!
+//! Columns of constant `q` are lettered from `a` (the leftmost, `q = -radius`)
+//! and cells are numbered from 1 up each column in increasing `r`, so on the
+//! standard board `a1` is cell 0, `e5` is the centre (cell 30) and `i5` is
+//! cell 60. Because cell indices follow the same column-major order, names
+//! sort the same way as indices. The swap move is written `swap`.
+
+use crate::board::Board;
+use crate::error::ParseError;
+
+pub const SWAP_NAME: &str = "swap";
+
+/// Axial `(q, r)` of a cell index, if it is on the board.
+pub fn index_to_axial(board: &Board, idx: u8) -> Option<(i32, i32)> {
+    board.axial(idx)
+}
+
+/// Cell index of axial `(q, r)`, if it is on the board.
+pub fn axial_to_index(board: &Board, q: i32, r: i32) -> Option<u8> {
+    board.index(q, r)
+}
+
+/// The lettered name of a cell, e.g. `e5`.
+pub fn cell_name(board: &Board, idx: u8) -> Option<String> {
+    let (q, r) = board.axial(idx)?;
+    let radius = i32::from(board.radius());
+    let column = (b'a' + (q + radius) as u8) as char;
+    let r_min = (-radius).max(-q - radius);
+    Some(format!("{column}{}", r - r_min + 1))
+}
+
+/// The name of a move: a cell name, or `swap`.
+pub fn move_name(board: &Board, mv: u8) -> Option<String> {
+    if mv == board.swap_move() {
+        Some(SWAP_NAME.to_string())
+    } else {
+        cell_name(board, mv)
+    }
+}
+
+/// Parses a lettered cell name (case-insensitive).
+pub fn parse_cell(board: &Board, text: &str) -> Result<u8, ParseError> {
+    let text = text.trim();
+    let mut chars = text.chars();
+    let column = chars
+        .next()
+        .filter(char::is_ascii_alphabetic)
+        .ok_or(ParseError::BadCoordinate)?
+        .to_ascii_lowercase();
+    let number: i32 = chars
+        .as_str()
+        .parse()
+        .map_err(|_| ParseError::BadCoordinate)?;
+    let radius = i32::from(board.radius());
+    let q = (column as u8 - b'a') as i32 - radius;
+    if q > radius || number < 1 {
+        return Err(ParseError::BadCoordinate);
+    }
+    let r_min = (-radius).max(-q - radius);
+    board
+        .index(q, r_min + number - 1)
+        .ok_or(ParseError::BadCoordinate)
+}
+
+/// Parses a move: a cell name or `swap`.
+pub fn parse_move(board: &Board, text: &str) -> Result<u8, ParseError> {
+    if text.trim().eq_ignore_ascii_case(SWAP_NAME) {
+        Ok(board.swap_move())
+    } else {
+        parse_cell(board, text)
+    }
+}
+
+/// Parses whitespace-separated moves, e.g. `"e5 swap d4"`.
+pub fn parse_moves(board: &Board, text: &str) -> Result<Vec<u8>, ParseError> {
+    text.split_whitespace()
+        .map(|word| parse_move(board, word))
+        .collect()
+}
+
+/// Names moves separated by spaces; indices that are not moves on this
+/// board are written as `?`.
+pub fn moves_name(board: &Board, moves: &[u8]) -> String {
+    moves
+        .iter()
+        .map(|&mv| move_name(board, mv).unwrap_or_else(|| "?".to_string()))
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::board::{MAX_RADIUS, MIN_RADIUS};
+
+    #[test]
+    fn standard_board_landmarks() {
+        let board = Board::standard();
+        assert_eq!(cell_name(board, 0).as_deref(), Some("a1"));
+        assert_eq!(cell_name(board, 30).as_deref(), Some("e5"));
+        assert_eq!(cell_name(board, 60).as_deref(), Some("i5"));
+        assert_eq!(cell_name(board, 61), None);
+        assert_eq!(move_name(board, 61).as_deref(), Some("swap"));
+        assert_eq!(parse_cell(board, "E5"), Ok(30));
+        assert_eq!(index_to_axial(board, 30), Some((0, 0)));
+        assert_eq!(axial_to_index(board, 0, 0), Some(30));
+        assert_eq!(parse_moves(board, "e5 swap d5"), Ok(vec![30, 61, 22]));
+        assert_eq!(moves_name(board, &[30, 61, 22]), "e5 swap d5");
+    }
+
+    #[test]
+    fn names_round_trip_on_every_board() {
+        for radius in MIN_RADIUS..=MAX_RADIUS {
+            let board = Board::with_radius(radius).unwrap();
+            for idx in 0..board.swap_move() + 1 {
+                let name = move_name(board, idx).unwrap();
+                assert_eq!(parse_move(board, &name), Ok(idx));
+            }
+        }
+    }
+
+    #[test]
+    fn rejects_cells_off_the_board() {
+        let board = Board::standard();
+        for text in ["a6", "j1", "e0", "e10", "5e", "", "e", "ee"] {
+            assert_eq!(parse_cell(board, text), Err(ParseError::BadCoordinate));
+        }
+    }
+}
@@ -0,0 +1,386 @@
+//! Proof-number search: exact answers to "is this a forced win?".
+//!
+//! Best-first proof-number search grows a tree of the game, always
+//! expanding the leaf that would most cheaply settle the question, until
+//! the root is proven or disproven or the node budget runs out. Yavalath's
+//! forcing play keeps the tree narrow: a player who can complete four does
+//! so, moves that make three are never tried, and a player facing a four
+//! must block it, so long sequences of threats cost one node per move.
+//!
+//! `solve` asks two questions in turn: can the side to move force a win,
+//! and if not, can the opponent? A position where neither can is a proven
+//! draw. A proof comes with the winner's strategy as a tree of moves.
+
+use serde::{Deserialize, Serialize};
+
+use crate::engine::{
+    apply_move_with_meta, candidate_moves, centered_moves, immediate_winning_moves, outcome,
+    winner_from_outcome, Candidates, Outcome, Position,
+};
+use crate::moveset::MoveSet;
+
+/// Proof and disproof numbers at or above this are infinite.
+const INFINITE: u32 = u32::MAX / 2;
+
+/// The exact result for the side to move, as far as the budget allowed.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Verdict {
+    Win,
+    Loss,
+    Draw,
+    /// The node budget ran out first.
+    Unknown,
+}
+
+/// One move of a winning strategy and everything that follows it.
+///
+/// After a move by the winner, `then` holds every reply the loser has to
+/// consider; after a loser's move it holds the winner's one answer. It is
+/// empty where the game is over or decided within two plies: the side to
+/// move has only moves that make three, or faces two fours at once.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ProofLine {
+    #[serde(rename = "move")]
+    pub mv: u8,
+    pub then: Vec<ProofLine>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SolveReport {
+    pub verdict: Verdict,
+    /// The winning move, or for a draw one that holds it.
+    pub best_move: Option<u8>,
+    /// Positions added to the proof trees.
+    pub nodes: u32,
+    /// The winner's strategy: the side to move's winning move, or after a
+    /// loss every move with its refutation. Empty for a draw, an unknown
+    /// result, or a position decided within two plies.
+    pub proof: Vec<ProofLine>,
+}
+
+struct Node {
+    pos: Position,
+    mv: u8,
+    children: Vec<usize>,
+    /// Moves still to be added as children; `None` once expanded, or for a
+    /// node settled when it was created.
+    moves: Option<MoveSet>,
+    /// A move that wins on the spot for the side to move, if it has one.
+    finisher: Option<u8>,
+    proof: u32,
+    disproof: u32,
+}
+
+/// One proof-number search for whether `attacker` can force a win.
+struct Tree {
+    nodes: Vec<Node>,
+    attacker: u8,
+}
+
+impl Tree {
+    fn new(root: Position, attacker: u8) -> Self {
+        let mut tree = Self {
+            nodes: Vec::new(),
+            attacker,
+        };
+        tree.add(root, None, 0);
+        tree
+    }
+
+    fn is_or(&self, idx: usize) -> bool {
+        self.nodes[idx].pos.turn == self.attacker
+    }
+
+    /// Adds `pos` as a node, settled at once if the tactics decide it.
+    fn add(&mut self, pos: Position, just_played: Option<(u8, u8)>, mv: u8) -> usize {
+        let mover = pos.turn;
+        let mut finisher = None;
+        let mut moves = None;
+        let result = outcome(pos, just_played);
+        let settled = match winner_from_outcome(result) {
+            Some(winner) => Some(winner == self.attacker),
+            None if !matches!(result, Outcome::Ongoing) => Some(false),
+            None => {
+                let wins = immediate_winning_moves(pos);
+                if !wins.is_empty() {
+                    finisher = centered_moves(pos.board(), wins).next();
+                    Some(mover == self.attacker)
+                } else {
+                    match candidate_moves(pos) {
+                        Candidates::LostIn(_) => Some(mover != self.attacker),
+                        // Nothing to play that does not lose at once, yet
+                        // no line: only an all-draw board gets here.
+                        Candidates::Moves(found) if found.is_empty() => Some(false),
+                        Candidates::Moves(found) => {
+                            moves = Some(found);
+                            None
+                        }
+                    }
+                }
+            }
+        };
+        let (proof, disproof) = match (settled, moves) {
+            (Some(true), _) => (0, INFINITE),
+            (Some(false), _) => (INFINITE, 0),
+            // More moves make an OR node easier to prove and an AND node
+            // harder.
+            (None, Some(found)) if mover == self.attacker => (1, found.len() as u32),
+            (None, Some(found)) => (found.len() as u32, 1),
+            (None, None) => unreachable!("unsettled nodes keep their moves"),
+        };
+        self.nodes.push(Node {
+            pos,
+            mv,
+            children: Vec::new(),
+            moves,
+            finisher,
+            proof,
+            disproof,
+        });
+        self.nodes.len() - 1
+    }
+
+    /// Grows the tree until the root is settled or it holds `budget`
+    /// nodes.
+    fn run(&mut self, budget: usize, parents: &mut Vec<usize>) {
+        while self.nodes[0].proof != 0 && self.nodes[0].disproof != 0 {
+            if self.nodes.len() >= budget {
+                return;
+            }
+            // Walk down to the most-proving leaf.
+            parents.clear();
+            let mut idx = 0;
+            while self.nodes[idx].moves.is_none() {
+                parents.push(idx);
+                let or = self.is_or(idx);
+                idx = *self.nodes[idx]
+                    .children
+                    .iter()
+                    .min_by_key(|&&child| {
+                        let child = &self.nodes[child];
+                        if or {
+                            child.proof
+                        } else {
+                            child.disproof
+                        }
+                    })
+                    .expect("an expanded, unsettled node has children");
+            }
+            self.expand(idx);
+            self.update(idx);
+            while let Some(parent) = parents.pop() {
+                self.update(parent);
+            }
+        }
+    }
+
+    fn expand(&mut self, idx: usize) {
+        let pos = self.nodes[idx].pos;
+        let moves = self.nodes[idx].moves.take().expect("leaf has moves");
+        let children = centered_moves(pos.board(), moves)
+            .filter_map(|mv| {
+                let (next, just_played) = apply_move_with_meta(pos, mv).ok()?;
+                Some(self.add(next, just_played, mv))
+            })
+            .collect();
+        self.nodes[idx].children = children;
+    }
+
+    fn update(&mut self, idx: usize) {
+        let (mut min_proof, mut min_disproof) = (INFINITE, INFINITE);
+        let (mut sum_proof, mut sum_disproof) = (0_u32, 0_u32);
+        for &child in &self.nodes[idx].children {
+            let child = &self.nodes[child];
+            min_proof = min_proof.min(child.proof);
+            min_disproof = min_disproof.min(child.disproof);
+            sum_proof = sum_proof.saturating_add(child.proof);
+            sum_disproof = sum_disproof.saturating_add(child.disproof);
+        }
+        let (proof, disproof) = if self.is_or(idx) {
+            (min_proof, sum_disproof.min(INFINITE))
+        } else {
+            (sum_proof.min(INFINITE), min_disproof)
+        };
+        self.nodes[idx].proof = proof;
+        self.nodes[idx].disproof = disproof;
+    }
+
+    fn proven(&self) -> bool {
+        self.nodes[0].proof == 0
+    }
+
+    fn disproven(&self) -> bool {
+        self.nodes[0].disproof == 0
+    }
+
+    /// The attacker's strategy below a proven node: one child at OR nodes,
+    /// all of them at AND nodes.
+    fn strategy(&self, idx: usize) -> Vec<ProofLine> {
+        let node = &self.nodes[idx];
+        if node.children.is_empty() {
+            return node
+                .finisher
+                .filter(|_| self.is_or(idx))
+                .map(|mv| ProofLine {
+                    mv,
+                    then: Vec::new(),
+                })
+                .into_iter()
+                .collect();
+        }
+        let line = |child: usize| ProofLine {
+            mv: self.nodes[child].mv,
+            then: self.strategy(child),
+        };
+        if self.is_or(idx) {
+            node.children
+                .iter()
+                .copied()
+                .find(|&child| self.nodes[child].proof == 0)
+                .map(line)
+                .into_iter()
+                .collect()
+        } else {
+            node.children.iter().copied().map(line).collect()
+        }
+    }
+
+    /// A root child that refutes the attacker, for a disproven root.
+    fn refutation(&self) -> Option<u8> {
+        let root = &self.nodes[0];
+        root.children
+            .iter()
+            .find(|&&child| self.nodes[child].disproof == 0)
+            .map(|&child| self.nodes[child].mv)
+            .or(root.finisher)
+    }
+}
+
+/// Solves `pos` for the side to move. The searches stop expanding once
+/// they hold `max_nodes` positions between them, so the count can end up
+/// one expansion over.
+pub fn solve(pos: Position, max_nodes: u32) -> SolveReport {
+    let budget = max_nodes as usize;
+    let mut parents = Vec::new();
+
+    let mut win = Tree::new(pos, pos.turn);
+    win.run(budget, &mut parents);
+    if win.proven() {
+        let proof = win.strategy(0);
+        return SolveReport {
+            verdict: Verdict::Win,
+            best_move: proof.first().map(|line| line.mv),
+            nodes: win.nodes.len() as u32,
+            proof,
+        };
+    }
+    let used = win.nodes.len();
+    if !win.disproven() {
+        return SolveReport {
+            verdict: Verdict::Unknown,
+            best_move: None,
+            nodes: used as u32,
+            proof: Vec::new(),
+        };
+    }
+
+    let mut loss = Tree::new(pos, pos.turn ^ 1);
+    loss.run(budget.saturating_sub(used).max(1), &mut parents);
+    let nodes = (used + loss.nodes.len()) as u32;
+    let (verdict, best_move, proof) = if loss.proven() {
+        (Verdict::Loss, None, loss.strategy(0))
+    } else if loss.disproven() {
+        (Verdict::Draw, loss.refutation(), Vec::new())
+    } else {
+        (Verdict::Unknown, None, Vec::new())
+    };
+    SolveReport {
+        verdict,
+        best_move,
+        nodes,
+        proof,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures;
+    use crate::notation::parse_cell;
+
+    /// Follows `lines` from `pos`, checking that `winner` wins whatever the
+    /// other side plays.
+    fn check_strategy(
+        pos: Position,
+        just_played: Option<(u8, u8)>,
+        lines: &[ProofLine],
+        winner: u8,
+    ) {
+        let result = outcome(pos, just_played);
+        if !matches!(result, Outcome::Ongoing) {
+            assert_eq!(winner_from_outcome(result), Some(winner), "\n{pos}");
+            return;
+        }
+        let candidates = candidate_moves(pos);
+        if pos.turn == winner {
+            assert_eq!(lines.len(), 1, "\n{pos}");
+        } else if lines.is_empty() {
+            assert!(matches!(candidates, Candidates::LostIn(_)), "\n{pos}");
+            return;
+        } else {
+            let Candidates::Moves(moves) = candidates else {
+                panic!("the loser has moves to answer\n{pos}");
+            };
+            let answered: Vec<u8> = lines.iter().map(|line| line.mv).collect();
+            assert_eq!(answered.len(), moves.len(), "\n{pos}");
+            assert!(answered.iter().all(|&mv| moves.contains(mv)), "\n{pos}");
+        }
+        for line in lines {
+            let (next, next_jp) = apply_move_with_meta(pos, line.mv).unwrap();
+            check_strategy(next, next_jp, &line.then, winner);
+        }
+    }
+
+    #[test]
+    fn proves_a_double_threat() {
+        let pos = fixtures::double_threat();
+        let report = solve(pos, 10_000);
+        assert_eq!(report.verdict, Verdict::Win);
+        assert_eq!(report.best_move, parse_cell(pos.board(), "e4").ok());
+        check_strategy(pos, None, &report.proof, 0);
+    }
+
+    #[test]
+    fn proofs_are_complete_strategies() {
+        let win = fixtures::forced_win();
+        let report = solve(win, 10_000);
+        assert_eq!(report.verdict, Verdict::Win);
+        assert_eq!(report.best_move, Some(report.proof[0].mv));
+        check_strategy(win, None, &report.proof, win.turn);
+
+        let loss = fixtures::forced_loss();
+        let report = solve(loss, 10_000);
+        assert_eq!(report.verdict, Verdict::Loss);
+        assert_eq!(report.best_move, None);
+        check_strategy(loss, None, &report.proof, loss.turn ^ 1);
+    }
+
+    #[test]
+    fn proves_draws_and_gives_up_at_the_budget() {
+        let pos = fixtures::drawn_ending();
+        let report = solve(pos, 10_000);
+        assert_eq!(report.verdict, Verdict::Draw);
+        assert!(report.proof.is_empty());
+        let holding = report.best_move.unwrap();
+        let (next, jp) = apply_move_with_meta(pos, holding).unwrap();
+        assert_eq!(solve(next, 10_000).verdict, Verdict::Draw, "\n{next}");
+        assert!(!matches!(outcome(next, jp), Outcome::Lose(..)));
+
+        let win = fixtures::forced_win();
+        let report = solve(win, 20);
+        assert_eq!(report.verdict, Verdict::Unknown);
+        assert!(report.nodes < 20 + 61);
+        assert!(report.proof.is_empty());
+    }
+}
@@ -0,0 +1,450 @@
+//! A PGN-like text record of a whole game.
+//!
+//! ```text
+//! [Event "Club night"]
+//! [Date "2026.10.18"]
+//! [X "Alice"]
+//! [O "Bob"]
+//! [Result "1-0"]
+//! [Radius "4"]
+//! [Rules "swap,no-block"]
+//!
+//! 1. e5 swap 2. d4 c3 3. d5 ... 1-0
+//! ```
+//!
+//! Header tags are `[Name "value"]`, one per line, with `\"` and `\\`
+//! escapes. `X` and `O` name the players (player 0 and player 1), `Result`
+//! is `1-0`, `0-1`, `1/2-1/2` or `*`, and `Radius`, `Rules` (a spec for
+//! `rules::Rules`) and `Start` (a `yav1` position string, for games that do
+//! not start from an empty board) set the game up. Other tags, such as
+//! `Date` or `TimeControl`, are kept as they are.
+//!
+//! The move list follows in notation, with `swap` for the pie rule. Move
+//! numbers, `{comments}` and the closing result are optional when reading.
+//! Every move is replayed, so a record with an illegal move, play after the
+//! end of the game or a `Result` that contradicts the final position is
+//! rejected. A decisive result on an unfinished game is accepted, since
+//! games also end by resignation or on time.
+
+use std::fmt;
+
+use crate::board::Board;
+use crate::engine::{Outcome, Position};
+use crate::error::{ParseError, RecordError};
+use crate::fen;
+use crate::game::Game;
+use crate::notation;
+use crate::rules::Rules;
+
+/// Tags written first, in this order; any others follow as recorded.
+const TAG_ORDER: [&str; 11] = [
+    "Event",
+    "Site",
+    "Date",
+    "Round",
+    "X",
+    "O",
+    "Result",
+    "TimeControl",
+    "Radius",
+    "Rules",
+    "Start",
+];
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum GameResult {
+    XWins,
+    OWins,
+    Draw,
+    Unfinished,
+}
+
+impl GameResult {
+    pub fn from_outcome(outcome: Outcome) -> Self {
+        match outcome {
+            Outcome::Win(0, _) | Outcome::Lose(1, _) => Self::XWins,
+            Outcome::Win(..) | Outcome::Lose(..) => Self::OWins,
+            Outcome::Draw => Self::Draw,
+            Outcome::Ongoing | Outcome::Invalid => Self::Unfinished,
+        }
+    }
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::XWins => "1-0",
+            Self::OWins => "0-1",
+            Self::Draw => "1/2-1/2",
+            Self::Unfinished => "*",
+        }
+    }
+
+    pub fn parse(text: &str) -> Option<Self> {
+        [Self::XWins, Self::OWins, Self::Draw, Self::Unfinished]
+            .into_iter()
+            .find(|result| result.as_str() == text)
+    }
+}
+
+/// A game with its header tags.
+#[derive(Clone, Debug)]
+pub struct GameRecord {
+    tags: Vec<(String, String)>,
+    game: Game,
+}
+
+impl GameRecord {
+    /// A record of the moves played so far in `game`, with `Result`,
+    /// `Radius`, `Rules` and, for a game set up from a position, `Start`
+    /// filled in.
+    pub fn from_game(game: &Game) -> Self {
+        let start = game.start();
+        let mut played = Game::from_position(start);
+        for &mv in game.moves() {
+            played.play(mv).expect("moves from a game replay");
+        }
+        let mut record = Self {
+            tags: Vec::new(),
+            game: played,
+        };
+        record.put_tag("Result", GameResult::from_outcome(game.outcome()).as_str());
+        record.put_tag("Radius", &start.board().radius().to_string());
+        record.put_tag("Rules", &start.rules().to_string());
+        if start.occupied() != 0 {
+            record.put_tag("Start", &fen::encode_position(start));
+        }
+        record
+    }
+
+    pub fn game(&self) -> &Game {
+        &self.game
+    }
+
+    pub fn tag(&self, name: &str) -> Option<&str> {
+        self.tags
+            .iter()
+            .find(|(tag, _)| tag == name)
+            .map(|(_, value)| value.as_str())
+    }
+
+    pub fn tags(&self) -> &[(String, String)] {
+        &self.tags
+    }
+
+    /// Sets or replaces a tag. Names are letters, digits and underscores,
+    /// and values may not hold line breaks or other control characters, as
+    /// `parse_record` reads them.
+    pub fn set_tag(&mut self, name: &str, value: &str) -> Result<(), RecordError> {
+        if !valid_tag_name(name) {
+            return Err(RecordError::TagName(name.to_string()));
+        }
+        if value.chars().any(char::is_control) {
+            return Err(RecordError::TagValue(name.to_string()));
+        }
+        self.put_tag(name, value);
+        Ok(())
+    }
+
+    fn put_tag(&mut self, name: &str, value: &str) {
+        match self.tags.iter_mut().find(|(tag, _)| tag == name) {
+            Some(entry) => entry.1 = value.to_string(),
+            None => self.tags.push((name.to_string(), value.to_string())),
+        }
+    }
+
+    /// The `Result` tag, or the result of the final position if there is
+    /// none.
+    pub fn result(&self) -> GameResult {
+        self.tag("Result")
+            .and_then(GameResult::parse)
+            .unwrap_or_else(|| GameResult::from_outcome(self.game.outcome()))
+    }
+}
+
+impl fmt::Display for GameRecord {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let known = TAG_ORDER
+            .iter()
+            .filter_map(|&name| self.tags.iter().find(|(tag, _)| tag == name));
+        let others = self
+            .tags
+            .iter()
+            .filter(|(tag, _)| !TAG_ORDER.contains(&tag.as_str()));
+        for (name, value) in known.chain(others) {
+            let value = value.replace('\\', "\\\\").replace('"', "\\\"");
+            writeln!(f, "[{name} \"{value}\"]")?;
+        }
+        writeln!(f)?;
+
+        let start = self.game.start();
+        let board = start.board();
+        let mut tokens = Vec::new();
+        let (mut turn, mut number) = (start.turn, 1);
+        for (i, &mv) in self.game.moves().iter().enumerate() {
+            if turn == 0 {
+                tokens.push(format!("{number}."));
+            } else if i == 0 {
+                tokens.push(format!("{number}..."));
+            }
+            tokens.push(notation::move_name(board, mv).unwrap_or_else(|| "?".to_string()));
+            if turn == 1 {
+                number += 1;
+            }
+            turn ^= 1;
+        }
+        tokens.push(self.result().as_str().to_string());
+
+        // Wrap the move text at 80 columns, as PGN does.
+        let mut width = 0;
+        for token in tokens {
+            if width > 0 && width + 1 + token.len() > 80 {
+                writeln!(f)?;
+                width = 0;
+            } else if width > 0 {
+                f.write_str(" ")?;
+                width += 1;
+            }
+            f.write_str(&token)?;
+            width += token.len();
+        }
+        writeln!(f)
+    }
+}
+
+/// Reads a game record, replaying and checking every move.
+pub fn parse_record(text: &str) -> Result<GameRecord, RecordError> {
+    let mut tags = Vec::new();
+    let mut movetext = String::new();
+    for (line_idx, line) in text.lines().enumerate() {
+        let line = line.trim();
+        if line.starts_with('[') && movetext.trim().is_empty() {
+            let tag = parse_tag(line).ok_or(RecordError::Header { line: line_idx + 1 })?;
+            tags.push(tag);
+        } else {
+            movetext.push_str(line);
+            movetext.push('\n');
+        }
+    }
+    let tag = |name: &str| {
+        tags.iter()
+            .find(|(tag, _): &&(String, String)| tag == name)
+            .map(|(_, value)| value.as_str())
+    };
+
+    let rules = match tag("Rules") {
+        Some(spec) => spec.parse::<Rules>().map_err(RecordError::Setup)?,
+        None => Rules::STANDARD,
+    };
+    let start = match (tag("Start"), tag("Radius")) {
+        (Some(start), _) => fen::parse_position_with(rules, start).map_err(RecordError::Setup)?,
+        (None, Some(radius)) => {
+            let board = radius
+                .parse()
+                .ok()
+                .and_then(Board::with_radius)
+                .ok_or(RecordError::Setup(ParseError::Malformed))?;
+            Position::empty_on(board).with_rules(rules)
+        }
+        (None, None) => Position::empty().with_rules(rules),
+    };
+
+    let mut game = Game::from_position(start);
+    let mut closing = None;
+    for token in move_tokens(&movetext) {
+        if closing.is_some() {
+            // Nothing may follow the result.
+            return Err(RecordError::Notation {
+                ply: game.ply() + 1,
+                error: ParseError::Malformed,
+            });
+        }
+        if let Some(result) = GameResult::parse(&token) {
+            closing = Some(result);
+            continue;
+        }
+        let ply = game.ply() + 1;
+        let mv = notation::parse_move(start.board(), &token)
+            .map_err(|error| RecordError::Notation { ply, error })?;
+        game.play(mv)
+            .map_err(|error| RecordError::IllegalMove { ply, error })?;
+    }
+
+    let record = GameRecord { tags, game };
+    let actual = GameResult::from_outcome(record.game.outcome());
+    for stated in [record.tag("Result").and_then(GameResult::parse), closing]
+        .into_iter()
+        .flatten()
+    {
+        let resigned = actual == GameResult::Unfinished;
+        if stated != actual && !resigned {
+            return Err(RecordError::ResultMismatch {
+                recorded: stated.as_str(),
+                actual: actual.as_str(),
+            });
+        }
+    }
+    Ok(record)
+}
+
+/// Letters, digits and underscores, at least one.
+fn valid_tag_name(name: &str) -> bool {
+    !name.is_empty()
+        && name
+            .chars()
+            .all(|ch| ch.is_ascii_alphanumeric() || ch == '_')
+}
+
+/// `[Name "value"]`, with `\"` and `\\` escapes in the value.
+fn parse_tag(line: &str) -> Option<(String, String)> {
+    let inner = line.strip_prefix('[')?.strip_suffix(']')?;
+    let (name, rest) = inner.split_once(char::is_whitespace)?;
+    let quoted = rest.trim().strip_prefix('"')?.strip_suffix('"')?;
+    if !valid_tag_name(name) {
+        return None;
+    }
+    let mut value = String::new();
+    let mut chars = quoted.chars();
+    while let Some(ch) = chars.next() {
+        match ch {
+            '\\' => value.push(chars.next()?),
+            '"' => return None,
+            _ => value.push(ch),
+        }
+    }
+    Some((name.to_string(), value))
+}
+
+/// The move list's words, without comments or move numbers.
+fn move_tokens(movetext: &str) -> Vec<String> {
+    let mut uncommented = String::with_capacity(movetext.len());
+    let mut depth = 0_usize;
+    for ch in movetext.chars() {
+        match ch {
+            '{' => depth += 1,
+            '}' => depth = depth.saturating_sub(1),
+            _ if depth == 0 => uncommented.push(ch),
+            _ => {}
+        }
+        if matches!(ch, '{' | '}') {
+            uncommented.push(' ');
+        }
+    }
+    uncommented
+        .split_whitespace()
+        .map(|word| {
+            // `12.`, `12...` and `12.e5` carry a move number.
+            let digits = word.trim_start_matches(|ch: char| ch.is_ascii_digit());
+            if digits.len() < word.len() && digits.starts_with('.') {
+                digits.trim_start_matches('.')
+            } else {
+                word
+            }
+        })
+        .filter(|word| !word.is_empty())
+        .map(str::to_string)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::MoveError;
+
+    fn played(moves: &str) -> Game {
+        let mut game = Game::new();
+        for mv in notation::parse_moves(Board::standard(), moves).unwrap() {
+            game.play(mv).unwrap();
+        }
+        game
+    }
+
+    #[test]
+    fn writes_and_reads_a_finished_game() {
+        let game = played("e1 a1 e2 a3 e4 i1 e5 i3 e3");
+        let mut record = GameRecord::from_game(&game);
+        record.set_tag("X", "Alice \"the Wall\"").unwrap();
+        record.set_tag("TimeControl", "300+5").unwrap();
+        assert_eq!(
+            record.set_tag("Time Control", "5"),
+            Err(RecordError::TagName("Time Control".to_string()))
+        );
+        assert_eq!(
+            record.set_tag("Event", "Club\nnight"),
+            Err(RecordError::TagValue("Event".to_string()))
+        );
+        assert_eq!(record.tag("Event"), None);
+        let text = record.to_string();
+        assert_eq!(
+            text,
+            "[X \"Alice \\\"the Wall\\\"\"]\n[Result \"1-0\"]\n[TimeControl \"300+5\"]\n\
+             [Radius \"4\"]\n[Rules \"swap,no-block\"]\n\n\
+             1. e1 a1 2. e2 a3 3. e4 i1 4. e5 i3 5. e3 1-0\n"
+        );
+        let read = parse_record(&text).unwrap();
+        assert_eq!(read.game().moves(), game.moves());
+        assert_eq!(read.tag("X"), Some("Alice \"the Wall\""));
+        assert_eq!(read.result(), GameResult::XWins);
+        assert_eq!(read.to_string(), text);
+
+        // Every value `set_tag` takes is read back unchanged.
+        let value = "C:\\games\\\"Café\" — ½";
+        record.set_tag("Site", value).unwrap();
+        let read = parse_record(&record.to_string()).unwrap();
+        assert_eq!(read.tag("Site"), Some(value));
+    }
+
+    #[test]
+    fn reads_swaps_comments_and_setups() {
+        let text =
+            "[Rules \"swap,block\"]\n\n1. e5 {the centre} swap 2.d4 {x to move after the swap} *";
+        let record = parse_record(text).unwrap();
+        assert_eq!(record.game().moves(), &[30, 61, 21]);
+        assert!(record.game().position().rules().forced_block);
+
+        let start = played("e5 d4").position();
+        let mut game = Game::from_position(start);
+        game.play(40).unwrap();
+        let text = GameRecord::from_game(&game).to_string();
+        assert!(text.contains("[Start \"yav1 "), "{text}");
+        assert!(text.ends_with("1. f6 *\n"), "{text}");
+        assert_eq!(
+            parse_record(&text).unwrap().game().position(),
+            game.position()
+        );
+    }
+
+    #[test]
+    fn rejects_illegal_games() {
+        assert_eq!(
+            parse_record("1. e5 e5").unwrap_err(),
+            RecordError::IllegalMove {
+                ply: 2,
+                error: MoveError::Occupied(30)
+            }
+        );
+        assert_eq!(
+            parse_record("1. e1 a1 2. e2 a3 3. e4 i1 4. e5 i3 5. e3 a5").unwrap_err(),
+            RecordError::IllegalMove {
+                ply: 10,
+                error: MoveError::GameOver
+            }
+        );
+        assert_eq!(
+            parse_record("[Result \"0-1\"]\n1. e1 a1 2. e2 a3 3. e4 i1 4. e5 i3 5. e3")
+                .unwrap_err(),
+            RecordError::ResultMismatch {
+                recorded: "0-1",
+                actual: "1-0"
+            }
+        );
+        assert!(matches!(
+            parse_record("1. e5 z9").unwrap_err(),
+            RecordError::Notation { ply: 2, .. }
+        ));
+        assert_eq!(
+            parse_record("[Event Club]\n1. e5").unwrap_err(),
+            RecordError::Header { line: 1 }
+        );
+        // A resignation: the record is decisive, the board is not.
+        assert!(parse_record("[Result \"0-1\"]\n1. e5 d4 0-1").is_ok());
+    }
+}
@@ -0,0 +1,111 @@
+//! House-rule options for two-player games.
+//!
+//! Rules travel with every `Position`, so move generation, the search and
+//! parsing all see the same configuration. They can also be written as a
+//! short comma-separated spec (see `FromStr`), which is how the wasm exports
+//! and `arena_agent --rules` receive them.
+
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::str::FromStr;
+
+use crate::error::ParseError;
+
+/// Serialized by field name; missing fields take their standard values.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Rules {
+    /// The pie rule: the second player may take over the first stone instead
+    /// of replying to it.
+    pub swap: bool,
+    /// A player whose opponent threatens to complete four next turn must
+    /// occupy that cell, unless they can complete four themselves.
+    pub forced_block: bool,
+    /// The opening stone must be at least this hex distance from the centre:
+    /// 0 allows any cell, 1 forbids only the centre cell.
+    pub first_move_min_distance: u8,
+}
+
+impl Rules {
+    /// Standard Yavalath: swap on, no forced block, any opening cell.
+    pub const STANDARD: Self = Self {
+        swap: true,
+        forced_block: false,
+        first_move_min_distance: 0,
+    };
+}
+
+impl Default for Rules {
+    fn default() -> Self {
+        Self::STANDARD
+    }
+}
+
+/// Parses a comma-separated list of options applied on top of the standard
+/// rules: `swap`, `no-swap`, `block`, `no-block`, `no-centre` and
+/// `opening-distance=N`. An empty string is the standard rules.
+impl FromStr for Rules {
+    type Err = ParseError;
+
+    fn from_str(spec: &str) -> Result<Self, Self::Err> {
+        let mut rules = Self::STANDARD;
+        for option in spec.split(',').map(str::trim).filter(|o| !o.is_empty()) {
+            match option {
+                "swap" => rules.swap = true,
+                "no-swap" => rules.swap = false,
+                "block" => rules.forced_block = true,
+                "no-block" => rules.forced_block = false,
+                "no-centre" | "no-center" => rules.first_move_min_distance = 1,
+                _ => {
+                    let distance = option
+                        .strip_prefix("opening-distance=")
+                        .ok_or(ParseError::UnknownRule)?;
+                    rules.first_move_min_distance = distance
+                        .parse()
+                        .map_err(|_| ParseError::BadOpeningDistance)?;
+                }
+            }
+        }
+        Ok(rules)
+    }
+}
+
+impl fmt::Display for Rules {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(if self.swap { "swap" } else { "no-swap" })?;
+        f.write_str(if self.forced_block {
+            ",block"
+        } else {
+            ",no-block"
+        })?;
+        if self.first_move_min_distance > 0 {
+            write!(f, ",opening-distance={}", self.first_move_min_distance)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn spec_round_trips() {
+        assert_eq!("".parse::<Rules>(), Ok(Rules::STANDARD));
+        let rules: Rules = "no-swap, block, no-centre".parse().unwrap();
+        assert_eq!(
+            rules,
+            Rules {
+                swap: false,
+                forced_block: true,
+                first_move_min_distance: 1,
+            }
+        );
+        assert_eq!(rules.to_string().parse::<Rules>(), Ok(rules));
+        assert_eq!(
+            "opening-distance=x".parse::<Rules>(),
+            Err(ParseError::BadOpeningDistance)
+        );
+        assert_eq!("pie".parse::<Rules>(), Err(ParseError::UnknownRule));
+    }
+}
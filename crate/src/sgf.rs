@@ -0,0 +1,581 @@
+//! Analysis trees: a game with alternative lines, comments and engine
+//! scores, stored in an SGF-style text format.
+//!
+//! ```text
+//! (;GM[Yavalath]FF[4]SZ[4]RU[swap,no-block]
+//!  ;B[e5]C[The strongest opening.]
+//!  (;W[swap]V[0.1];B[d4])
+//!  (;W[d4]V[-0.3]C[Declining the swap.]))
+//! ```
+//!
+//! The root declares the game (`GM[Yavalath]`), the board radius (`SZ`), the
+//! rules as a spec for `rules::Rules` (`RU`) and, for analysis that does not
+//! start from an empty board, the starting position as a `yav1` string
+//! (`YP`). Each later node holds one move: `B` for player 0 and `W` for
+//! player 1, as in SGF where the first player is black, with the move in
+//! notation (`e5`, `swap`). `C` is a comment and `V` an engine score for the
+//! side that just moved. The first variation at each node is the main line.
+//!
+//! Every move is checked with `Position::apply`/`apply_swap` when it is added
+//! or read, and no move may follow one that ended the game.
+
+use std::fmt::{self, Write as _};
+
+use crate::board::{Board, MAX_CELLS};
+use crate::engine::{outcome, Outcome, Position};
+use crate::error::{MoveError, ParseError, SgfError};
+use crate::fen;
+use crate::game::Game;
+use crate::notation;
+use crate::rules::Rules;
+
+pub const GAME_ID: &str = "Yavalath";
+
+/// Most nodes on one line from the root: the root itself, a move on every
+/// cell of the largest board and the swap. Deeper nesting is rejected
+/// before it can exhaust the stack.
+const MAX_DEPTH: usize = MAX_CELLS + 2;
+
+/// Index of a node in its `GameTree`.
+pub type NodeId = usize;
+
+#[derive(Clone, Debug)]
+pub struct Node {
+    mv: Option<u8>,
+    position: Position,
+    result: Outcome,
+    parent: Option<NodeId>,
+    children: Vec<NodeId>,
+    comment: Option<String>,
+    score: Option<f64>,
+}
+
+impl Node {
+    /// The move that reached this node; `None` at the root.
+    pub fn mv(&self) -> Option<u8> {
+        self.mv
+    }
+
+    pub fn position(&self) -> Position {
+        self.position
+    }
+
+    /// The result after this node's move.
+    pub fn outcome(&self) -> Outcome {
+        self.result
+    }
+
+    pub fn parent(&self) -> Option<NodeId> {
+        self.parent
+    }
+
+    /// Replies to this node, main line first.
+    pub fn children(&self) -> &[NodeId] {
+        &self.children
+    }
+
+    pub fn comment(&self) -> Option<&str> {
+        self.comment.as_deref()
+    }
+
+    pub fn score(&self) -> Option<f64> {
+        self.score
+    }
+}
+
+/// A tree of variations from a starting position.
+#[derive(Clone, Debug)]
+pub struct GameTree {
+    nodes: Vec<Node>,
+}
+
+impl GameTree {
+    pub const ROOT: NodeId = 0;
+
+    pub fn new(start: Position) -> Self {
+        Self {
+            nodes: vec![Node {
+                mv: None,
+                position: start,
+                result: outcome(start, None),
+                parent: None,
+                children: Vec::new(),
+                comment: None,
+                score: None,
+            }],
+        }
+    }
+
+    /// A tree holding the moves of `game` as its main line.
+    pub fn from_game(game: &Game) -> Self {
+        let mut tree = Self::new(game.start());
+        let mut node = Self::ROOT;
+        for &mv in game.moves() {
+            node = tree.add_move(node, mv).expect("moves from a game replay");
+        }
+        tree
+    }
+
+    pub fn node(&self, id: NodeId) -> &Node {
+        &self.nodes[id]
+    }
+
+    pub fn node_count(&self) -> usize {
+        self.nodes.len()
+    }
+
+    /// Plays `mv` after `parent`, returning the new node, or the existing
+    /// child if that move was already there. New moves become the last
+    /// variation.
+    pub fn add_move(&mut self, parent: NodeId, mv: u8) -> Result<NodeId, MoveError> {
+        if let Some(&child) = self.nodes[parent]
+            .children
+            .iter()
+            .find(|&&child| self.nodes[child].mv == Some(mv))
+        {
+            return Ok(child);
+        }
+        let from = &self.nodes[parent];
+        if !matches!(from.result, Outcome::Ongoing) {
+            return Err(MoveError::GameOver);
+        }
+        let pos = from.position;
+        let (position, just_played) = if mv == pos.swap_move() {
+            (pos.apply_swap()?, None)
+        } else {
+            (pos.apply(mv)?, Some((pos.turn, mv)))
+        };
+        let id = self.nodes.len();
+        self.nodes.push(Node {
+            mv: Some(mv),
+            position,
+            result: outcome(position, just_played),
+            parent: Some(parent),
+            children: Vec::new(),
+            comment: None,
+            score: None,
+        });
+        self.nodes[parent].children.push(id);
+        Ok(id)
+    }
+
+    pub fn set_comment(&mut self, id: NodeId, comment: Option<&str>) {
+        self.nodes[id].comment = comment.map(str::to_string);
+    }
+
+    pub fn set_score(&mut self, id: NodeId, score: Option<f64>) {
+        self.nodes[id].score = score;
+    }
+
+    /// Makes the line through `id` the main line, by moving it to the front
+    /// of its siblings at every branch above it.
+    pub fn promote(&mut self, id: NodeId) {
+        let mut child = id;
+        while let Some(parent) = self.nodes[child].parent {
+            let siblings = &mut self.nodes[parent].children;
+            if let Some(at) = siblings.iter().position(|&c| c == child) {
+                let node = siblings.remove(at);
+                siblings.insert(0, node);
+            }
+            child = parent;
+        }
+    }
+
+    /// The main line from the root, excluding the root itself.
+    pub fn main_line(&self) -> Vec<NodeId> {
+        let mut line = Vec::new();
+        let mut node = Self::ROOT;
+        while let Some(&next) = self.nodes[node].children.first() {
+            line.push(next);
+            node = next;
+        }
+        line
+    }
+
+    /// The moves from the root to `id`.
+    pub fn moves_to(&self, id: NodeId) -> Vec<u8> {
+        let mut moves = Vec::new();
+        let mut node = id;
+        while let (Some(mv), Some(parent)) = (self.nodes[node].mv, self.nodes[node].parent) {
+            moves.push(mv);
+            node = parent;
+        }
+        moves.reverse();
+        moves
+    }
+
+    fn write_node(&self, out: &mut String, id: NodeId) {
+        let node = &self.nodes[id];
+        let board = node.position.board();
+        out.push(';');
+        if node.parent.is_none() {
+            let _ = write!(
+                out,
+                "GM[{GAME_ID}]FF[4]SZ[{}]RU[{}]",
+                board.radius(),
+                node.position.rules()
+            );
+            if node.position.occupied() != 0 {
+                let _ = write!(out, "YP[{}]", fen::encode_position(node.position));
+            }
+        }
+        if let (Some(mv), Some(parent)) = (node.mv, node.parent) {
+            let colour = if self.nodes[parent].position.turn == 0 {
+                'B'
+            } else {
+                'W'
+            };
+            let name = notation::move_name(board, mv).unwrap_or_default();
+            let _ = write!(out, "{colour}[{name}]");
+        }
+        if let Some(score) = node.score {
+            let _ = write!(out, "V[{score}]");
+        }
+        if let Some(comment) = &node.comment {
+            let _ = write!(out, "C[{}]", escape(comment));
+        }
+        match node.children[..] {
+            [] => {}
+            [only] => self.write_node(out, only),
+            ref children => {
+                for &child in children {
+                    out.push('(');
+                    self.write_node(out, child);
+                    out.push(')');
+                }
+            }
+        }
+    }
+}
+
+/// The tree in the SGF-style format; `parse_sgf` reads it back.
+impl fmt::Display for GameTree {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut out = String::from("(");
+        self.write_node(&mut out, Self::ROOT);
+        out.push(')');
+        f.write_str(&out)
+    }
+}
+
+fn escape(text: &str) -> String {
+    text.replace('\\', "\\\\").replace(']', "\\]")
+}
+
+/// A node as read, before its move is checked.
+struct RawNode {
+    offset: usize,
+    properties: Vec<(String, Vec<String>)>,
+    children: Vec<RawNode>,
+}
+
+impl RawNode {
+    fn value(&self, name: &str) -> Option<&str> {
+        self.properties
+            .iter()
+            .find(|(ident, _)| ident == name)
+            .and_then(|(_, values)| values.first())
+            .map(String::as_str)
+    }
+}
+
+struct Reader<'a> {
+    text: &'a str,
+    at: usize,
+}
+
+impl Reader<'_> {
+    fn skip_space(&mut self) {
+        let rest = &self.text[self.at..];
+        self.at += rest.len() - rest.trim_start().len();
+    }
+
+    fn peek(&mut self) -> Option<char> {
+        self.skip_space();
+        self.text[self.at..].chars().next()
+    }
+
+    fn expect(&mut self, ch: char) -> Result<(), SgfError> {
+        if self.peek() == Some(ch) {
+            self.at += ch.len_utf8();
+            Ok(())
+        } else {
+            Err(SgfError::Syntax { offset: self.at })
+        }
+    }
+
+    /// `( node+ tree* )`, with the sequence chained into one branch.
+    /// `depth` is the number of nodes above the tree.
+    fn tree(&mut self, depth: usize) -> Result<RawNode, SgfError> {
+        self.expect('(')?;
+        let mut sequence = Vec::new();
+        while self.peek() == Some(';') {
+            if depth + sequence.len() >= MAX_DEPTH {
+                return Err(SgfError::Syntax { offset: self.at });
+            }
+            sequence.push(self.node()?);
+        }
+        if sequence.is_empty() {
+            return Err(SgfError::Syntax { offset: self.at });
+        }
+        let mut variations = Vec::new();
+        while self.peek() == Some('(') {
+            variations.push(self.tree(depth + sequence.len())?);
+        }
+        self.expect(')')?;
+        let mut last = sequence.pop().expect("sequence is not empty");
+        last.children = variations;
+        while let Some(mut node) = sequence.pop() {
+            node.children = vec![last];
+            last = node;
+        }
+        Ok(last)
+    }
+
+    fn node(&mut self) -> Result<RawNode, SgfError> {
+        self.expect(';')?;
+        let offset = self.at;
+        let mut properties = Vec::new();
+        while self.peek().is_some_and(|ch| ch.is_ascii_uppercase()) {
+            let rest = &self.text[self.at..];
+            let len = rest
+                .find(|ch: char| !ch.is_ascii_uppercase())
+                .unwrap_or(rest.len());
+            let ident = rest[..len].to_string();
+            self.at += len;
+            let mut values = Vec::new();
+            while self.peek() == Some('[') {
+                values.push(self.value()?);
+            }
+            if values.is_empty() {
+                return Err(SgfError::Syntax { offset: self.at });
+            }
+            properties.push((ident, values));
+        }
+        Ok(RawNode {
+            offset,
+            properties,
+            children: Vec::new(),
+        })
+    }
+
+    fn value(&mut self) -> Result<String, SgfError> {
+        self.expect('[')?;
+        let mut value = String::new();
+        let mut chars = self.text[self.at..].char_indices();
+        while let Some((i, ch)) = chars.next() {
+            match ch {
+                ']' => {
+                    self.at += i + 1;
+                    return Ok(value);
+                }
+                '\\' => match chars.next() {
+                    Some((_, escaped)) => value.push(escaped),
+                    None => break,
+                },
+                _ => value.push(ch),
+            }
+        }
+        Err(SgfError::Syntax {
+            offset: self.text.len(),
+        })
+    }
+}
+
+/// Reads a variation tree, checking every move in every branch.
+pub fn parse_sgf(text: &str) -> Result<GameTree, SgfError> {
+    let mut reader = Reader { text, at: 0 };
+    let root = reader.tree(0)?;
+    if reader.peek().is_some_and(|ch| ch != '(') {
+        return Err(SgfError::Syntax { offset: reader.at });
+    }
+
+    if root.value("GM") != Some(GAME_ID) {
+        return Err(SgfError::NotYavalath);
+    }
+    let rules = match root.value("RU") {
+        Some(spec) => spec.parse::<Rules>().map_err(SgfError::Setup)?,
+        None => Rules::STANDARD,
+    };
+    let start = match (root.value("YP"), root.value("SZ")) {
+        (Some(start), _) => fen::parse_position_with(rules, start).map_err(SgfError::Setup)?,
+        (None, Some(size)) => {
+            let board = size
+                .parse()
+                .ok()
+                .and_then(Board::with_radius)
+                .ok_or(SgfError::Setup(ParseError::Malformed))?;
+            Position::empty_on(board).with_rules(rules)
+        }
+        (None, None) => Position::empty().with_rules(rules),
+    };
+    if root.value("B").is_some() || root.value("W").is_some() {
+        return Err(SgfError::Syntax {
+            offset: root.offset,
+        });
+    }
+
+    let mut tree = GameTree::new(start);
+    annotate(&mut tree, GameTree::ROOT, &root)?;
+    add_children(&mut tree, GameTree::ROOT, &root, 1)?;
+    Ok(tree)
+}
+
+fn add_children(
+    tree: &mut GameTree,
+    parent: NodeId,
+    raw: &RawNode,
+    ply: usize,
+) -> Result<(), SgfError> {
+    for child in &raw.children {
+        let turn = tree.node(parent).position().turn;
+        let (colour, name) = match (child.value("B"), child.value("W")) {
+            (Some(name), None) => (0, name),
+            (None, Some(name)) => (1, name),
+            _ => {
+                return Err(SgfError::Syntax {
+                    offset: child.offset,
+                })
+            }
+        };
+        if colour != turn {
+            return Err(SgfError::WrongPlayer { ply });
+        }
+        let board = tree.node(parent).position().board();
+        let mv =
+            notation::parse_move(board, name).map_err(|error| SgfError::Notation { ply, error })?;
+        let id = tree
+            .add_move(parent, mv)
+            .map_err(|error| SgfError::IllegalMove { ply, error })?;
+        annotate(tree, id, child)?;
+        add_children(tree, id, child, ply + 1)?;
+    }
+    Ok(())
+}
+
+fn annotate(tree: &mut GameTree, id: NodeId, raw: &RawNode) -> Result<(), SgfError> {
+    tree.set_comment(id, raw.value("C"));
+    let score = raw
+        .value("V")
+        .map(|value| value.trim().parse::<f64>())
+        .transpose()
+        .map_err(|_| SgfError::Syntax { offset: raw.offset })?;
+    tree.set_score(id, score);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn cell(name: &str) -> u8 {
+        notation::parse_move(Board::standard(), name).unwrap()
+    }
+
+    fn sample() -> GameTree {
+        let mut tree = GameTree::new(Position::empty());
+        let e5 = tree.add_move(GameTree::ROOT, cell("e5")).unwrap();
+        tree.set_comment(e5, Some("The strongest opening [by far]."));
+        let swap = tree.add_move(e5, cell("swap")).unwrap();
+        tree.set_score(swap, Some(0.1));
+        tree.add_move(swap, cell("d4")).unwrap();
+        let d4 = tree.add_move(e5, cell("d4")).unwrap();
+        tree.set_score(d4, Some(-0.3));
+        let c3 = tree.add_move(d4, cell("c3")).unwrap();
+        tree.add_move(d4, cell("c4")).unwrap();
+        tree.set_comment(c3, Some("a \\ b"));
+        tree
+    }
+
+    #[test]
+    fn writes_nested_variations() {
+        assert_eq!(
+            sample().to_string(),
+            "(;GM[Yavalath]FF[4]SZ[4]RU[swap,no-block];B[e5]C[The strongest opening [by far\\].]\
+             (;W[swap]V[0.1];B[d4])(;W[d4]V[-0.3](;B[c3]C[a \\\\ b])(;B[c4])))"
+        );
+    }
+
+    #[test]
+    fn round_trips() {
+        let tree = sample();
+        let text = tree.to_string();
+        let read = parse_sgf(&text).unwrap();
+        assert_eq!(read.to_string(), text);
+        assert_eq!(read.node_count(), tree.node_count());
+        let e5 = read.main_line()[0];
+        assert_eq!(
+            read.node(e5).comment(),
+            Some("The strongest opening [by far].")
+        );
+
+        let start = Position::empty().apply(30).unwrap().apply(10).unwrap();
+        let mut from_start = GameTree::new(start);
+        let f6 = from_start.add_move(GameTree::ROOT, 40).unwrap();
+        let text = from_start.to_string();
+        assert!(text.contains("YP[yav1 "), "{text}");
+        let read = parse_sgf(&text).unwrap();
+        assert_eq!(read.node(f6).position(), from_start.node(f6).position());
+    }
+
+    #[test]
+    fn promotes_a_variation_to_the_main_line() {
+        let mut tree = sample();
+        let e5 = tree.main_line()[0];
+        let d4 = tree.node(e5).children()[1];
+        let c4 = tree.node(d4).children()[1];
+        tree.promote(c4);
+        assert_eq!(tree.main_line(), vec![e5, d4, c4]);
+        assert_eq!(tree.moves_to(c4), vec![cell("e5"), cell("d4"), cell("c4")]);
+        let read = parse_sgf(&tree.to_string()).unwrap();
+        let line: Vec<u8> = read
+            .main_line()
+            .iter()
+            .filter_map(|&id| read.node(id).mv())
+            .collect();
+        assert_eq!(line, vec![cell("e5"), cell("d4"), cell("c4")]);
+    }
+
+    #[test]
+    fn checks_every_branch() {
+        assert_eq!(
+            parse_sgf("(;GM[Yavalath];B[e5](;W[d4])(;W[e5]))").unwrap_err(),
+            SgfError::IllegalMove {
+                ply: 2,
+                error: MoveError::Occupied(30)
+            }
+        );
+        assert_eq!(
+            parse_sgf("(;GM[Yavalath];B[e5];B[d4])").unwrap_err(),
+            SgfError::WrongPlayer { ply: 2 }
+        );
+        assert_eq!(
+            parse_sgf("(;GM[Yavalath]RU[no-swap];B[e5];W[swap])").unwrap_err(),
+            SgfError::IllegalMove {
+                ply: 2,
+                error: MoveError::SwapNotAllowed
+            }
+        );
+        assert_eq!(
+            parse_sgf("(;GM[1];B[e5])").unwrap_err(),
+            SgfError::NotYavalath
+        );
+        assert!(matches!(
+            parse_sgf("(;GM[Yavalath];B[e5]"),
+            Err(SgfError::Syntax { .. })
+        ));
+        // Nesting far past any legal line stops early instead of
+        // overflowing the stack.
+        let nested = format!("(;GM[Yavalath]{}", "(;".repeat(200_000));
+        assert!(matches!(parse_sgf(&nested), Err(SgfError::Syntax { .. })));
+        let long = format!("(;GM[Yavalath]{})", ";".repeat(200_000));
+        assert!(matches!(parse_sgf(&long), Err(SgfError::Syntax { .. })));
+        // Nothing may follow a move that ended the game.
+        let mut tree = GameTree::new(Position::empty());
+        let mut node = GameTree::ROOT;
+        for name in ["e1", "a1", "e2", "a3", "e4", "i1", "e5", "i3", "e3"] {
+            node = tree.add_move(node, cell(name)).unwrap();
+        }
+        assert_eq!(tree.add_move(node, cell("a5")), Err(MoveError::GameOver));
+    }
+}
@@ -0,0 +1,258 @@
+//! Standalone SVG drawings of positions.
+//!
+//! This is also how the web front-end (`src/main.js`) draws its board, via
+//! `Game.svg`: pointy-top hexes laid out by `axial_to_pixel`, red for
+//! player 0 and blue for player 1 (as in `src/style.css`), and a heavy
+//! outline on cells of a completed line. Every cell polygon also carries
+//! `class="cell ..."` and `data-idx`, which the front-end styles and uses to
+//! map clicks to moves.
+//!
+//! `SvgOptions` adds the highlights used in reports and puzzle sheets: the
+//! last move, a heatmap over cells (e.g. search visits) and short text
+//! annotations. It deserializes from JSON with every field optional, which
+//! is how the wasm export receives it.
+
+use std::fmt::Write as _;
+
+use serde::Deserialize;
+
+use crate::board::{bit, Bitboard};
+use crate::engine::{outcome, Outcome, Position};
+use crate::notation;
+
+const EMPTY_FILL: &str = "#f8fafc";
+const P0_FILL: &str = "#ef4444";
+const P1_FILL: &str = "#0ea5e9";
+const CELL_STROKE: &str = "#94a3b8";
+const LINE_STROKE: &str = "#111827";
+const HEAT_FILL: &str = "#f59e0b";
+const LABEL_FILL: &str = "#64748b";
+
+#[derive(Clone, Debug, PartialEq, Deserialize)]
+#[serde(default)]
+pub struct SvgOptions {
+    /// Marks this move with a ring; the swap move marks nothing.
+    pub last_move: Option<u8>,
+    /// The result whose lines are outlined; found from the whole board when
+    /// absent.
+    pub outcome: Option<Outcome>,
+    /// `(cell, weight)` pairs shaded in proportion to the largest weight.
+    pub heatmap: Vec<(u8, f64)>,
+    /// `(cell, text)` pairs written in the middle of the cell.
+    pub annotations: Vec<(u8, String)>,
+    /// Writes each empty cell's name (`e5`) in it.
+    pub coordinates: bool,
+    /// Distance from a hex's centre to its corners, in SVG units.
+    pub hex_size: f64,
+}
+
+impl Default for SvgOptions {
+    fn default() -> Self {
+        Self {
+            last_move: None,
+            outcome: None,
+            heatmap: Vec::new(),
+            annotations: Vec::new(),
+            coordinates: false,
+            hex_size: 42.0,
+        }
+    }
+}
+
+/// Centre of cell `(q, r)` relative to the board centre, for hexes of
+/// `size`.
+pub fn axial_to_pixel(q: i32, r: i32, size: f64) -> (f64, f64) {
+    let width = 3f64.sqrt() * size;
+    let height = 2.0 * size;
+    (
+        width * (f64::from(q) + f64::from(r) / 2.0),
+        height * 0.75 * f64::from(r),
+    )
+}
+
+pub fn render_svg(pos: Position, options: &SvgOptions) -> String {
+    let board = pos.board();
+    let size = options.hex_size;
+    let radius = f64::from(board.radius());
+    let result = options.outcome.unwrap_or_else(|| outcome(pos, None));
+    let line_cells: Bitboard = result
+        .lines()
+        .map_or(0, |lines| lines.iter().fold(0, |a, b| a | b));
+    let max_heat = options
+        .heatmap
+        .iter()
+        .map(|&(_, weight)| weight)
+        .fold(0.0, f64::max);
+
+    // The board spans 2r + 1 hex widths across and 1.5r + 1 hex heights
+    // down; leave half a hex of margin on every side.
+    let width = 3f64.sqrt() * size * (2.0 * radius + 2.0);
+    let height = 2.0 * size * (1.5 * radius + 1.5);
+    let (cx, cy) = (width / 2.0, height / 2.0);
+
+    let mut svg = String::new();
+    let _ = write!(
+        svg,
+        r#"<svg xmlns="http://www.w3.org/2000/svg" viewBox="0 0 {width:.2} {height:.2}" width="{width:.0}" height="{height:.0}">"#
+    );
+    let _ = write!(svg, r#"<rect width="100%" height="100%" fill="white"/>"#);
+
+    for (idx, &(q, r)) in board.axial_cells().iter().enumerate() {
+        let idx = idx as u8;
+        let cell = bit(idx);
+        let (dx, dy) = axial_to_pixel(q, r, size);
+        let (x, y) = (cx + dx, cy + dy);
+        let points: Vec<String> = (0..6)
+            .map(|corner| {
+                let angle =
+                    std::f64::consts::FRAC_PI_3 * f64::from(corner) + std::f64::consts::FRAC_PI_6;
+                format!(
+                    "{:.2},{:.2}",
+                    x + size * angle.cos(),
+                    y + size * angle.sin()
+                )
+            })
+            .collect();
+        let (fill, class) = if pos.p0 & cell != 0 {
+            (P0_FILL, "cell p0")
+        } else if pos.p1 & cell != 0 {
+            (P1_FILL, "cell p1")
+        } else {
+            (EMPTY_FILL, "cell")
+        };
+        let (stroke, stroke_width, line_class) = if line_cells & cell != 0 {
+            (LINE_STROKE, 5, " line")
+        } else {
+            (CELL_STROKE, 2, "")
+        };
+        let _ = write!(
+            svg,
+            r#"<polygon class="{class}{line_class}" data-idx="{idx}" points="{}" fill="{fill}" stroke="{stroke}" stroke-width="{stroke_width}"/>"#,
+            points.join(" ")
+        );
+
+        let heat = options
+            .heatmap
+            .iter()
+            .filter(|&&(heat_cell, _)| heat_cell == idx)
+            .map(|&(_, weight)| weight)
+            .sum::<f64>();
+        if max_heat > 0.0 && heat > 0.0 {
+            let _ = write!(
+                svg,
+                r#"<polygon class="heat" points="{}" fill="{HEAT_FILL}" fill-opacity="{:.3}" pointer-events="none"/>"#,
+                points.join(" "),
+                0.75 * heat / max_heat
+            );
+        }
+        if options.last_move == Some(idx) {
+            let _ = write!(
+                svg,
+                r#"<circle class="last-move" cx="{x:.2}" cy="{y:.2}" r="{:.2}" fill="none" stroke="white" stroke-width="3" pointer-events="none"/>"#,
+                size * 0.35
+            );
+        }
+
+        let annotation = options
+            .annotations
+            .iter()
+            .find(|(note_cell, _)| *note_cell == idx)
+            .map(|(_, text)| escape(text));
+        let name = (options.coordinates && (pos.p0 | pos.p1) & cell == 0)
+            .then(|| notation::cell_name(board, idx))
+            .flatten();
+        if let Some(text) = annotation.or(name) {
+            let colour = if (pos.p0 | pos.p1) & cell != 0 {
+                "white"
+            } else {
+                LABEL_FILL
+            };
+            let _ = write!(
+                svg,
+                r#"<text x="{x:.2}" y="{y:.2}" font-family="sans-serif" font-size="{:.1}" text-anchor="middle" dominant-baseline="central" fill="{colour}" pointer-events="none">{text}</text>"#,
+                size * 0.4
+            );
+        }
+    }
+    svg.push_str("</svg>");
+    svg
+}
+
+fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for ch in text.chars() {
+        match ch {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            _ => escaped.push(ch),
+        }
+    }
+    escaped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::board::Board;
+    use crate::diagram::parse_diagram;
+
+    #[test]
+    fn draws_every_cell_with_its_stone() {
+        let pos = Position::empty().apply(30).unwrap().apply(0).unwrap();
+        let svg = render_svg(pos, &SvgOptions::default());
+        assert!(svg.starts_with("<svg xmlns=\"http://www.w3.org/2000/svg\""));
+        assert!(svg.ends_with("</svg>"));
+        assert_eq!(svg.matches("<polygon class=\"cell").count(), 61);
+        assert!(svg.contains(r#"class="cell p0" data-idx="30""#));
+        assert!(svg.contains(r#"class="cell p1" data-idx="0""#));
+
+        let board = Board::with_radius(6).unwrap();
+        let svg = render_svg(Position::empty_on(board), &SvgOptions::default());
+        assert_eq!(svg.matches("<polygon class=\"cell").count(), 127);
+    }
+
+    #[test]
+    fn draws_highlights() {
+        let pos = parse_diagram(
+            "
+                a o . . . .
+               b o . . . . .
+              c . . . . . . .
+             d . . . . . . . .
+            e X X X X . . . . .
+             f . . . . . . . .
+              g . . . . . . .
+               h o . . . . .
+                i . . . . .
+            ",
+        )
+        .unwrap();
+        let options = SvgOptions {
+            last_move: Some(29),
+            heatmap: vec![(40, 3.0), (41, 1.0)],
+            annotations: vec![(50, "a<b".to_string())],
+            coordinates: true,
+            ..SvgOptions::default()
+        };
+        let svg = render_svg(pos, &options);
+        assert_eq!(svg.matches(r#"line" data-idx"#).count(), 4);
+        assert!(svg.contains(r#"class="cell p0 line" data-idx="29""#));
+        assert_eq!(svg.matches("class=\"last-move\"").count(), 1);
+        assert!(svg.contains(r#"fill-opacity="0.750""#));
+        assert!(svg.contains(r#"fill-opacity="0.250""#));
+        assert!(svg.contains(">a&lt;b</text>"));
+        assert!(svg.contains(">e5</text>"));
+    }
+
+    #[test]
+    fn options_read_from_json() {
+        let options: SvgOptions =
+            serde_json::from_str(r#"{"last_move":30,"heatmap":[[30,0.5]],"hex_size":20}"#).unwrap();
+        assert_eq!(options.last_move, Some(30));
+        assert_eq!(options.heatmap, vec![(30, 0.5)]);
+        assert_eq!(options.hex_size, 20.0);
+        assert!(!options.coordinates);
+    }
+}
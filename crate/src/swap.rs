@@ -0,0 +1,232 @@
+//! Swap decisions for the second player.
+//!
+//! Whether to swap depends only on where the first stone went, so on the
+//! standard board the answer is worked out ahead of time: `STANDARD_TABLE`
+//! holds, for one cell of each symmetry class, the value to the second
+//! player of swapping and of the best reply without swapping, each the
+//! mean of several long searches (`search_averaged`; `book_gen
+//! --swap-table` prints it). Other boards and the forced-block rule have no
+//! table; `evaluate` searches them on the spot.
+//!
+//! `Searcher::best_move` follows the table unless `MoveOptions::swap_table`
+//! is off: it swaps when swapping is worth more, and otherwise searches the
+//! replies with the swap left out. The opening book (`book::generate`)
+//! makes the same choices, so the two never disagree.
+
+use std::collections::BTreeSet;
+
+use serde::{Deserialize, Serialize};
+
+use crate::board::{bit, Board};
+use crate::engine::{search_seeded, Position, Searcher};
+use crate::geometry::canonical;
+use crate::rules::Rules;
+
+/// One row per symmetry class of the first move on the standard board:
+/// the class's canonical cell, then the value to the second player of
+/// swapping and of keeping, from -1 to 1. Each value is the mean of eight
+/// 10-second MCTS searches at strength 3 (`search_averaged`).
+const STANDARD_TABLE: [(u8, f32, f32); 9] = [
+    (0, -0.155, 0.156),
+    (1, -0.166, 0.164),
+    (2, -0.164, 0.164),
+    (6, -0.164, 0.168),
+    (7, -0.133, 0.134),
+    (13, -0.040, 0.039),
+    (14, 0.017, -0.013),
+    (21, -0.158, 0.156),
+    (30, -0.080, 0.089),
+];
+
+/// What the second player should do about the swap.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub struct SwapAdvice {
+    pub swap: bool,
+    /// Expected result of swapping for the second player, from -1 to 1.
+    pub swap_value: f64,
+    /// Expected result of the best reply that keeps the colours.
+    pub keep_value: f64,
+    /// Half the gap between the two values: 0 when they are level, 1 when
+    /// one choice wins for sure and the other loses.
+    pub confidence: f64,
+    /// The values come from the precomputed table rather than a search.
+    pub precomputed: bool,
+}
+
+impl SwapAdvice {
+    fn new(swap_value: f64, keep_value: f64, precomputed: bool) -> Self {
+        Self {
+            swap: swap_value > keep_value,
+            swap_value,
+            keep_value,
+            confidence: ((swap_value - keep_value).abs() / 2.0).min(1.0),
+            precomputed,
+        }
+    }
+}
+
+/// `pos` with the swap ruled out, so a search only considers replies that
+/// keep the colours.
+pub fn without_swap(pos: Position) -> Position {
+    pos.with_rules(Rules {
+        swap: false,
+        ..pos.rules()
+    })
+}
+
+/// One first move from each symmetry class of `board`, in cell order: the
+/// cell `canonical` moves the others of its class to.
+pub fn representative_openings(board: &'static Board) -> Vec<u8> {
+    (0..board.cell_count() as u8)
+        .map(|cell| opening_class(board, cell))
+        .collect::<BTreeSet<_>>()
+        .into_iter()
+        .collect()
+}
+
+/// The cell `canonical` moves a first stone on `cell` to, the same for
+/// every cell of a symmetry class.
+fn opening_class(board: &'static Board, cell: u8) -> u8 {
+    let opened = Position::from_bitboards_on(board, bit(cell), 0, 1, 1);
+    canonical(opened).0.p0.trailing_zeros() as u8
+}
+
+/// The table's advice for `pos`, if it is a swap decision the table
+/// covers.
+pub fn precomputed(pos: Position) -> Option<SwapAdvice> {
+    if !pos.can_swap() {
+        return None;
+    }
+    if *pos.board() != *Board::standard() || pos.rules().forced_block {
+        return None;
+    }
+    if pos.p0.count_ones() != 1 {
+        return None;
+    }
+    let cell = opening_class(pos.board(), pos.p0.trailing_zeros() as u8);
+    let &(_, swap_value, keep_value) = STANDARD_TABLE.iter().find(|row| row.0 == cell)?;
+    Some(SwapAdvice::new(
+        f64::from(swap_value),
+        f64::from(keep_value),
+        true,
+    ))
+}
+
+/// Advice from two searches of `budget_ms` each: the position after the
+/// swap, and `pos` with the swap ruled out. `None` unless `pos` is a swap
+/// decision.
+pub fn search(
+    pos: Position,
+    searcher: Searcher,
+    budget_ms: f64,
+    strength: u8,
+) -> Option<SwapAdvice> {
+    let swapped = pos.apply_swap().ok()?;
+    // After the swap the first player moves, so their value is negated.
+    let swap_value = -searcher.search(swapped, budget_ms, strength).value?;
+    let keep_value = searcher
+        .search(without_swap(pos), budget_ms, strength)
+        .value?;
+    Some(SwapAdvice::new(swap_value, keep_value, false))
+}
+
+/// Advice from the mean values of `runs` MCTS searches of `budget_ms` for
+/// each choice, every run with its own playout seed. `None` unless `pos` is
+/// a swap decision.
+pub fn search_averaged(
+    pos: Position,
+    budget_ms: f64,
+    strength: u8,
+    runs: u32,
+) -> Option<SwapAdvice> {
+    let swapped = pos.apply_swap().ok()?;
+    let kept = without_swap(pos);
+    let runs = runs.max(1);
+    let (mut swap_sum, mut keep_sum) = (0.0, 0.0);
+    for run in 1..=u64::from(runs) {
+        let seed = pos.hash ^ run.wrapping_mul(0x9e37_79b9_7f4a_7c15);
+        swap_sum -= search_seeded(swapped, budget_ms, strength, seed).value?;
+        keep_sum += search_seeded(kept, budget_ms, strength, seed).value?;
+    }
+    let runs = f64::from(runs);
+    Some(SwapAdvice::new(swap_sum / runs, keep_sum / runs, false))
+}
+
+/// The advice for `pos`: from the table when it covers `pos`, otherwise
+/// from MCTS with `budget_ms` split between the two searches. `None`
+/// unless `pos` is a swap decision.
+pub fn evaluate(pos: Position, budget_ms: f64, strength: u8) -> Option<SwapAdvice> {
+    precomputed(pos).or_else(|| search(pos, Searcher::Mcts, budget_ms / 2.0, strength))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::book;
+    use crate::engine::{best_move_with_strength, MoveOptions};
+    use crate::geometry::Transform;
+
+    #[test]
+    fn table_covers_every_opening_class() {
+        let board = Board::standard();
+        assert_eq!(
+            representative_openings(board),
+            STANDARD_TABLE.map(|row| row.0)
+        );
+
+        let start = Position::empty();
+        for cell in 0..board.cell_count() as u8 {
+            let opened = start.apply(cell).unwrap();
+            let advice = precomputed(opened).unwrap();
+            assert!(advice.precomputed);
+            for t in Transform::all() {
+                assert_eq!(precomputed(t.position(opened)), Some(advice));
+            }
+        }
+        assert_eq!(precomputed(start), None);
+        let no_swap = start.with_rules("no-swap".parse().unwrap());
+        assert_eq!(precomputed(no_swap.apply(30).unwrap()), None);
+    }
+
+    #[test]
+    fn follows_the_table() {
+        for &(cell, swap_value, keep_value) in &STANDARD_TABLE {
+            let opened = Position::empty().apply(cell).unwrap();
+            let mv = best_move_with_strength(opened, 5.0, 0).unwrap();
+            assert_eq!(mv == opened.swap_move(), swap_value > keep_value, "{cell}");
+
+            // The book was generated from the same table.
+            let booked = book::embedded().moves(opened).unwrap();
+            let swaps = booked.iter().any(|entry| entry.mv == opened.swap_move());
+            assert_eq!(swaps, swap_value > keep_value, "{cell}");
+            assert!(!swaps || booked.len() == 1, "{cell}");
+        }
+
+        let searched = MoveOptions {
+            book: false,
+            swap_table: false,
+            seed: 0,
+        };
+        let centre = Position::empty().apply(30).unwrap();
+        assert!(Searcher::Mcts
+            .best_move_with(centre, 5.0, 0, searched)
+            .is_some());
+    }
+
+    #[test]
+    fn searches_boards_without_a_table() {
+        let board = Board::with_radius(3).unwrap();
+        assert_eq!(representative_openings(board).len(), 1 + 1 + 2 + 2);
+        let opened = Position::empty_on(board).apply(0).unwrap();
+        assert_eq!(precomputed(opened), None);
+        let advice = evaluate(opened, 20.0, 0).unwrap();
+        assert!(!advice.precomputed);
+        assert_eq!(advice.swap, advice.swap_value > advice.keep_value);
+        assert!((0.0..=1.0).contains(&advice.confidence));
+        assert_eq!(evaluate(opened.apply(1).unwrap(), 20.0, 0), None);
+
+        let averaged = search_averaged(opened, 5.0, 0, 3).unwrap();
+        assert!(!averaged.precomputed);
+        assert_eq!(averaged.swap, averaged.swap_value > averaged.keep_value);
+    }
+}
@@ -0,0 +1,250 @@
+//! Threat-space search: forced wins made only of four-threats.
+//!
+//! A forcing move leaves the attacker a cell that would complete four, so
+//! the defender's reply is fixed: block that cell, or win on the spot. A
+//! chain of such moves wins when it ends in two threats at once (only one
+//! can be blocked) or in a threat whose block makes three for the defender.
+//! Looking only at forcing moves keeps the tree tiny, so chains several
+//! moves long are found in a fraction of a playout budget.
+//!
+//! The search is sound but not complete: every line it returns is a forced
+//! win, but wins that need a quiet move somewhere are out of its reach; see
+//! `pns` for those. `engine::search` runs it before MCTS.
+
+use std::collections::HashSet;
+
+use crate::board::{bit, Bitboard};
+use crate::engine::{
+    centered_moves, has_line3_from_move, has_line4_from_move, immediate_winning_moves, outcome,
+    Outcome, Position,
+};
+use crate::moveset::MoveSet;
+
+/// Attacker moves the MCTS pre-check looks ahead.
+pub const PRECHECK_MOVES: u8 = 6;
+
+/// Empty cells where `player` would complete four.
+fn fours(pos: Position, player: u8) -> Bitboard {
+    let board = pos.board();
+    let bits = pos.stones(player);
+    MoveSet::from_cells(pos.swap_move(), board.mask() & !pos.occupied())
+        .filter(|mv| has_line4_from_move(board, bits | bit(mv), mv).is_some())
+        .cells()
+}
+
+/// Cells where the side to move makes a four-threat without making three.
+fn forcing_moves(pos: Position) -> MoveSet {
+    let board = pos.board();
+    let (ours, theirs) = (pos.stones(pos.turn), pos.stones(pos.turn ^ 1));
+    pos.legal_moves().filter(|mv| {
+        let (masks, _) = board.win_checks(mv);
+        let makes_threat = masks.iter().any(|&mask| {
+            mask != Bitboard::MAX && theirs & mask == 0 && (ours & mask).count_ones() == 2
+        });
+        makes_threat && has_line3_from_move(board, ours | bit(mv), mv).is_none()
+    })
+}
+
+struct Search {
+    /// Positions already shown to have no win within the given moves.
+    refuted: HashSet<(u64, u8)>,
+}
+
+impl Search {
+    /// Looks for a win in at most `moves_left` attacker moves from `pos`,
+    /// with the attacker to move. On success the line is appended to `line`.
+    fn attack(&mut self, pos: Position, moves_left: u8, line: &mut Vec<u8>) -> bool {
+        if moves_left == 0 || self.refuted.contains(&(pos.hash, moves_left)) {
+            return false;
+        }
+        if let Some(win) = centered_moves(pos.board(), immediate_winning_moves(pos)).next() {
+            line.push(win);
+            return true;
+        }
+        let attacker = pos.turn;
+        // A threat of the defender's own has to be blocked first, and the
+        // block only counts if it is a threat too.
+        let counter = fours(pos, attacker ^ 1);
+        if counter.count_ones() > 1 {
+            return false;
+        }
+        let mut candidates = forcing_moves(pos);
+        if counter != 0 {
+            candidates = candidates.filter(|mv| counter & bit(mv) != 0);
+        }
+
+        let start = line.len();
+        for mv in centered_moves(pos.board(), candidates) {
+            let Ok(next) = pos.apply(mv) else {
+                continue;
+            };
+            let threats = fours(next, attacker);
+            if threats == 0 || !immediate_winning_moves(next).is_empty() {
+                continue;
+            }
+            line.push(mv);
+            // With two threats the defender blocks one and the other wins;
+            // with one, the block is forced.
+            let double = threats.count_ones() > 1;
+            if double && moves_left < 2 {
+                line.truncate(start);
+                continue;
+            }
+            let Some(block) = centered_moves(next.board(), next.legal_moves())
+                .find(|&cell| threats & bit(cell) != 0)
+            else {
+                line.truncate(start);
+                continue;
+            };
+            let Ok(after) = next.apply(block) else {
+                line.truncate(start);
+                continue;
+            };
+            line.push(block);
+            let won = match outcome(after, Some((attacker ^ 1, block))) {
+                Outcome::Lose(player, _) => player != attacker,
+                Outcome::Ongoing => self.attack(after, moves_left - 1, line),
+                _ => false,
+            };
+            if won {
+                return true;
+            }
+            line.truncate(start);
+        }
+        self.refuted.insert((pos.hash, moves_left));
+        false
+    }
+}
+
+/// The shortest forced win for the side to move of at most `max_moves`
+/// of its own moves, all of them four-threats but the last. The line
+/// alternates attacker and defender moves and ends in the attacker's four,
+/// or in the defender's block that makes three.
+pub fn find_forced_win(pos: Position, max_moves: u8) -> Option<Vec<u8>> {
+    if !matches!(outcome(pos, None), Outcome::Ongoing) {
+        return None;
+    }
+    let mut search = Search {
+        refuted: HashSet::new(),
+    };
+    let mut line = Vec::new();
+    (1..=max_moves)
+        .any(|moves| search.attack(pos, moves, &mut line))
+        .then_some(line)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::board::Board;
+    use crate::diagram::parse_diagram;
+    use crate::engine::apply_move_with_meta;
+    use crate::fixtures;
+    use crate::notation::{move_name, parse_moves};
+    use crate::pns::{solve, Verdict};
+
+    fn names(pos: Position, line: &[u8]) -> String {
+        line.iter()
+            .map(|&mv| move_name(pos.board(), mv).unwrap())
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+
+    /// Plays `line` and returns the winner.
+    fn replay(pos: Position, line: &[u8]) -> Option<u8> {
+        let (mut pos, mut jp) = (pos, None);
+        for &mv in line {
+            assert!(matches!(outcome(pos, jp), Outcome::Ongoing));
+            (pos, jp) = apply_move_with_meta(pos, mv).unwrap();
+        }
+        match outcome(pos, jp) {
+            Outcome::Win(player, _) => Some(player),
+            Outcome::Lose(player, _) => Some(player ^ 1),
+            _ => None,
+        }
+    }
+
+    #[test]
+    fn finds_a_double_threat() {
+        let pos = fixtures::double_threat();
+        assert_eq!(find_forced_win(pos, 1), None);
+        let line = find_forced_win(pos, 2).unwrap();
+        assert_eq!(names(pos, &line), "e4 d3 e3");
+        assert_eq!(replay(pos, &line), Some(0));
+
+        // MCTS plays it without searching.
+        let report = crate::engine::search(pos, 20.0, 0);
+        assert_eq!(report.best_move, Some(line[0]));
+        assert!(report.proven);
+        assert_eq!(report.iterations, 0);
+    }
+
+    #[test]
+    fn finds_a_block_that_makes_three() {
+        // o can only block e1 e2 _ e4 at e3, which completes e3 f3 g3.
+        let pos = parse_diagram(
+            "
+                a o . . . .
+               b . . . . . .
+              c . . . . . . .
+             d . . . . . . . .
+            e x x . . . . . . .
+             f . . o . . . . .
+              g . . o . . . .
+               h . . . . . .
+                i . . . . x
+            x to move
+            ",
+        )
+        .unwrap();
+        let line = find_forced_win(pos, 1).unwrap();
+        assert_eq!(names(pos, &line), "e4 e3");
+        assert_eq!(replay(pos, &line), Some(0));
+    }
+
+    #[test]
+    fn blocks_a_counter_threat_first() {
+        // e4 would be a double threat, but o threatens i4 and x has to block
+        // it; i4 is no threat, so there is no forcing line until it is made.
+        let pos = parse_diagram(
+            "
+                a o . . . .
+               b . . . . . .
+              c . x . . . . .
+             d . . . . . . . .
+            e x x . . . . . . .
+             f . . . x . . . .
+              g . . . . . . .
+               h . . . . . .
+                i . o o . o
+            x to move
+            ",
+        )
+        .unwrap();
+        assert_eq!(find_forced_win(pos, 3), None);
+        let board = pos.board();
+        let quiet = parse_moves(board, "i4 b6").unwrap();
+        let pos = quiet.iter().fold(pos, |pos, &mv| pos.apply(mv).unwrap());
+        let line = find_forced_win(pos, 3).unwrap();
+        assert_eq!(names(pos, &line[..1]), "e4");
+    }
+
+    #[test]
+    fn every_line_found_is_a_win() {
+        let mut found = 0;
+        for (pos, jp) in fixtures::random_games(Board::standard(), 3, 60, 12, 10) {
+            if !matches!(outcome(pos, jp), Outcome::Ongoing)
+                || !immediate_winning_moves(pos).is_empty()
+            {
+                continue;
+            }
+            let Some(line) = find_forced_win(pos, 5) else {
+                continue;
+            };
+            found += 1;
+            assert_eq!(replay(pos, &line), Some(pos.turn), "\n{pos}");
+            assert_ne!(solve(pos, 50_000).verdict, Verdict::Loss, "\n{pos}");
+        }
+        assert!(found > 0);
+    }
+}
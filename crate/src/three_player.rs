@@ -0,0 +1,593 @@
+//! Three-player Yavalath.
+//!
+//! Players move in the order 0, 1, 2. Four in a row wins outright. Three in a
+//! row (without four) eliminates the player who made it; their stones stay on
+//! the board and they are skipped from then on. The last player standing
+//! wins. A player must block the next player's immediate win when one exists,
+//! unless they can win on the spot themselves.
+
+use serde::Serialize;
+
+use crate::board::{bit, Bitboard, Board, MAX_CELLS};
+use crate::engine::{
+    has_line3_from_move, has_line4, has_line4_from_move, now_ms, splitmix64, Rng64,
+};
+use crate::error::MoveError;
+use crate::moveset::MoveSet;
+
+pub const PLAYERS: usize = 3;
+const ALL_ALIVE: u8 = 0b111;
+
+const fn zobrist_stone_keys() -> [[u64; MAX_CELLS]; PLAYERS] {
+    let mut keys = [[0_u64; MAX_CELLS]; PLAYERS];
+    let mut state = 0x7468_7265_6501_u64;
+    let mut player = 0;
+    while player < PLAYERS {
+        let mut idx = 0;
+        while idx < MAX_CELLS {
+            let (next_state, key) = splitmix64(state);
+            state = next_state;
+            keys[player][idx] = key;
+            idx += 1;
+        }
+        player += 1;
+    }
+    keys
+}
+
+const fn zobrist_player_keys(seed: u64) -> [u64; PLAYERS] {
+    let (state, a) = splitmix64(seed);
+    let (state, b) = splitmix64(state);
+    let (_, c) = splitmix64(state);
+    [a, b, c]
+}
+
+const ZOBRIST_STONES: [[u64; MAX_CELLS]; PLAYERS] = zobrist_stone_keys();
+const ZOBRIST_TURN: [u64; PLAYERS] = zobrist_player_keys(0x0074_7572_6e33);
+const ZOBRIST_OUT: [u64; PLAYERS] = zobrist_player_keys(0x6f75_7433);
+
+/// Serialises as `{"state", "winner", "eliminated", "line"}`, with `state`
+/// one of `ongoing`, `draw`, `win`, `eliminated` or `last_standing`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+#[serde(into = "ThreePlayerOutcomeResponse")]
+pub enum ThreePlayerOutcome {
+    Ongoing,
+    Draw,
+    Win(u8, [u8; 4]),
+    /// The player who just moved made three and is out; play continues.
+    Eliminated(u8, [u8; 3]),
+    /// Everyone else has been eliminated.
+    LastStanding(u8),
+}
+
+impl ThreePlayerOutcome {
+    pub fn is_over(self) -> bool {
+        matches!(self, Self::Draw | Self::Win(..) | Self::LastStanding(_))
+    }
+
+    pub fn winner(self) -> Option<u8> {
+        match self {
+            Self::Win(player, _) | Self::LastStanding(player) => Some(player),
+            _ => None,
+        }
+    }
+}
+
+/// The JSON shape of a `ThreePlayerOutcome`.
+#[derive(Serialize)]
+struct ThreePlayerOutcomeResponse {
+    state: &'static str,
+    winner: Option<u8>,
+    eliminated: Option<u8>,
+    line: Vec<u8>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ThreePlayerPosition {
+    pub stones: [Bitboard; PLAYERS],
+    pub turn: u8,
+    pub ply: u8,
+    /// One bit per player still in the game.
+    pub alive: u8,
+    /// Zobrist key over stones, side to move and eliminations.
+    pub hash: u64,
+    board: &'static Board,
+}
+
+impl ThreePlayerPosition {
+    /// An empty standard board.
+    pub fn empty() -> Self {
+        Self::empty_on(Board::standard())
+    }
+
+    pub fn empty_on(board: &'static Board) -> Self {
+        Self::from_stones_on(board, [0; PLAYERS], 0, ALL_ALIVE)
+    }
+
+    /// A position with the given stones; the ply is the number of stones.
+    pub fn from_stones_on(
+        board: &'static Board,
+        stones: [Bitboard; PLAYERS],
+        turn: u8,
+        alive: u8,
+    ) -> Self {
+        let mut pos = Self {
+            stones,
+            turn,
+            ply: stones.iter().map(|s| s.count_ones()).sum::<u32>() as u8,
+            alive: alive & ALL_ALIVE,
+            hash: 0,
+            board,
+        };
+        pos.hash = pos.zobrist();
+        pos
+    }
+
+    pub fn board(self) -> &'static Board {
+        self.board
+    }
+
+    /// Computes the Zobrist key from scratch.
+    pub fn zobrist(self) -> u64 {
+        let mut hash = ZOBRIST_TURN[self.turn as usize];
+        for player in 0..PLAYERS {
+            if !self.is_alive(player as u8) {
+                hash ^= ZOBRIST_OUT[player];
+            }
+            let mut rest = self.stones[player];
+            while rest != 0 {
+                let idx = rest.trailing_zeros() as usize;
+                hash ^= ZOBRIST_STONES[player][idx];
+                rest &= rest - 1;
+            }
+        }
+        hash
+    }
+
+    pub fn occupied(self) -> Bitboard {
+        self.stones.iter().fold(0, |acc, &s| acc | s)
+    }
+
+    pub fn is_alive(self, player: u8) -> bool {
+        self.alive & (1 << player) != 0
+    }
+
+    pub fn alive_count(self) -> usize {
+        self.alive.count_ones() as usize
+    }
+
+    /// The next player still in the game after `player`, wrapping around.
+    pub fn next_player(self, player: u8) -> u8 {
+        (1..=PLAYERS as u8)
+            .map(|k| (player + k) % PLAYERS as u8)
+            .find(|&p| self.is_alive(p))
+            .unwrap_or(player)
+    }
+
+    fn empty_cells(self) -> MoveSet {
+        MoveSet::from_cells(self.board.swap_move(), !self.occupied() & self.board.mask())
+    }
+
+    /// Empty cells where `player` would complete four.
+    pub fn winning_cells(self, player: u8) -> MoveSet {
+        let bits = self.stones[player as usize];
+        self.empty_cells()
+            .filter(|mv| has_line4_from_move(self.board, bits | bit(mv), mv).is_some())
+    }
+
+    /// Cells the side to move is obliged to block: the next player's
+    /// immediate wins.
+    pub fn forced_blocks(self) -> MoveSet {
+        let next = self.next_player(self.turn);
+        if next == self.turn {
+            return MoveSet::empty(self.board.swap_move());
+        }
+        self.winning_cells(next)
+    }
+
+    /// Empty cells, narrowed to blocks (plus the mover's own wins) when the
+    /// next player threatens to win. There is no swap move in this variant.
+    pub fn legal_moves(self) -> MoveSet {
+        let blocks = self.forced_blocks();
+        if blocks.is_empty() {
+            self.empty_cells()
+        } else {
+            blocks | self.winning_cells(self.turn)
+        }
+    }
+
+    /// Places a stone for the side to move, eliminating them if it makes
+    /// three without four, and passes the turn to the next player still in.
+    pub fn apply(self, mv: u8) -> Result<Self, MoveError> {
+        if !self.legal_moves().contains(mv) {
+            return Err(if mv as usize >= self.board.cell_count() {
+                MoveError::OffBoard(mv)
+            } else if self.occupied() & bit(mv) != 0 {
+                MoveError::Occupied(mv)
+            } else {
+                MoveError::Forbidden(mv)
+            });
+        }
+        let player = self.turn;
+        let mut next = self;
+        next.stones[player as usize] |= bit(mv);
+        let bits = next.stones[player as usize];
+        if has_line4_from_move(self.board, bits, mv).is_none()
+            && has_line3_from_move(self.board, bits, mv).is_some()
+        {
+            next.alive &= !(1 << player);
+        }
+        next.turn = next.next_player(player);
+        next.ply += 1;
+        next.hash = next.zobrist();
+        Ok(next)
+    }
+}
+
+pub fn outcome(pos: ThreePlayerPosition, just_played: Option<(u8, u8)>) -> ThreePlayerOutcome {
+    let board = pos.board;
+    let full = pos.ply as usize >= board.cell_count();
+    if let Some((player, last_move)) = just_played {
+        let bits = pos.stones[player as usize];
+        if let Some(line4) = has_line4_from_move(board, bits, last_move) {
+            return ThreePlayerOutcome::Win(player, line4);
+        }
+        if let Some(line3) = has_line3_from_move(board, bits, last_move) {
+            if pos.alive_count() == 1 {
+                return ThreePlayerOutcome::LastStanding(pos.turn);
+            }
+            if !full {
+                return ThreePlayerOutcome::Eliminated(player, line3);
+            }
+        }
+    } else {
+        for player in 0..PLAYERS as u8 {
+            if let Some(line4) = has_line4(board, pos.stones[player as usize]) {
+                return ThreePlayerOutcome::Win(player, line4);
+            }
+        }
+        if pos.alive_count() == 1 {
+            return ThreePlayerOutcome::LastStanding(pos.turn);
+        }
+    }
+    if full {
+        return ThreePlayerOutcome::Draw;
+    }
+    ThreePlayerOutcome::Ongoing
+}
+
+impl From<ThreePlayerOutcome> for ThreePlayerOutcomeResponse {
+    fn from(outcome: ThreePlayerOutcome) -> Self {
+        match outcome {
+            ThreePlayerOutcome::Ongoing => ThreePlayerOutcomeResponse {
+                state: "ongoing",
+                winner: None,
+                eliminated: None,
+                line: vec![],
+            },
+            ThreePlayerOutcome::Draw => ThreePlayerOutcomeResponse {
+                state: "draw",
+                winner: None,
+                eliminated: None,
+                line: vec![],
+            },
+            ThreePlayerOutcome::Win(player, line) => ThreePlayerOutcomeResponse {
+                state: "win",
+                winner: Some(player),
+                eliminated: None,
+                line: line.to_vec(),
+            },
+            ThreePlayerOutcome::Eliminated(player, line) => ThreePlayerOutcomeResponse {
+                state: "eliminated",
+                winner: None,
+                eliminated: Some(player),
+                line: line.to_vec(),
+            },
+            ThreePlayerOutcome::LastStanding(player) => ThreePlayerOutcomeResponse {
+                state: "last_standing",
+                winner: Some(player),
+                eliminated: None,
+                line: vec![],
+            },
+        }
+    }
+}
+
+/// How playout results are backed up through the tree.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Backup {
+    /// Every player maximises their own share of the result.
+    MaxN,
+    /// The searching player assumes the other two are allied against them.
+    Paranoid,
+}
+
+/// Each player's share of a finished (or abandoned) playout: 1 to a winner,
+/// otherwise split evenly between the players still in.
+fn rewards(pos: ThreePlayerPosition, result: ThreePlayerOutcome) -> [f64; PLAYERS] {
+    let mut shares = [0.0; PLAYERS];
+    if let Some(winner) = result.winner() {
+        shares[winner as usize] = 1.0;
+        return shares;
+    }
+    let alive = pos.alive_count().max(1) as f64;
+    for (player, share) in shares.iter_mut().enumerate() {
+        if pos.is_alive(player as u8) {
+            *share = 1.0 / alive;
+        }
+    }
+    shares
+}
+
+struct Node {
+    pos: ThreePlayerPosition,
+    just_played: Option<(u8, u8)>,
+    result: ThreePlayerOutcome,
+    edges: Vec<(u8, usize)>,
+    untried_moves: MoveSet,
+    visits: u32,
+    reward_sums: [f64; PLAYERS],
+}
+
+impl Node {
+    fn new(pos: ThreePlayerPosition, just_played: Option<(u8, u8)>) -> Self {
+        let result = outcome(pos, just_played);
+        let untried_moves = if result.is_over() {
+            MoveSet::empty(pos.board.swap_move())
+        } else {
+            pos.legal_moves()
+        };
+        Self {
+            pos,
+            just_played,
+            result,
+            edges: Vec::new(),
+            untried_moves,
+            visits: 0,
+            reward_sums: [0.0; PLAYERS],
+        }
+    }
+}
+
+fn select_child(nodes: &[Node], node_idx: usize, root_player: u8, backup: Backup) -> usize {
+    const EXPLORATION: f64 = 1.2;
+    let parent = &nodes[node_idx];
+    let mover = parent.pos.turn as usize;
+    let parent_visits = f64::from(parent.visits.max(1));
+    let mut best_child = parent.edges[0].1;
+    let mut best_score = f64::NEG_INFINITY;
+    for &(_, child_idx) in &parent.edges {
+        let child = &nodes[child_idx];
+        if child.visits == 0 {
+            return child_idx;
+        }
+        let visits = f64::from(child.visits);
+        let exploit = match backup {
+            Backup::MaxN => child.reward_sums[mover] / visits,
+            Backup::Paranoid => {
+                let root_share = child.reward_sums[root_player as usize] / visits;
+                if mover == root_player as usize {
+                    root_share
+                } else {
+                    1.0 - root_share
+                }
+            }
+        };
+        let score = exploit + EXPLORATION * (parent_visits.ln() / visits).sqrt();
+        if score > best_score {
+            best_score = score;
+            best_child = child_idx;
+        }
+    }
+    best_child
+}
+
+fn rollout_choice(pos: ThreePlayerPosition, rng: &mut Rng64) -> Option<u8> {
+    let legal = pos.legal_moves();
+    let wins = legal & pos.winning_cells(pos.turn);
+    if !wins.is_empty() {
+        return wins.first();
+    }
+    let bits = pos.stones[pos.turn as usize];
+    let safe = legal.filter(|mv| has_line3_from_move(pos.board, bits | bit(mv), mv).is_none());
+    rng.choose(safe).or_else(|| rng.choose(legal))
+}
+
+fn rollout(
+    mut pos: ThreePlayerPosition,
+    mut just_played: Option<(u8, u8)>,
+    rng: &mut Rng64,
+) -> [f64; PLAYERS] {
+    loop {
+        let result = outcome(pos, just_played);
+        if result.is_over() {
+            return rewards(pos, result);
+        }
+        let Some(mv) = rollout_choice(pos, rng) else {
+            return rewards(pos, ThreePlayerOutcome::Draw);
+        };
+        just_played = Some((pos.turn, mv));
+        pos = pos.apply(mv).expect("rollout move is legal");
+    }
+}
+
+/// Picks a move for the side to move with MCTS, backing results up with
+/// max-n or paranoid statistics.
+pub fn best_move(pos: ThreePlayerPosition, budget_ms: f64, backup: Backup) -> Option<u8> {
+    let legal = pos.legal_moves();
+    if legal.len() <= 1 {
+        return legal.first();
+    }
+    let wins = legal & pos.winning_cells(pos.turn);
+    if !wins.is_empty() {
+        return wins.first();
+    }
+
+    let root_player = pos.turn;
+    let deadline = now_ms() + budget_ms.max(10.0);
+    let max_iterations = ((budget_ms.max(10.0) * 4.0) as usize).clamp(120, 200_000);
+    let fold = |bits: Bitboard| bits as u64 ^ (bits >> 64) as u64;
+    let mut rng = Rng64::new(
+        pos.stones
+            .iter()
+            .enumerate()
+            .fold(u64::from(pos.ply), |acc, (p, &s)| {
+                acc ^ fold(s).rotate_left(7 * p as u32 + 3)
+            }),
+    );
+
+    let mut nodes = vec![Node::new(pos, None)];
+    let mut iterations = 0usize;
+    while iterations < max_iterations && now_ms() < deadline {
+        iterations += 1;
+        let mut node_idx = 0usize;
+        let mut path = vec![0usize];
+        loop {
+            let node = &mut nodes[node_idx];
+            if let Some(mv) = rng.choose(node.untried_moves) {
+                node.untried_moves.remove(mv);
+                let mover = node.pos.turn;
+                let next = node.pos.apply(mv).expect("untried move is legal");
+                let child_idx = nodes.len();
+                nodes.push(Node::new(next, Some((mover, mv))));
+                nodes[node_idx].edges.push((mv, child_idx));
+                path.push(child_idx);
+                node_idx = child_idx;
+                break;
+            } else if !node.edges.is_empty() {
+                node_idx = select_child(&nodes, node_idx, root_player, backup);
+                path.push(node_idx);
+            } else {
+                break;
+            }
+        }
+
+        let leaf = &nodes[node_idx];
+        let shares = if leaf.result.is_over() {
+            rewards(leaf.pos, leaf.result)
+        } else {
+            rollout(leaf.pos, leaf.just_played, &mut rng)
+        };
+        for idx in path {
+            let node = &mut nodes[idx];
+            node.visits += 1;
+            for (sum, share) in node.reward_sums.iter_mut().zip(shares) {
+                *sum += share;
+            }
+        }
+    }
+
+    nodes[0]
+        .edges
+        .iter()
+        .max_by_key(|&&(_, child)| nodes[child].visits)
+        .map(|&(mv, _)| mv)
+        .or_else(|| legal.first())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn cells(idxs: &[u8]) -> Bitboard {
+        idxs.iter().fold(0, |acc, &idx| acc | bit(idx))
+    }
+
+    #[test]
+    fn turns_rotate_and_hash_is_consistent() {
+        let mut pos = ThreePlayerPosition::empty();
+        for (expected_turn, mv) in [(0, 30), (1, 0), (2, 60), (0, 10)] {
+            assert_eq!(pos.turn, expected_turn);
+            pos = pos.apply(mv).unwrap();
+            assert_eq!(pos.hash, pos.zobrist());
+        }
+        assert_eq!(pos.turn, 1);
+        assert_eq!(pos.stones[2], bit(60));
+        assert_eq!(pos.apply(30), Err(MoveError::Occupied(30)));
+    }
+
+    #[test]
+    fn must_block_the_next_players_win() {
+        // Player 1 wins at 43 (26-35-43-50), so player 0 has to take it.
+        let pos = ThreePlayerPosition::from_stones_on(
+            Board::standard(),
+            [
+                cells(&[0, 30, 60]),
+                cells(&[26, 35, 50]),
+                cells(&[5, 15, 55]),
+            ],
+            0,
+            ALL_ALIVE,
+        );
+        assert_eq!(pos.forced_blocks().to_vec(), vec![43]);
+        assert_eq!(pos.legal_moves().to_vec(), vec![43]);
+        assert_eq!(pos.apply(31), Err(MoveError::Forbidden(31)));
+        for backup in [Backup::MaxN, Backup::Paranoid] {
+            assert_eq!(best_move(pos, 20.0, backup), Some(43));
+        }
+    }
+
+    #[test]
+    fn making_three_eliminates_and_skips_the_player() {
+        let pos = ThreePlayerPosition::from_stones_on(
+            Board::standard(),
+            [cells(&[26, 35]), cells(&[0, 60]), cells(&[5, 55])],
+            0,
+            ALL_ALIVE,
+        );
+        let next = pos.apply(43).unwrap();
+        assert!(matches!(
+            outcome(next, Some((0, 43))),
+            ThreePlayerOutcome::Eliminated(0, _)
+        ));
+        assert!(!next.is_alive(0));
+        assert_eq!(next.turn, 1);
+        let next = next.apply(10).unwrap();
+        assert_eq!(next.turn, 2);
+        let next = next.apply(20).unwrap();
+        assert_eq!(next.turn, 1);
+        assert!(next.stones[0] & bit(43) != 0);
+    }
+
+    #[test]
+    fn last_player_standing_wins() {
+        let pos = ThreePlayerPosition::from_stones_on(
+            Board::standard(),
+            [cells(&[0, 60]), cells(&[26, 35]), cells(&[5, 55])],
+            1,
+            0b011,
+        );
+        let next = pos.apply(43).unwrap();
+        assert_eq!(
+            outcome(next, Some((1, 43))),
+            ThreePlayerOutcome::LastStanding(0)
+        );
+        assert_eq!(outcome(next, None), ThreePlayerOutcome::LastStanding(0));
+    }
+
+    #[test]
+    fn four_in_a_row_wins_outright() {
+        let pos = ThreePlayerPosition::from_stones_on(
+            Board::standard(),
+            [cells(&[26, 35, 50]), cells(&[0, 60]), cells(&[5, 55])],
+            0,
+            ALL_ALIVE,
+        );
+        let next = pos.apply(43).unwrap();
+        assert!(matches!(
+            outcome(next, Some((0, 43))),
+            ThreePlayerOutcome::Win(0, _)
+        ));
+        assert!(matches!(outcome(next, None), ThreePlayerOutcome::Win(0, _)));
+        assert_eq!(best_move(pos, 20.0, Backup::MaxN), Some(43));
+    }
+
+    #[test]
+    fn encodes_eliminations() {
+        let json = serde_json::to_string(&ThreePlayerOutcome::Eliminated(2, [0, 1, 2])).unwrap();
+        assert_eq!(
+            json,
+            r#"{"state":"eliminated","winner":null,"eliminated":2,"line":[0,1,2]}"#
+        );
+    }
+}
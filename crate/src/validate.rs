@@ -0,0 +1,158 @@
+//! Reachability checks for positions built by hand or imported from
+//! elsewhere.
+//!
+//! Parsing only checks that a board is well-formed. `validate` also checks
+//! that it could have come from a game played under the position's rules:
+//! the stone counts fit the side to move (allowing for a swap), the opening
+//! restriction could have been met, and at most one player has a terminal
+//! line — the player who just moved, with every line through their last
+//! stone. The forced-block rule is not replayed, since checking it needs
+//! the move order.
+
+use crate::board::bit;
+use crate::engine::{has_line3, Position};
+use crate::error::InvalidPosition;
+
+pub fn validate(pos: Position) -> Result<(), InvalidPosition> {
+    let board = pos.board();
+    let rules = pos.rules();
+    if pos.p0 & pos.p1 != 0 {
+        return Err(InvalidPosition::Overlap);
+    }
+    if pos.occupied() & !board.mask() != 0 {
+        return Err(InvalidPosition::OffBoard);
+    }
+
+    let (p0, p1) = (pos.p0.count_ones(), pos.p1.count_ones());
+    let unswapped = if pos.turn == 0 {
+        p0 == p1
+    } else {
+        p0 == p1 + 1
+    };
+    let swapped = pos.swap_used();
+    if (!unswapped && !swapped) || u32::from(pos.ply) != p0 + p1 || pos.turn > 1 {
+        return Err(InvalidPosition::StoneCount { p0, p1 });
+    }
+    if swapped && !rules.swap {
+        return Err(InvalidPosition::SwapNotAllowed);
+    }
+
+    if rules.first_move_min_distance > 0 {
+        let opener = if swapped { pos.p1 } else { pos.p0 };
+        let forbidden = board.cells_within(rules.first_move_min_distance - 1);
+        if opener != 0 && opener & !forbidden == 0 {
+            return Err(InvalidPosition::OpeningRestricted);
+        }
+    }
+
+    // A three covers fours too: any four contains one.
+    let terminal = |bits| has_line3(board, bits).is_some();
+    match (terminal(pos.p0), terminal(pos.p1)) {
+        (true, true) => return Err(InvalidPosition::BothPlayersTerminal),
+        (false, false) => return Ok(()),
+        _ => {}
+    }
+    let player = if terminal(pos.p0) { 0 } else { 1 };
+    if player == pos.turn {
+        return Err(InvalidPosition::WrongMover { player });
+    }
+    let stones = pos.stones(player);
+    let last_stone_explains_all = (0..board.cell_count() as u8)
+        .filter(|&idx| stones & bit(idx) != 0)
+        .any(|idx| !terminal(stones & !bit(idx)));
+    if !last_stone_explains_all {
+        return Err(InvalidPosition::PlayContinued { player });
+    }
+    Ok(())
+}
+
+impl Position {
+    /// Whether this position could arise in a game under its rules; see
+    /// `validate` for the reason when it could not.
+    pub fn is_reachable(self) -> bool {
+        validate(self).is_ok()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::board::Bitboard;
+    use crate::engine::{apply_move_with_meta, legal_moves_with_swap, outcome, Outcome, Rng64};
+    use crate::rules::Rules;
+
+    fn cells(idxs: &[u8]) -> Bitboard {
+        idxs.iter().fold(0, |acc, &idx| acc | bit(idx))
+    }
+
+    #[test]
+    fn positions_from_real_games_are_reachable() {
+        let mut rng = Rng64::new(0x9e37_79b9);
+        for _ in 0..200 {
+            let mut pos = Position::empty();
+            let mut jp = None;
+            loop {
+                assert_eq!(validate(pos), Ok(()), "{pos:?}");
+                if !matches!(outcome(pos, jp), Outcome::Ongoing) {
+                    break;
+                }
+                let mv = rng.choose(legal_moves_with_swap(pos)).unwrap();
+                (pos, jp) = apply_move_with_meta(pos, mv).unwrap();
+            }
+        }
+    }
+
+    #[test]
+    fn rejects_lines_for_both_players_or_the_wrong_mover() {
+        // 0-1-2 and 5-6-7 are threes for each player.
+        let both = Position::from_bitboards(cells(&[0, 1, 2, 30]), cells(&[5, 6, 7]), 1, 7);
+        assert_eq!(validate(both), Err(InvalidPosition::BothPlayersTerminal));
+        let wrong = Position::from_bitboards(cells(&[0, 1, 2]), cells(&[30, 40, 50]), 0, 6);
+        assert_eq!(
+            validate(wrong),
+            Err(InvalidPosition::WrongMover { player: 0 })
+        );
+        let fine = Position::from_bitboards(cells(&[0, 1, 2]), cells(&[30, 40]), 1, 5);
+        assert!(fine.is_reachable());
+    }
+
+    #[test]
+    fn rejects_play_after_the_game_ended() {
+        // Two separate threes cannot both have been made by one last stone.
+        let pos = Position::from_bitboards(
+            cells(&[0, 1, 2, 58, 59, 60]),
+            cells(&[20, 30, 40, 50, 25]),
+            1,
+            11,
+        );
+        assert_eq!(
+            validate(pos),
+            Err(InvalidPosition::PlayContinued { player: 0 })
+        );
+    }
+
+    #[test]
+    fn rejects_counts_swaps_and_openings_the_rules_forbid() {
+        let counts = Position::from_bitboards(cells(&[0, 10]), 0, 1, 2);
+        assert_eq!(
+            validate(counts),
+            Err(InvalidPosition::StoneCount { p0: 2, p1: 0 })
+        );
+        let swapped = Position::from_bitboards(0, cells(&[30]), 0, 1);
+        assert!(swapped.is_reachable());
+        let no_swap = Rules {
+            swap: false,
+            ..Rules::STANDARD
+        };
+        assert_eq!(
+            validate(swapped.with_rules(no_swap)),
+            Err(InvalidPosition::SwapNotAllowed)
+        );
+        let no_centre = Rules {
+            first_move_min_distance: 1,
+            ..Rules::STANDARD
+        };
+        let centre = Position::from_bitboards(cells(&[30]), 0, 1, 1).with_rules(no_centre);
+        assert_eq!(validate(centre), Err(InvalidPosition::OpeningRestricted));
+    }
+}
@@ -7,119 +7,12 @@ import os
 import random
 import subprocess
 import sys
-from dataclasses import dataclass
 from pathlib import Path
 from typing import Dict, List, Optional, Tuple
 
 ROOT = Path(__file__).resolve().parents[1]
 WORKTREE_ROOT = ROOT / ".arena" / "worktrees"
 
-BOARD_RADIUS = 4
-BOARD_CELLS = 61
-SWAP_MOVE = BOARD_CELLS
-LINE_DIRS = [(1, 0), (0, 1), (1, -1)]
-
-
-def axial_cells() -> List[Tuple[int, int]]:
-    cells = []
-    for q in range(-BOARD_RADIUS, BOARD_RADIUS + 1):
-        r_min = max(-BOARD_RADIUS, -q - BOARD_RADIUS)
-        r_max = min(BOARD_RADIUS, -q + BOARD_RADIUS)
-        for r in range(r_min, r_max + 1):
-            cells.append((q, r))
-    return cells
-
-
-CELLS = axial_cells()
-CELL_TO_IDX = {c: i for i, c in enumerate(CELLS)}
-
-
-def all_lines(k: int) -> List[Tuple[int, ...]]:
-    lines = set()
-    for q, r in CELLS:
-        for dq, dr in LINE_DIRS:
-            run = []
-            for step in range(k):
-                nxt = (q + dq * step, r + dr * step)
-                if nxt not in CELL_TO_IDX:
-                    run = []
-                    break
-                run.append(CELL_TO_IDX[nxt])
-            if len(run) == k:
-                lines.add(tuple(run))
-    return sorted(lines)
-
-
-THREE_LINES = all_lines(3)
-FOUR_LINES = all_lines(4)
-
-
-@dataclass
-class Position:
-    p0: int = 0
-    p1: int = 0
-    turn: int = 0
-    ply: int = 0
-
-    def occupied(self) -> int:
-        return self.p0 | self.p1
-
-    def can_swap(self) -> bool:
-        return self.ply == 1 and self.turn == 1
-
-    def legal(self) -> List[int]:
-        moves = [i for i in range(BOARD_CELLS) if ((self.occupied() >> i) & 1) == 0]
-        if self.can_swap():
-            moves.append(SWAP_MOVE)
-        return moves
-
-    def apply(self, mv: int) -> Optional[Tuple["Position", Optional[Tuple[int, int]]]]:
-        if mv == SWAP_MOVE:
-            if not self.can_swap():
-                return None
-            return Position(self.p1, self.p0, self.turn ^ 1, self.ply), None
-        if mv < 0 or mv >= BOARD_CELLS:
-            return None
-        if (self.occupied() >> mv) & 1:
-            return None
-        nxt = Position(self.p0, self.p1, self.turn ^ 1, self.ply + 1)
-        if self.turn == 0:
-            nxt.p0 |= 1 << mv
-        else:
-            nxt.p1 |= 1 << mv
-        return nxt, (self.turn, mv)
-
-
-def has_line(bits: int, lines: List[Tuple[int, ...]]) -> Optional[Tuple[int, ...]]:
-    for line in lines:
-        if all(((bits >> i) & 1) for i in line):
-            return line
-    return None
-
-
-def outcome(pos: Position, just_played: Optional[Tuple[int, int]]) -> Tuple[str, Optional[int]]:
-    if just_played is not None:
-        player, _ = just_played
-        bits = pos.p0 if player == 0 else pos.p1
-        if has_line(bits, FOUR_LINES):
-            return "win", player
-        if has_line(bits, THREE_LINES):
-            return "lose", player
-    else:
-        for player, bits in ((0, pos.p0), (1, pos.p1)):
-            if has_line(bits, FOUR_LINES):
-                return "win", player
-            if has_line(bits, THREE_LINES):
-                return "lose", player
-    if pos.ply >= BOARD_CELLS:
-        return "draw", None
-    return "ongoing", None
-
-
-def encode_board_hex(pos: Position) -> str:
-    return f"{((pos.p0 << 64) | pos.p1):032x}"
-
-
 class AgentProc:
     def __init__(self, cmd: List[str]):
         self.proc = subprocess.Popen(
@@ -131,11 +24,18 @@ class AgentProc:
             bufsize=1,
         )
 
-    def pick(self, board_hex: str) -> int:
+    def ask(self, line: str) -> str:
         assert self.proc.stdin and self.proc.stdout
-        self.proc.stdin.write(board_hex + "\n")
+        self.proc.stdin.write(line + "\n")
         self.proc.stdin.flush()
-        out = self.proc.stdout.readline().strip()
+        return self.proc.stdout.readline().strip()
+
+    def pick(self, request: str) -> int:
+        out = self.ask(request)
+        # Newer agents explain refusals as "error <reason>"; older ones print -1.
+        if out.startswith("error"):
+            print(f"agent refused {request}: {out[len('error'):].strip()}", file=sys.stderr)
+            return -1
         return int(out)
 
     def close(self):
@@ -147,6 +47,26 @@ class AgentProc:
                 self.proc.kill()
 
 
+class Referee(AgentProc):
+    """The current `arena_agent` answering `result` requests, so games are
+    judged by the engine's own rules rather than a copy of them."""
+
+    def __init__(self):
+        super().__init__([str(ensure_local_binary()), "--strategy", "sequence"])
+
+    def result(self, moves: List[int]) -> Optional[Dict]:
+        """The board hex and outcome after `moves`, or None if the last move
+        was not allowed."""
+        out = self.ask(moves_request(moves, "result moves"))
+        if out.startswith("error"):
+            return None
+        return json.loads(out)
+
+
+def moves_request(moves: List[int], prefix: str = "moves") -> str:
+    return " ".join([prefix, *map(str, moves)])
+
+
 def ensure_worktree(commit: str) -> Path:
     WORKTREE_ROOT.mkdir(parents=True, exist_ok=True)
     short = subprocess.check_output(["git", "rev-parse", "--short", commit], cwd=ROOT, text=True).strip()
@@ -181,34 +101,45 @@ def command_for_player(player: Dict, game_seed: int) -> List[str]:
     binary = ensure_agent_binary(commit)
     strategy = player["strategy"]
     cmd = [str(binary), "--strategy", strategy, "--seed", str(game_seed)]
-    if strategy == "strength":
+    if strategy in ("strength", "mcts", "alpha-beta", "pns"):
         cmd += ["--strength", str(player.get("strength", 2)), "--time-ms", str(player.get("time_ms", 25.0))]
+    if "nodes" in player:
+        cmd += ["--nodes", str(player["nodes"])]
+    if player.get("book") is False:
+        cmd += ["--no-book"]
+    if player.get("swap_table") is False:
+        cmd += ["--no-swap-table"]
     return cmd
 
 
-def play_game(p0: Dict, p1: Dict, seed: int) -> float:
+def play_game(p0: Dict, p1: Dict, seed: int, referee: Referee) -> float:
     agents = [AgentProc(command_for_player(p0, seed * 2 + 1)), AgentProc(command_for_player(p1, seed * 2 + 2))]
-    pos = Position()
-    jp = None
-    winner = None
+    # Agents built before `moves` requests existed only understand board hex.
+    send_moves = [player.get("protocol") == "moves" for player in (p0, p1)]
+    moves: List[int] = []
     try:
         while True:
-            state, player = outcome(pos, jp)
-            if state == "win":
-                winner = player
+            status = referee.result(moves)
+            if status is None:
+                # Every move, the swap included, passes the turn, so the
+                # illegal move was made by the side that moved last.
+                winner = len(moves) % 2
+                break
+            outcome = status["outcome"]
+            if outcome["state"] == "win":
+                winner = outcome["winner"]
                 break
-            if state == "lose":
-                winner = player ^ 1
+            if outcome["state"] == "lose":
+                winner = outcome["loser"] ^ 1
                 break
-            if state == "draw":
+            if outcome["state"] == "draw":
                 return 0.5
+            if outcome["state"] != "ongoing":
+                raise RuntimeError(f"referee rejected the game {moves}: {outcome}")
 
-            mv = agents[pos.turn].pick(encode_board_hex(pos))
-            applied = pos.apply(mv)
-            if applied is None:
-                winner = pos.turn ^ 1
-                break
-            pos, jp = applied
+            turn = len(moves) % 2
+            request = moves_request(moves) if send_moves[turn] else status["board_hex"]
+            moves.append(agents[turn].pick(request))
 
         return 1.0 if winner == 0 else 0.0
     finally:
@@ -263,17 +194,21 @@ def run_tournament(config: Dict):
     seed = config.get("seed", 1)
     random.seed(seed)
 
-    for a, b in itertools.combinations(players, 2):
-        ia, ib = idx[a["name"]], idx[b["name"]]
-        for g in range(rounds):
-            s = seed + g + ia * 10_000 + ib * 100
-            first_a = (g % 2 == 0)
-            if first_a:
-                score = play_game(a, b, s)
-                games.append((ia, ib, score))
-            else:
-                score_b = play_game(b, a, s)
-                games.append((ia, ib, 1.0 - score_b))
+    referee = Referee()
+    try:
+        for a, b in itertools.combinations(players, 2):
+            ia, ib = idx[a["name"]], idx[b["name"]]
+            for g in range(rounds):
+                s = seed + g + ia * 10_000 + ib * 100
+                first_a = (g % 2 == 0)
+                if first_a:
+                    score = play_game(a, b, s, referee)
+                    games.append((ia, ib, score))
+                else:
+                    score_b = play_game(b, a, s, referee)
+                    games.append((ia, ib, 1.0 - score_b))
+    finally:
+        referee.close()
 
     ratings = fit_elo(names, games)
     ci = bootstrap_ci(names, games, config.get("bootstrap_samples", 200), seed + 999)
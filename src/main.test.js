@@ -1,10 +1,82 @@
 /** @vitest-environment jsdom */
 import { describe, it, expect, beforeEach, vi } from 'vitest';
 
+// A stand-in for the wasm `Game`: stones and turns only, no lines.
 vi.mock('../src/wasm/yavalath_engine.js', () => ({
   default: vi.fn(async () => ({})),
-  check_game_outcome: vi.fn(() => JSON.stringify({ state: 'ongoing', line: [] })),
-  pick_move_with_strength: vi.fn(() => 1),
+  Game: class {
+    constructor() {
+      this.stones = [[], []];
+      this.history = [];
+      this.future = [];
+    }
+
+    play(mv) {
+      if (this.stones.flat().includes(mv)) throw new Error('cell is occupied');
+      this.stones[this.turn()].push(mv);
+      this.history.push(mv);
+      this.future = [];
+    }
+
+    undo() {
+      const mv = this.history.pop();
+      if (mv === undefined) return -1;
+      this.stones[this.turn()].pop();
+      this.future.push(mv);
+      return mv;
+    }
+
+    redo() {
+      const mv = this.future.pop();
+      if (mv === undefined) return -1;
+      this.stones[this.turn()].push(mv);
+      this.history.push(mv);
+      return mv;
+    }
+
+    turn() {
+      return this.history.length % 2;
+    }
+
+    ply() {
+      return this.history.length;
+    }
+
+    can_undo() {
+      return this.history.length > 0;
+    }
+
+    can_redo() {
+      return this.future.length > 0;
+    }
+
+    can_swap() {
+      return false;
+    }
+
+    swap_move() {
+      return 61;
+    }
+
+    svg() {
+      const cells = Array.from({ length: 61 }, (_, idx) => {
+        const player = this.stones.findIndex((stones) => stones.includes(idx));
+        const stone = player < 0 ? '' : ` p${player}`;
+        return `<polygon class="cell${stone}" data-idx="${idx}"/>`;
+      });
+      return `<svg xmlns="http://www.w3.org/2000/svg">${cells.join('')}</svg>`;
+    }
+
+    outcome() {
+      return JSON.stringify({ state: 'ongoing', line: [] });
+    }
+
+    pick_move() {
+      return 1;
+    }
+
+    free() {}
+  },
 }));
 
 describe('AI turn flow', () => {
@@ -12,8 +84,9 @@ describe('AI turn flow', () => {
     document.body.innerHTML = `
       <main class="app">
         <div id="status"></div>
-        <svg id="board" viewBox="0 0 900 780"></svg>
+        <div id="board"></div>
         <button id="undo-move"></button>
+        <button id="redo-move"></button>
         <button id="swap-move"></button>
         <button id="new-game"></button>
         <button id="ai-move"></button>
@@ -26,12 +99,18 @@ describe('AI turn flow', () => {
   it('makes an AI move after the player clicks a cell', async () => {
     await import('../src/main.js');
 
-    const humanCell = document.querySelector('polygon[data-idx="0"]');
-    const aiCell = document.querySelector('polygon[data-idx="1"]');
+    // The board is redrawn after every move, so cells are looked up afresh.
+    const cell = (idx) => document.querySelector(`polygon[data-idx="${idx}"]`);
 
-    humanCell.dispatchEvent(new MouseEvent('click', { bubbles: true }));
+    cell(0).dispatchEvent(new MouseEvent('click', { bubbles: true }));
     await new Promise((resolve) => setTimeout(resolve, 30));
 
-    expect(aiCell.classList.contains('p1')).toBe(true);
+    expect(cell(1).classList.contains('p1')).toBe(true);
+
+    document.getElementById('undo-move').click();
+    expect(cell(1).classList.contains('p1')).toBe(false);
+    expect(cell(0).classList.contains('p0')).toBe(true);
+    document.getElementById('redo-move').click();
+    expect(cell(1).classList.contains('p1')).toBe(true);
   });
 });